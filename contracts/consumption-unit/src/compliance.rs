@@ -0,0 +1,309 @@
+use crate::error::ContractError;
+use crate::msg::{AllowlistResponse, IsAllowedResponse, RegistryQueryMsg};
+use crate::state::{ALLOWLIST, COMPLIANCE_CONFIG};
+use crate::types::ComplianceConfig;
+use cosmwasm_std::{
+    Addr, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, Response, StdResult,
+};
+use cw_storage_plus::Bound;
+use q_nft::execute::assert_creator;
+use q_nft::query::{DEFAULT_LIMIT, MAX_LIMIT};
+
+/// Whether `address` may hold CUs, any address may while compliance is not configured
+pub fn is_allowed(deps: Deps, address: &Addr) -> StdResult<bool> {
+    match COMPLIANCE_CONFIG.may_load(deps.storage)? {
+        None => Ok(true),
+        Some(ComplianceConfig::Local {}) => Ok(ALLOWLIST.has(deps.storage, address)),
+        Some(ComplianceConfig::Registry { contract }) => {
+            let response: IsAllowedResponse = deps.querier.query_wasm_smart(
+                contract,
+                &RegistryQueryMsg::IsAllowed {
+                    address: address.to_string(),
+                },
+            )?;
+            Ok(response.allowed)
+        }
+    }
+}
+
+/// Fails if `recipient` may not hold CUs, called on mints and through `assert_can_receive`
+/// on transfers
+pub fn assert_allowed(deps: Deps, recipient: &Addr) -> Result<(), ContractError> {
+    if !is_allowed(deps, recipient)? {
+        return Err(ContractError::NotAllowed {
+            address: recipient.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Fails if a CU of `holder` may not be moved to `recipient`, CUs can always return to their
+/// holder, e.g. an escrow contract returning a CU to its depositor (see `escrow::holder`)
+pub fn assert_can_receive(
+    deps: Deps,
+    holder: &Addr,
+    recipient: &Addr,
+) -> Result<(), ContractError> {
+    if recipient == holder {
+        return Ok(());
+    }
+    assert_allowed(deps, recipient)
+}
+
+pub fn execute_set_compliance_config(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    config: Option<ComplianceConfig>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let mut event = Event::new("consumption-unit::set_compliance_config");
+    match config {
+        Some(ComplianceConfig::Registry { contract }) => {
+            let contract = deps.api.addr_validate(contract.as_str())?;
+            event = event
+                .add_attribute("mode", "registry")
+                .add_attribute("registry", contract.to_string());
+            COMPLIANCE_CONFIG.save(deps.storage, &ComplianceConfig::Registry { contract })?;
+        }
+        Some(ComplianceConfig::Local {}) => {
+            event = event.add_attribute("mode", "local");
+            COMPLIANCE_CONFIG.save(deps.storage, &ComplianceConfig::Local {})?;
+        }
+        None => COMPLIANCE_CONFIG.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::set_compliance_config")
+        .add_event(event))
+}
+
+/// Adds and removes addresses of the local allowlist, removals are applied last
+pub fn execute_update_allowlist(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    for address in &add {
+        let address = deps.api.addr_validate(address)?;
+        ALLOWLIST.save(deps.storage, &address, &Empty {})?;
+    }
+    for address in &remove {
+        let address = deps.api.addr_validate(address)?;
+        ALLOWLIST.remove(deps.storage, &address);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::update_allowlist")
+        .add_event(
+            Event::new("consumption-unit::update_allowlist")
+                .add_attribute("added", add.join(","))
+                .add_attribute("removed", remove.join(",")),
+        ))
+}
+
+pub fn query_compliance_config(deps: Deps) -> StdResult<Option<ComplianceConfig>> {
+    COMPLIANCE_CONFIG.may_load(deps.storage)
+}
+
+pub fn query_is_allowed(deps: Deps, address: String) -> StdResult<IsAllowedResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(IsAllowedResponse {
+        allowed: is_allowed(deps, &address)?,
+    })
+}
+
+/// Returns the local allowlist ordered by address
+pub fn query_allowlist(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllowlistResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+
+    let addresses = ALLOWLIST
+        .keys(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AllowlistResponse { addresses })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::ExecuteMsg;
+    use crate::testing::{cu_data, mint, setup};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{from_json, to_json_binary, ContractResult, SystemResult, WasmQuery};
+    use cw20::Denom;
+
+    fn exec(deps: DepsMut, sender: &Addr, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), message_info(sender, &[]), msg)
+    }
+
+    #[test]
+    fn test_local_allowlist() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let alice = deps.api.addr_make("alice");
+        let bob = deps.api.addr_make("bob");
+        let auction = deps.api.addr_make("auction");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::SetComplianceConfig {
+                config: Some(ComplianceConfig::Local {}),
+            },
+        )
+        .unwrap();
+
+        let err = mint(deps.as_mut(), &creator, "1", &alice, cu_data(100, 1)).unwrap_err();
+        assert!(matches!(err, ContractError::NotAllowed { address } if address == alice.as_str()));
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::UpdateAllowlist {
+                add: vec![alice.to_string(), bob.to_string()],
+                remove: vec![],
+            },
+        )
+        .unwrap();
+        mint(deps.as_mut(), &creator, "1", &alice, cu_data(100, 1)).unwrap();
+        assert_eq!(
+            query_allowlist(deps.as_ref(), None, None)
+                .unwrap()
+                .addresses
+                .len(),
+            2
+        );
+
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::UpdateAllowlist {
+                add: vec![],
+                remove: vec![bob.to_string()],
+            },
+        )
+        .unwrap();
+        let transfer_msg = ExecuteMsg::TransferNft {
+            recipient: bob.to_string(),
+            token_id: "1".to_string(),
+        };
+        let err = exec(deps.as_mut(), &alice, transfer_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotAllowed { .. }));
+
+        // an escrow contract can return a CU to its depositor once no longer allowed
+        for msg in [
+            ExecuteMsg::AddEscrowContract {
+                contract: auction.to_string(),
+            },
+            ExecuteMsg::UpdateAllowlist {
+                add: vec![auction.to_string()],
+                remove: vec![],
+            },
+        ] {
+            exec(deps.as_mut(), &creator, msg).unwrap();
+        }
+        exec(
+            deps.as_mut(),
+            &alice,
+            ExecuteMsg::SendNft {
+                contract: auction.to_string(),
+                token_id: "1".to_string(),
+                msg: to_json_binary("auction").unwrap(),
+            },
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::UpdateAllowlist {
+                add: vec![],
+                remove: vec![alice.to_string()],
+            },
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            &auction,
+            ExecuteMsg::TransferNft {
+                recipient: alice.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+
+        // without compliance any address may hold CUs
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::SetComplianceConfig { config: None },
+        )
+        .unwrap();
+        exec(deps.as_mut(), &alice, transfer_msg).unwrap();
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let registry = deps.api.addr_make("registry");
+        let alice = deps.api.addr_make("alice");
+        let bob = deps.api.addr_make("bob");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+
+        let verified = alice.to_string();
+        deps.querier.update_wasm(move |query| {
+            let WasmQuery::Smart { msg, .. } = query else {
+                panic!("unexpected query {query:?}");
+            };
+            let RegistryQueryMsg::IsAllowed { address } = from_json(msg).unwrap();
+            let response = to_json_binary(&IsAllowedResponse {
+                allowed: address == verified,
+            });
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        });
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::SetComplianceConfig {
+                config: Some(ComplianceConfig::Registry { contract: registry }),
+            },
+        )
+        .unwrap();
+
+        mint(deps.as_mut(), &creator, "1", &alice, cu_data(100, 1)).unwrap();
+        let err = mint(deps.as_mut(), &creator, "2", &bob, cu_data(100, 1)).unwrap_err();
+        assert!(matches!(err, ContractError::NotAllowed { .. }));
+        assert!(
+            !query_is_allowed(deps.as_ref(), bob.to_string())
+                .unwrap()
+                .allowed
+        );
+    }
+}
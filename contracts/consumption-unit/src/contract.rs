@@ -1,13 +1,24 @@
 use crate::error::ContractError;
 use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg, InstantiateMsg, MigrateMsg};
-use crate::types::{CUConfig, ConsumptionUnitData, ConsumptionUnitNft, ConsumptionUnitState};
+use crate::types::{
+    CUConfig, ConsumptionUnitData, ConsumptionUnitNft, ConsumptionUnitState, HistoryAction,
+};
+use crate::{
+    compliance, dispute, escrow, expiry, fee, freeze, history, migration, oracle, penalty, pool,
+    reporter, reserve, rewards, round, settlement, tier, voting,
+};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, Response};
+use cosmwasm_std::{
+    Addr, Api, Binary, DepsMut, Env, Event, MessageInfo, Response, StdResult, Storage, Uint128,
+};
+use cw20::Denom;
 use cw_ownable::OwnershipError;
 use q_nft::error::Cw721ContractError;
-use q_nft::execute::assert_minter;
-use q_nft::state::{CollectionInfo, Cw721Config};
+use q_nft::execute::{assert_creator, assert_minter, snapshot_ownership, transfer_nft};
+use q_nft::msg::Cw721ReceiveMsg;
+use q_nft::state::{CollectionInfo, Cw721Config, CREATOR};
+use q_utils::asset::denom_to_string;
 
 const CONTRACT_NAME: &str = "gemlabs.io:consumption-unit";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -24,8 +35,15 @@ pub fn instantiate(
     let cfg = CUConfig {
         settlement_token: msg.collection_info_extension.settlement_token.clone(),
         native_token: msg.collection_info_extension.native_token.clone(),
-        price_oracle: msg.collection_info_extension.price_oracle.clone(),
+        price_oracles: msg
+            .collection_info_extension
+            .price_oracles
+            .iter()
+            .map(|oracle| deps.api.addr_validate(oracle.as_str()))
+            .collect::<StdResult<_>>()?,
+        oracle_quorum: msg.collection_info_extension.oracle_quorum,
     };
+    validate_oracle_quorum(&cfg)?;
 
     let collection_info = CollectionInfo {
         name: msg.name,
@@ -53,13 +71,15 @@ pub fn instantiate(
         None => info.sender.as_str(),
     };
     q_nft::execute::initialize_creator(deps.storage, deps.api, Some(creator))?;
+    q_nft::execute::initialize_snapshots(deps.storage, msg.snapshots)?;
 
     Ok(Response::default()
         .add_attribute("action", "consumption-unit::instantiate")
         .add_event(
             Event::new("consumption-unit::instantiate")
                 .add_attribute("minter", minter)
-                .add_attribute("creator", creator),
+                .add_attribute("creator", creator)
+                .add_attribute("snapshots", msg.snapshots.to_string()),
         ))
 }
 
@@ -77,10 +97,216 @@ pub fn execute(
             extension,
         } => execute_mint(deps, &env, &info, token_id, owner, extension),
         ExecuteMsg::Burn { token_id } => execute_burn(deps, &env, &info, token_id),
+        ExecuteMsg::TransferNft {
+            recipient,
+            token_id,
+        } => execute_transfer_nft(deps, &env, &info, recipient, token_id),
+        ExecuteMsg::SendNft {
+            contract,
+            token_id,
+            msg,
+        } => execute_send_nft(deps, &env, &info, contract, token_id, msg),
         ExecuteMsg::UpdateNftInfo {
             token_id,
             extension,
         } => execute_update_nft_info(deps, &env, &info, token_id, extension),
+        ExecuteMsg::UpdateConfig {
+            settlement_token,
+            native_token,
+            price_oracles,
+            oracle_quorum,
+        } => execute_update_config(
+            deps,
+            &env,
+            &info,
+            settlement_token,
+            native_token,
+            price_oracles,
+            oracle_quorum,
+        ),
+        ExecuteMsg::Receive(wrapper) => reserve::execute_receive(deps, &env, &info, wrapper),
+        ExecuteMsg::Deposit(msg) => reserve::execute_deposit(deps, &env, &info, msg),
+        ExecuteMsg::SetPoolCapacity { tier, capacity } => {
+            pool::execute_set_pool_capacity(deps, &env, &info, tier, capacity)
+        }
+        ExecuteMsg::SetTierConfig { tier, config } => {
+            tier::execute_set_tier_config(deps, &env, &info, tier, config)
+        }
+        ExecuteMsg::SetRoundConfig { config } => {
+            round::execute_set_round_config(deps, &env, &info, config)
+        }
+        ExecuteMsg::SelectWinners {
+            round_id,
+            token_ids,
+        } => round::execute_select_winners(deps, &env, &info, round_id, token_ids),
+        ExecuteMsg::SetMintFee { fee } => fee::execute_set_mint_fee(deps, &env, &info, fee),
+        ExecuteMsg::WithdrawFees { recipient } => {
+            fee::execute_withdraw_fees(deps, &env, &info, recipient)
+        }
+        ExecuteMsg::RegisterReporter {
+            id,
+            key,
+            allowed_currencies,
+            quota,
+        } => reporter::execute_register_reporter(
+            deps,
+            &env,
+            &info,
+            id,
+            key,
+            allowed_currencies,
+            quota,
+        ),
+        ExecuteMsg::RemoveReporter { id } => {
+            reporter::execute_remove_reporter(deps, &env, &info, id)
+        }
+        ExecuteMsg::MintFromReport { report, signature } => {
+            reporter::execute_mint_from_report(deps, &env, &info, report, signature)
+        }
+        ExecuteMsg::SetOracleConfig { config } => {
+            oracle::execute_set_oracle_config(deps, &env, &info, config)
+        }
+        ExecuteMsg::ObservePrice {} => oracle::execute_observe_price(deps, &env, &info),
+        ExecuteMsg::SetExpiryConfig { config } => {
+            expiry::execute_set_expiry_config(deps, &env, &info, config)
+        }
+        ExecuteMsg::SweepExpired { limit } => {
+            expiry::execute_sweep_expired(deps, &env, &info, limit)
+        }
+        ExecuteMsg::SetVestingConfig { config } => {
+            settlement::execute_set_vesting_config(deps, &env, &info, config)
+        }
+        ExecuteMsg::ClaimSettlement { token_id } => {
+            settlement::execute_claim_settlement(deps, &env, &info, token_id)
+        }
+        ExecuteMsg::ClaimVested {} => settlement::execute_claim_vested(deps, &env, &info),
+        ExecuteMsg::ClaimRewards {} => rewards::execute_claim_rewards(deps, &env, &info),
+        ExecuteMsg::AddEscrowContract { contract } => {
+            escrow::execute_add_escrow_contract(deps, &env, &info, contract)
+        }
+        ExecuteMsg::RemoveEscrowContract { contract } => {
+            escrow::execute_remove_escrow_contract(deps, &env, &info, contract)
+        }
+        ExecuteMsg::UpdateCreatorOwnership(action) => {
+            let ownership = CREATOR
+                .update_ownership(deps, &env.block, &info.sender, action)
+                .map_err(Cw721ContractError::Ownership)?;
+            Ok(Response::new()
+                .add_attribute("action", "consumption-unit::update_creator_ownership")
+                .add_attributes(ownership.into_attributes()))
+        }
+        ExecuteMsg::AddAuditor { auditor } => {
+            dispute::execute_add_auditor(deps, &env, &info, auditor)
+        }
+        ExecuteMsg::RemoveAuditor { auditor } => {
+            dispute::execute_remove_auditor(deps, &env, &info, auditor)
+        }
+        ExecuteMsg::OpenDispute { token_id, reason } => {
+            dispute::execute_open_dispute(deps, &env, &info, token_id, reason)
+        }
+        ExecuteMsg::ResolveDispute {
+            token_id,
+            burn,
+            reason,
+        } => dispute::execute_resolve_dispute(deps, &env, &info, token_id, burn, reason),
+        ExecuteMsg::SetComplianceConfig { config } => {
+            compliance::execute_set_compliance_config(deps, &env, &info, config)
+        }
+        ExecuteMsg::UpdateAllowlist { add, remove } => {
+            compliance::execute_update_allowlist(deps, &env, &info, add, remove)
+        }
+        ExecuteMsg::AddGuardian { guardian } => {
+            freeze::execute_add_guardian(deps, &env, &info, guardian)
+        }
+        ExecuteMsg::RemoveGuardian { guardian } => {
+            freeze::execute_remove_guardian(deps, &env, &info, guardian)
+        }
+        ExecuteMsg::FreezeAddress { address, reason } => {
+            freeze::execute_freeze(deps, &env, &info, address, reason)
+        }
+        ExecuteMsg::UnfreezeAddress { address } => {
+            freeze::execute_unfreeze(deps, &env, &info, address)
+        }
+    }
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    settlement_token: Option<Denom>,
+    native_token: Option<Denom>,
+    price_oracles: Option<Vec<String>>,
+    oracle_quorum: Option<u32>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    let old_cfg = config.collection_config.load(deps.storage)?;
+
+    let mut new_cfg = old_cfg.clone();
+    if let Some(settlement_token) = settlement_token {
+        new_cfg.settlement_token = validate_denom(deps.api, settlement_token)?;
+        // balances are tracked as plain amounts of the settlement token
+        if new_cfg.settlement_token != old_cfg.settlement_token
+            && reserve::holds_settlement_tokens(deps.storage, &old_cfg.settlement_token)?
+        {
+            return Err(ContractError::SettlementTokenInUse {});
+        }
+    }
+    if let Some(native_token) = native_token {
+        new_cfg.native_token = validate_denom(deps.api, native_token)?;
+    }
+    if let Some(price_oracles) = price_oracles {
+        new_cfg.price_oracles = price_oracles
+            .iter()
+            .map(|oracle| deps.api.addr_validate(oracle))
+            .collect::<StdResult<_>>()?;
+    }
+    if let Some(oracle_quorum) = oracle_quorum {
+        new_cfg.oracle_quorum = oracle_quorum;
+    }
+    validate_oracle_quorum(&new_cfg)?;
+
+    config.collection_config.save(deps.storage, &new_cfg)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::update_config")
+        .add_event(
+            Event::new("consumption-unit::update_config")
+                .add_attribute("sender", info.sender.to_string())
+                .add_attribute(
+                    "old_settlement_token",
+                    denom_to_string(&old_cfg.settlement_token),
+                )
+                .add_attribute(
+                    "new_settlement_token",
+                    denom_to_string(&new_cfg.settlement_token),
+                )
+                .add_attribute("old_native_token", denom_to_string(&old_cfg.native_token))
+                .add_attribute("new_native_token", denom_to_string(&new_cfg.native_token))
+                .add_attribute("old_price_oracles", join_addrs(&old_cfg.price_oracles))
+                .add_attribute("new_price_oracles", join_addrs(&new_cfg.price_oracles))
+                .add_attribute("old_oracle_quorum", old_cfg.oracle_quorum.to_string())
+                .add_attribute("new_oracle_quorum", new_cfg.oracle_quorum.to_string()),
+        ))
+}
+
+fn join_addrs(addrs: &[Addr]) -> String {
+    addrs.iter().map(Addr::as_str).collect::<Vec<_>>().join(",")
+}
+
+fn validate_oracle_quorum(cfg: &CUConfig) -> Result<(), ContractError> {
+    if cfg.oracle_quorum == 0 || cfg.oracle_quorum as usize > cfg.price_oracles.len() {
+        return Err(ContractError::InvalidOracleQuorum {});
+    }
+    Ok(())
+}
+
+fn validate_denom(api: &dyn Api, denom: Denom) -> StdResult<Denom> {
+    match denom {
+        Denom::Native(denom) => Ok(Denom::Native(denom)),
+        Denom::Cw20(addr) => Ok(Denom::Cw20(api.addr_validate(addr.as_str())?)),
     }
 }
 
@@ -91,62 +317,172 @@ fn execute_update_nft_info(
     token_id: String,
     update: ConsumptionUnitExtensionUpdate,
 ) -> Result<Response, ContractError> {
-    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
-
     match update {
         ConsumptionUnitExtensionUpdate::UpdatePool {
             new_commitment_tier_id,
         } => {
-            let mut current_nft_info = config.nft_info.load(deps.storage, &token_id)?;
-            if current_nft_info.owner != info.sender {
-                return Err(ContractError::Cw721ContractError(
-                    Cw721ContractError::Ownership(OwnershipError::NotOwner),
-                ));
-            }
+            let paid = reserve::native_payment(deps.as_ref(), info)?;
+            update_pool(
+                deps,
+                env,
+                &info.sender,
+                token_id,
+                new_commitment_tier_id,
+                paid,
+            )
+        }
+        ConsumptionUnitExtensionUpdate::Nominate {} => {
+            round::nominate(deps, env, &info.sender, token_id)
+        }
+    }
+}
 
-            if current_nft_info.extension.state == ConsumptionUnitState::Selected {
-                return Err(ContractError::WrongInput {});
-            }
+/// Moves the CU to a new commitment tier, `paid` is the amount of settlement tokens
+/// sent along to cover the switching fee.
+pub fn update_pool(
+    mut deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    token_id: String,
+    new_commitment_tier_id: u16,
+    paid: Uint128,
+) -> Result<Response, ContractError> {
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
 
-            current_nft_info.extension = current_nft_info
-                .extension
-                .update_tier(new_commitment_tier_id, env);
+    let mut current_nft_info = config.nft_info.load(deps.storage, &token_id)?;
+    if current_nft_info.owner != sender {
+        return Err(ContractError::Cw721ContractError(
+            Cw721ContractError::Ownership(OwnershipError::NotOwner),
+        ));
+    }
 
-            config
-                .nft_info
-                .save(deps.storage, &token_id, &current_nft_info)?;
+    if current_nft_info.extension.state == ConsumptionUnitState::Selected {
+        return Err(ContractError::WrongInput {});
+    }
+    dispute::assert_not_disputed(&token_id, &current_nft_info.extension)?;
+    freeze::assert_not_frozen(deps.storage, sender)?;
+    expiry::assert_not_expired(deps.storage, env, &token_id, &current_nft_info.extension)?;
 
-            Ok(Response::new()
-                .add_attribute("action", "consumption-unit::update_nft_info")
-                .add_event(
-                    Event::new("consumption-unit::update_nft_info")
-                        .add_attribute("token_id", token_id)
-                        .add_attribute(
-                            "new_commitment_pool_id",
-                            new_commitment_tier_id.to_string(),
-                        ),
-                ))
-        }
+    let fee = tier::assert_can_change_tier(
+        deps.storage,
+        env,
+        &current_nft_info.extension,
+        new_commitment_tier_id,
+    )?;
+    let penalty = penalty::early_exit_penalty(deps.storage, env, &current_nft_info.extension)?;
+    let expected = fee.checked_add(penalty)?;
+    if paid != expected {
+        return Err(ContractError::InvalidFee {
+            expected,
+            received: paid,
+        });
+    }
+    if !fee.is_zero() {
+        reserve::add_to_reserve(deps.storage, fee)?;
+    }
+    let penalty_msg = penalty::route_penalty(
+        deps.storage,
+        current_nft_info.extension.commitment_tier,
+        penalty,
+    )?;
+
+    let price = oracle::fetch_price(deps.branch(), env)?;
+
+    pool::remove_from_pool(deps.storage, &current_nft_info.extension)?;
+    let holder = escrow::holder(deps.storage, &token_id, sender)?;
+    voting::remove_holding(deps.storage, env, &holder, &current_nft_info.extension)?;
+    let (old_tier, old_floor_price) = (
+        current_nft_info.extension.commitment_tier,
+        current_nft_info.extension.floor_price,
+    );
+    current_nft_info.extension = current_nft_info
+        .extension
+        .update_tier(new_commitment_tier_id, env);
+    if let Some(price) = price {
+        current_nft_info.extension.floor_price =
+            tier::floor_price(deps.storage, new_commitment_tier_id, price)?;
     }
+    pool::add_to_pool(deps.storage, &current_nft_info.extension)?;
+    voting::add_holding(deps.storage, env, &holder, &current_nft_info.extension)?;
+    history::record(
+        deps.storage,
+        env,
+        &token_id,
+        sender,
+        HistoryAction::TierChange {
+            old_tier,
+            new_tier: new_commitment_tier_id,
+            old_floor_price,
+            new_floor_price: current_nft_info.extension.floor_price,
+        },
+    )?;
+
+    config
+        .nft_info
+        .save(deps.storage, &token_id, &current_nft_info)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::update_nft_info")
+        .add_messages(penalty_msg)
+        .add_event(
+            Event::new("consumption-unit::update_nft_info")
+                .add_attribute("token_id", token_id)
+                .add_attribute("new_commitment_pool_id", new_commitment_tier_id.to_string())
+                .add_attribute("switch_fee", fee.to_string())
+                .add_attribute("penalty", penalty.to_string())
+                .add_attribute(
+                    "floor_price",
+                    current_nft_info.extension.floor_price.to_string(),
+                ),
+        ))
 }
 
 #[allow(clippy::too_many_arguments)]
 fn execute_mint(
     deps: DepsMut,
-    _env: &Env,
+    env: &Env,
     info: &MessageInfo,
     token_id: String,
     owner: String,
     extension: ConsumptionUnitData,
 ) -> Result<Response, ContractError> {
     assert_minter(deps.storage, &info.sender)?;
+    mint(deps, env, info, token_id, owner, extension)
+}
+
+/// Mints a new CU, the caller is responsible for verifying the sender may mint it
+pub fn mint(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: String,
+    owner: String,
+    extension: ConsumptionUnitData,
+) -> Result<Response, ContractError> {
     // validate owner
     let owner_addr = deps.api.addr_validate(&owner)?;
+    compliance::assert_allowed(deps.as_ref(), &owner_addr)?;
+    history::assert_unused(deps.storage, &token_id)?;
 
     let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
 
+    let fee = fee::collect_mint_fee(deps.storage, info, extension.consumption_value)?;
+    pool::add_to_pool(deps.storage, &extension)?;
+    rewards::add_weight(deps.storage, &owner_addr, extension.consumption_value)?;
+    voting::add_holding(deps.storage, env, &owner_addr, &extension)?;
+
     // create the token
 
+    snapshot_ownership(deps.storage, env, &token_id, None, Some(&owner_addr))?;
+    history::record(
+        deps.storage,
+        env,
+        &token_id,
+        &info.sender,
+        HistoryAction::Mint {
+            owner: owner_addr.clone(),
+        },
+    )?;
     let token = ConsumptionUnitNft {
         owner: owner_addr,
         extension,
@@ -166,23 +502,173 @@ fn execute_mint(
         .add_event(
             Event::new("consumption-unit::mint")
                 .add_attribute("token_id", token_id)
-                .add_attribute("owner", owner),
+                .add_attribute("owner", owner)
+                .add_attribute("fee", fee.to_string()),
+        ))
+}
+
+/// Holder of the CU before it is transferred, the depositor if escrowed
+fn load_holder(storage: &dyn Storage, token_id: &str) -> Result<Addr, ContractError> {
+    let owner = Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+        .nft_info
+        .load(storage, token_id)?
+        .owner;
+    Ok(escrow::holder(storage, token_id, &owner)?)
+}
+
+fn execute_transfer_nft(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    recipient: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let holder = load_holder(deps.storage, &token_id)?;
+    freeze::assert_can_transfer(deps.storage, &info.sender, &holder, &recipient)?;
+    dispute::assert_token_not_disputed(deps.storage, &token_id)?;
+    compliance::assert_can_receive(deps.as_ref(), &holder, &recipient)?;
+    let token = transfer_nft::<ConsumptionUnitData>(
+        deps.storage,
+        env,
+        &info.sender,
+        &recipient,
+        &token_id,
+    )?;
+    let new_holder = escrow::move_holder(deps.storage, &token_id, &holder, &recipient)?;
+    // expired CUs no longer carry reward weight or voting power
+    if token.extension.state != ConsumptionUnitState::Expired {
+        rewards::transfer_weight(
+            deps.storage,
+            &holder,
+            &new_holder,
+            token.extension.consumption_value,
+        )?;
+        voting::transfer_holding(deps.storage, env, &holder, &new_holder, &token.extension)?;
+    }
+    history::record(
+        deps.storage,
+        env,
+        &token_id,
+        &info.sender,
+        HistoryAction::Transfer {
+            from: info.sender.clone(),
+            to: recipient.clone(),
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::transfer_nft")
+        .add_event(
+            Event::new("consumption-unit::transfer_nft")
+                .add_attribute("sender", info.sender.to_string())
+                .add_attribute("recipient", recipient.to_string())
+                .add_attribute("token_id", token_id),
+        ))
+}
+
+fn execute_send_nft(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    contract: String,
+    token_id: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let contract = deps.api.addr_validate(&contract)?;
+    let holder = load_holder(deps.storage, &token_id)?;
+    freeze::assert_can_transfer(deps.storage, &info.sender, &holder, &contract)?;
+    dispute::assert_token_not_disputed(deps.storage, &token_id)?;
+    compliance::assert_can_receive(deps.as_ref(), &holder, &contract)?;
+    let token =
+        transfer_nft::<ConsumptionUnitData>(deps.storage, env, &info.sender, &contract, &token_id)?;
+    let new_holder = escrow::move_holder(deps.storage, &token_id, &holder, &contract)?;
+    // expired CUs no longer carry reward weight or voting power
+    if token.extension.state != ConsumptionUnitState::Expired {
+        rewards::transfer_weight(
+            deps.storage,
+            &holder,
+            &new_holder,
+            token.extension.consumption_value,
+        )?;
+        voting::transfer_holding(deps.storage, env, &holder, &new_holder, &token.extension)?;
+    }
+    history::record(
+        deps.storage,
+        env,
+        &token_id,
+        &info.sender,
+        HistoryAction::Transfer {
+            from: info.sender.clone(),
+            to: contract.clone(),
+        },
+    )?;
+
+    let receive_msg = Cw721ReceiveMsg {
+        sender: info.sender.to_string(),
+        token_id: token_id.clone(),
+        msg,
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::send_nft")
+        .add_message(receive_msg.into_cosmos_msg(&contract)?)
+        .add_event(
+            Event::new("consumption-unit::send_nft")
+                .add_attribute("sender", info.sender.to_string())
+                .add_attribute("contract", contract.to_string())
+                .add_attribute("token_id", token_id),
         ))
 }
 
+/// Burns the CU and removes it from pools, rewards and voting power,
+/// the caller is responsible for verifying the sender may burn it
+pub fn burn(
+    storage: &mut dyn Storage,
+    env: &Env,
+    actor: &Addr,
+    token_id: &str,
+    token: ConsumptionUnitNft,
+) -> Result<(), ContractError> {
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    let holder = escrow::holder(storage, token_id, &token.owner)?;
+    escrow::release(storage, token_id);
+    // expired CUs already left their pool, rewards and votes when swept
+    if token.extension.state != ConsumptionUnitState::Expired {
+        pool::remove_from_pool(storage, &token.extension)?;
+        rewards::remove_weight(storage, &holder, token.extension.consumption_value)?;
+        voting::remove_holding(storage, env, &holder, &token.extension)?;
+    }
+    snapshot_ownership(storage, env, token_id, Some(&token.owner), None)?;
+    history::record(
+        storage,
+        env,
+        token_id,
+        actor,
+        HistoryAction::Burn { owner: token.owner },
+    )?;
+    config.nft_info.remove(storage, token_id)?;
+    config.decrement_tokens(storage)?;
+    Ok(())
+}
+
 fn execute_burn(
     deps: DepsMut,
-    _env: &Env,
+    env: &Env,
     info: &MessageInfo,
     token_id: String,
 ) -> Result<Response, ContractError> {
     let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
-    // TODO verify ownership
-    // let token = config.nft_info.load(deps.storage, &token_id)?;
-    // check_can_send(deps.as_ref(), env, info.sender.as_str(), &token)?;
+    let token = config.nft_info.load(deps.storage, &token_id)?;
+    if token.owner != info.sender {
+        return Err(ContractError::Cw721ContractError(
+            Cw721ContractError::Ownership(OwnershipError::NotOwner),
+        ));
+    }
+    dispute::assert_not_disputed(&token_id, &token.extension)?;
+    freeze::assert_not_frozen(deps.storage, &token.owner)?;
 
-    config.nft_info.remove(deps.storage, &token_id)?;
-    config.decrement_tokens(deps.storage)?;
+    burn(deps.storage, env, &info.sender, &token_id, token)?;
 
     Ok(Response::new()
         .add_attribute("action", "consumption-unit::burn")
@@ -194,10 +680,239 @@ fn execute_burn(
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
     cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     match msg {
-        MigrateMsg::Migrate {} => Ok(Response::new()),
+        MigrateMsg::Migrate { snapshots } => {
+            let snapshots_recorded = match snapshots {
+                Some(true) => {
+                    q_nft::execute::enable_snapshots::<ConsumptionUnitData>(deps.storage, &env)?
+                }
+                _ => 0,
+            };
+            let config_migrated = migration::migrate_config(deps.storage)?;
+            let pools_backfilled = migration::backfill_pool_stats(deps.storage)?;
+            let reward_weights_backfilled = migration::backfill_reward_weights(deps.storage)?;
+            let holdings_backfilled = migration::backfill_holdings(deps.storage, &env)?;
+            Ok(Response::new()
+                .add_attribute("action", "consumption-unit::migrate")
+                .add_event(
+                    Event::new("consumption-unit::migrate")
+                        .add_attribute("config_migrated", config_migrated.to_string())
+                        .add_attribute("pools_backfilled", pools_backfilled.to_string())
+                        .add_attribute(
+                            "reward_weights_backfilled",
+                            reward_weights_backfilled.to_string(),
+                        )
+                        .add_attribute("holdings_backfilled", holdings_backfilled.to_string())
+                        .add_attribute("snapshots_recorded", snapshots_recorded.to_string()),
+                ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::ReceiveMsg;
+    use crate::query::query_config;
+    use crate::testing::{cu_data, mint, setup};
+    use cosmwasm_std::coins;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{to_json_binary, WasmMsg};
+    use q_nft::query::{query_balance_at_height, query_owner_of_at_height};
+
+    #[test]
+    fn test_update_config() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let oracle = deps.api.addr_make("new_oracle");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+
+        let msg = ExecuteMsg::UpdateConfig {
+            settlement_token: None,
+            native_token: Some(Denom::Native("qnc".to_string())),
+            price_oracles: Some(vec![oracle.to_string()]),
+            oracle_quorum: None,
+        };
+
+        let stranger = deps.api.addr_make("stranger");
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&stranger, &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Cw721ContractError(Cw721ContractError::NotCreator {})
+        ));
+
+        execute(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap();
+
+        let config = query_config(deps.as_ref()).unwrap();
+        assert_eq!(
+            config.collection_config,
+            CUConfig {
+                settlement_token: Denom::Native("settlement".to_string()),
+                native_token: Denom::Native("qnc".to_string()),
+                price_oracles: vec![oracle],
+                oracle_quorum: 1,
+            }
+        );
+        assert_eq!(config.creator_ownership.owner, Some(creator.clone()));
+        assert_eq!(config.contract_version.contract, CONTRACT_NAME);
+
+        // the settlement token is fixed once the contract holds any
+        let msg = ExecuteMsg::UpdateConfig {
+            settlement_token: Some(Denom::Native("other".to_string())),
+            native_token: None,
+            price_oracles: None,
+            oracle_quorum: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &coins(10, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::FundReserve {}),
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), mock_env(), message_info(&creator, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::SettlementTokenInUse {}));
+    }
+
+    #[test]
+    fn test_transfer_and_send_nft() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        let recipient = deps.api.addr_make("recipient");
+        let marketplace = deps.api.addr_make("marketplace");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(100, 1)).unwrap();
+
+        let transfer_msg = ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: "1".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&recipient, &[]),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Cw721ContractError(Cw721ContractError::Ownership(
+                OwnershipError::NotOwner
+            ))
+        ));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            transfer_msg,
+        )
+        .unwrap();
+
+        let hook = to_json_binary("list").unwrap();
+        let response = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&recipient, &[]),
+            ExecuteMsg::SendNft {
+                contract: marketplace.to_string(),
+                token_id: "1".to_string(),
+                msg: hook.clone(),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            response.messages[0].msg,
+            WasmMsg::Execute {
+                contract_addr: marketplace.to_string(),
+                msg: to_json_binary(&q_nft::msg::ReceiverExecuteMsg::ReceiveNft(
+                    Cw721ReceiveMsg {
+                        sender: recipient.to_string(),
+                        token_id: "1".to_string(),
+                        msg: hook,
+                    }
+                ))
+                .unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+
+        let nft_info = Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+            .nft_info
+            .load(deps.as_ref().storage, "1")
+            .unwrap();
+        assert_eq!(nft_info.owner, marketplace);
+    }
+
+    #[test]
+    fn test_ownership_snapshots() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        let recipient = deps.api.addr_make("recipient");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        let minted = env.block.height;
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(100, 1)).unwrap();
+
+        env.block.height += 1;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: recipient.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        env.block.height += 1;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&recipient, &[]),
+            ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+
+        let owner_at = |height| query_owner_of_at_height(&deps.storage, "1".to_string(), height);
+        let balance_at = |owner: &Addr, height| {
+            query_balance_at_height(deps.as_ref(), owner.to_string(), height)
+                .unwrap()
+                .count
+        };
+        // snapshots are taken at the start of each block
+        assert!(owner_at(minted).is_err());
+        assert_eq!(owner_at(minted + 1).unwrap().owner, owner.to_string());
+        assert_eq!(owner_at(minted + 2).unwrap().owner, recipient.to_string());
+        assert!(owner_at(minted + 3).is_err());
+        assert_eq!(balance_at(&owner, minted + 1), 1);
+        assert_eq!(balance_at(&owner, minted + 2), 0);
+        assert_eq!(balance_at(&recipient, minted + 2), 1);
+        assert_eq!(balance_at(&recipient, minted + 3), 0);
     }
 }
@@ -0,0 +1,327 @@
+use crate::contract::burn;
+use crate::error::ContractError;
+use crate::history;
+use crate::msg::{AuditorsResponse, DisputeResponse, DisputesResponse};
+use crate::state::{AUDITORS, DISPUTES};
+use crate::types::{
+    CUConfig, ConsumptionUnitData, ConsumptionUnitState, Dispute, DisputeResolution, HistoryAction,
+};
+use cosmwasm_std::{
+    Addr, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, Response, StdResult, Storage,
+};
+use cw_storage_plus::Bound;
+use q_nft::execute::assert_creator;
+use q_nft::query::{DEFAULT_LIMIT, MAX_LIMIT};
+use q_nft::state::Cw721Config;
+
+/// Fails if the CU is frozen by an open dispute
+pub fn assert_not_disputed(
+    token_id: &str,
+    data: &ConsumptionUnitData,
+) -> Result<(), ContractError> {
+    if data.state == ConsumptionUnitState::Disputed {
+        return Err(ContractError::Disputed {
+            token_id: token_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Same as `assert_not_disputed`, loading the CU
+pub fn assert_token_not_disputed(
+    storage: &dyn Storage,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    let nft_info = Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+        .nft_info
+        .load(storage, token_id)?;
+    assert_not_disputed(token_id, &nft_info.extension)
+}
+
+pub fn execute_add_auditor(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    auditor: String,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+    let auditor = deps.api.addr_validate(&auditor)?;
+    AUDITORS.save(deps.storage, &auditor, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::add_auditor")
+        .add_event(
+            Event::new("consumption-unit::add_auditor")
+                .add_attribute("auditor", auditor.to_string()),
+        ))
+}
+
+pub fn execute_remove_auditor(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    auditor: String,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+    let auditor = deps.api.addr_validate(&auditor)?;
+    AUDITORS.remove(deps.storage, &auditor);
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::remove_auditor")
+        .add_event(
+            Event::new("consumption-unit::remove_auditor")
+                .add_attribute("auditor", auditor.to_string()),
+        ))
+}
+
+pub fn execute_open_dispute(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    if !AUDITORS.has(deps.storage, &info.sender) {
+        return Err(ContractError::NotAuditor {});
+    }
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    let mut nft_info = config.nft_info.load(deps.storage, &token_id)?;
+    assert_not_disputed(&token_id, &nft_info.extension)?;
+
+    let dispute = Dispute {
+        auditor: info.sender.clone(),
+        reason: reason.clone(),
+        opened_at: env.block.time,
+        previous_state: nft_info.extension.state.clone(),
+        resolution: None,
+    };
+    DISPUTES.save(deps.storage, &token_id, &dispute)?;
+    history::record(
+        deps.storage,
+        env,
+        &token_id,
+        &info.sender,
+        HistoryAction::DisputeOpened {
+            reason: reason.clone(),
+        },
+    )?;
+    nft_info.extension.state = ConsumptionUnitState::Disputed;
+    config.nft_info.save(deps.storage, &token_id, &nft_info)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::open_dispute")
+        .add_event(
+            Event::new("consumption-unit::open_dispute")
+                .add_attribute("token_id", token_id)
+                .add_attribute("auditor", info.sender.to_string())
+                .add_attribute("reason", reason),
+        ))
+}
+
+pub fn execute_resolve_dispute(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: String,
+    burn_token: bool,
+    reason: String,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    let mut nft_info = config.nft_info.load(deps.storage, &token_id)?;
+    let mut dispute = match DISPUTES.may_load(deps.storage, &token_id)? {
+        Some(dispute) if nft_info.extension.state == ConsumptionUnitState::Disputed => dispute,
+        _ => return Err(ContractError::NotDisputed { token_id }),
+    };
+
+    dispute.resolution = Some(DisputeResolution {
+        resolver: info.sender.clone(),
+        burned: burn_token,
+        reason: reason.clone(),
+        resolved_at: env.block.time,
+    });
+    DISPUTES.save(deps.storage, &token_id, &dispute)?;
+    history::record(
+        deps.storage,
+        env,
+        &token_id,
+        &info.sender,
+        HistoryAction::DisputeResolved {
+            burned: burn_token,
+            reason: reason.clone(),
+        },
+    )?;
+
+    nft_info.extension.state = dispute.previous_state;
+    if burn_token {
+        burn(deps.storage, env, &info.sender, &token_id, nft_info)?;
+    } else {
+        config.nft_info.save(deps.storage, &token_id, &nft_info)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::resolve_dispute")
+        .add_event(
+            Event::new("consumption-unit::resolve_dispute")
+                .add_attribute("token_id", token_id)
+                .add_attribute("burned", burn_token.to_string())
+                .add_attribute("reason", reason),
+        ))
+}
+
+pub fn query_auditors(deps: Deps) -> StdResult<AuditorsResponse> {
+    let auditors = AUDITORS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    Ok(AuditorsResponse { auditors })
+}
+
+pub fn query_dispute(deps: Deps, token_id: String) -> StdResult<Option<Dispute>> {
+    DISPUTES.may_load(deps.storage, &token_id)
+}
+
+/// Returns the latest dispute of each CU ordered by token id, only unresolved ones if `open`
+pub fn query_disputes(
+    deps: Deps,
+    open: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<DisputesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let disputes = DISPUTES
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter(|item| !open || matches!(item, Ok((_, dispute)) if dispute.resolution.is_none()))
+        .take(limit)
+        .map(|item| item.map(|(token_id, dispute)| DisputeResponse { token_id, dispute }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(DisputesResponse { disputes })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg};
+    use crate::testing::{cu_data, mint, setup};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cw20::Denom;
+
+    fn exec(deps: DepsMut, sender: &Addr, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), message_info(sender, &[]), msg)
+    }
+
+    fn state(deps: Deps, token_id: &str) -> ConsumptionUnitState {
+        Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+            .nft_info
+            .load(deps.storage, token_id)
+            .unwrap()
+            .extension
+            .state
+    }
+
+    #[test]
+    fn test_dispute_flow() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let auditor = deps.api.addr_make("auditor");
+        let owner = deps.api.addr_make("owner");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(100, 1)).unwrap();
+        let open_msg = ExecuteMsg::OpenDispute {
+            token_id: "1".to_string(),
+            reason: "duplicated records".to_string(),
+        };
+
+        let err = exec(deps.as_mut(), &auditor, open_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotAuditor {}));
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::AddAuditor {
+                auditor: auditor.to_string(),
+            },
+        )
+        .unwrap();
+        exec(deps.as_mut(), &auditor, open_msg.clone()).unwrap();
+        assert_eq!(state(deps.as_ref(), "1"), ConsumptionUnitState::Disputed);
+
+        // the CU is frozen while disputed
+        let transfer_msg = ExecuteMsg::TransferNft {
+            recipient: creator.to_string(),
+            token_id: "1".to_string(),
+        };
+        for msg in [
+            transfer_msg.clone(),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: "1".to_string(),
+                extension: ConsumptionUnitExtensionUpdate::UpdatePool {
+                    new_commitment_tier_id: 2,
+                },
+            },
+            ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+            ExecuteMsg::ClaimSettlement {
+                token_id: "1".to_string(),
+            },
+        ] {
+            let err = exec(deps.as_mut(), &owner, msg).unwrap_err();
+            assert!(matches!(err, ContractError::Disputed { .. }));
+        }
+        let err = exec(deps.as_mut(), &auditor, open_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Disputed { .. }));
+        assert_eq!(
+            query_disputes(deps.as_ref(), true, None, None)
+                .unwrap()
+                .disputes
+                .len(),
+            1
+        );
+
+        // clearing restores the previous state
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::ResolveDispute {
+                token_id: "1".to_string(),
+                burn: false,
+                reason: "records verified".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(state(deps.as_ref(), "1"), ConsumptionUnitState::Reflected);
+        assert!(query_disputes(deps.as_ref(), true, None, None)
+            .unwrap()
+            .disputes
+            .is_empty());
+
+        exec(deps.as_mut(), &auditor, open_msg).unwrap();
+        let resolve_msg = ExecuteMsg::ResolveDispute {
+            token_id: "1".to_string(),
+            burn: true,
+            reason: "fraudulent records".to_string(),
+        };
+        let err = exec(deps.as_mut(), &auditor, resolve_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Cw721ContractError(_)));
+        exec(deps.as_mut(), &creator, resolve_msg).unwrap();
+        assert!(Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+            .nft_info
+            .may_load(deps.as_ref().storage, "1")
+            .unwrap()
+            .is_none());
+        let resolution = query_dispute(deps.as_ref(), "1".to_string())
+            .unwrap()
+            .unwrap()
+            .resolution
+            .unwrap();
+        assert!(resolution.burned);
+        assert_eq!(resolution.reason, "fraudulent records");
+    }
+}
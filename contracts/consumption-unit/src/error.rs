@@ -1,5 +1,8 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, DecimalRangeExceeded, OverflowError, StdError, Uint128};
+use cw_utils::PaymentError;
 use q_nft::error::Cw721ContractError;
+use q_utils::asset::AssetError;
+use q_utils::fixed_point::FixedPointError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,6 +11,123 @@ pub enum ContractError {
     Std(#[from] StdError),
     #[error("{0}")]
     Cw721ContractError(#[from] Cw721ContractError),
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+    #[error("{0}")]
+    FixedPoint(#[from] FixedPointError),
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+    #[error("{0}")]
+    DecimalRangeExceeded(#[from] DecimalRangeExceeded),
     #[error("WrongInput")]
     WrongInput {},
+    #[error("Only the configured settlement token is accepted")]
+    UnsupportedToken {},
+    #[error("Settlement token cannot change while settlement tokens are held")]
+    SettlementTokenInUse {},
+    #[error("Capacity of commitment tier {tier} exceeded")]
+    PoolCapacityExceeded { tier: u16 },
+    #[error("Commitment tier {tier} is locked, {remaining} seconds remaining")]
+    TierLocked { tier: u16, remaining: u64 },
+    #[error("Tier change is on cooldown, {remaining} seconds remaining")]
+    TierChangeCooldown { remaining: u64 },
+    #[error("Early exit penalty rate must be at most 1")]
+    InvalidPenalty {},
+    #[error("Insufficient mint deposit: required {required}, available {available}")]
+    InsufficientDeposit {
+        required: Uint128,
+        available: Uint128,
+    },
+    #[error("No fees to withdraw")]
+    NoFeesToWithdraw {},
+    #[error("Reporter {id} is not registered")]
+    UnknownReporter { id: String },
+    #[error("Unauthorized reporter")]
+    UnauthorizedReporter {},
+    #[error("Invalid report signature")]
+    InvalidSignature {},
+    #[error("Currency {currency} is not allowed for the reporter")]
+    CurrencyNotAllowed { currency: String },
+    #[error("Reporter quota exceeded")]
+    ReporterQuotaExceeded {},
+    #[error("Invalid reporter public key")]
+    InvalidReporterKey {},
+    #[error("Report has expired")]
+    ReportExpired {},
+    #[error("Report was already submitted")]
+    ReportAlreadyConsumed {},
+    #[error("Oracle quorum must be between 1 and the number of oracles")]
+    InvalidOracleQuorum {},
+    #[error("Only {sources} oracles reported a fresh price, quorum is {quorum}")]
+    OracleQuorumNotMet { sources: u32, quorum: u32 },
+    #[error("Oracle price checks are not configured")]
+    OracleNotConfigured {},
+    #[error("Price history does not cover the TWAP window of {window} seconds")]
+    InsufficientPriceHistory { window: u64 },
+    #[error(
+        "Window of {window} seconds exceeds the configured TWAP window of {max_window} seconds"
+    )]
+    TwapWindowTooLong { window: u64, max_window: u64 },
+    #[error("Oracle price {price} is out of bounds")]
+    PriceOutOfBounds { price: Decimal },
+    #[error("Oracle price {price} deviates too much from the last accepted price {reference}")]
+    PriceDeviationExceeded { price: Decimal, reference: Decimal },
+    #[error("Consumption unit {token_id} is expired")]
+    Expired { token_id: String },
+    #[error("Expiry of consumption units is not configured")]
+    ExpiryNotConfigured {},
+    #[error("Expiry ttl must be non-zero and representable")]
+    InvalidExpiryConfig {},
+    #[error("Invalid round config")]
+    InvalidRoundConfig {},
+    #[error("No raffle round is running")]
+    NoActiveRound {},
+    #[error("Nominations for round {round_id} are closed")]
+    NominationClosed { round_id: u64 },
+    #[error("Nominations for round {round_id} are still open")]
+    NominationOpen { round_id: u64 },
+    #[error("Token {token_id} is already nominated in round {round_id}")]
+    AlreadyNominated { token_id: String, round_id: u64 },
+    #[error("Token {token_id} is not nominated in round {round_id}")]
+    NotNominated { token_id: String, round_id: u64 },
+    #[error("Consumption unit {token_id} is not selected")]
+    NotSelected { token_id: String },
+    #[error("Insufficient reserve: required {required}, available {available}")]
+    InsufficientReserve {
+        required: Uint128,
+        available: Uint128,
+    },
+    #[error("Vesting cliff must not exceed a non-zero duration")]
+    InvalidVestingConfig {},
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+    #[error("Invalid fee: expected {expected}, received {received}")]
+    InvalidFee {
+        expected: Uint128,
+        received: Uint128,
+    },
+    #[error("Token id {token_id} was already used, burned token ids cannot be minted again")]
+    TokenIdUsed { token_id: String },
+    #[error("Caller is not a registered auditor")]
+    NotAuditor {},
+    #[error("Consumption unit {token_id} is disputed")]
+    Disputed { token_id: String },
+    #[error("Consumption unit {token_id} is not disputed")]
+    NotDisputed { token_id: String },
+    #[error("Address {address} is not allowed to hold consumption units")]
+    NotAllowed { address: String },
+    #[error("Address {address} is frozen")]
+    Frozen { address: String },
+    #[error("Caller is neither collection creator nor guardian")]
+    NotCreatorOrGuardian {},
+}
+
+impl From<AssetError> for ContractError {
+    fn from(err: AssetError) -> Self {
+        match err {
+            AssetError::Std(err) => ContractError::Std(err),
+            AssetError::Payment(err) => ContractError::Payment(err),
+            AssetError::UnexpectedToken { .. } => ContractError::UnsupportedToken {},
+        }
+    }
 }
@@ -0,0 +1,80 @@
+use crate::error::ContractError;
+use crate::msg::EscrowContractsResponse;
+use crate::state::{ESCROWED, ESCROW_CONTRACTS};
+use cosmwasm_std::{
+    Addr, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, Response, StdResult, Storage,
+};
+use q_nft::execute::assert_creator;
+
+/// Address the rewards and voting power of the CU are attributed to: the depositor while
+/// the CU is held by a registered escrow contract, its owner otherwise
+pub fn holder(storage: &dyn Storage, token_id: &str, owner: &Addr) -> StdResult<Addr> {
+    Ok(ESCROWED
+        .may_load(storage, token_id)?
+        .unwrap_or_else(|| owner.clone()))
+}
+
+/// Records the move of the CU from `holder` to `recipient` and returns its new holder.
+/// CUs entering an escrow contract stay attributed to their holder,
+/// CUs leaving escrow are attributed to their new owner.
+pub fn move_holder(
+    storage: &mut dyn Storage,
+    token_id: &str,
+    holder: &Addr,
+    recipient: &Addr,
+) -> StdResult<Addr> {
+    if ESCROW_CONTRACTS.has(storage, recipient) {
+        ESCROWED.save(storage, token_id, holder)?;
+        return Ok(holder.clone());
+    }
+    ESCROWED.remove(storage, token_id);
+    Ok(recipient.clone())
+}
+
+/// Forgets the depositor of a burned CU
+pub fn release(storage: &mut dyn Storage, token_id: &str) {
+    ESCROWED.remove(storage, token_id);
+}
+
+pub fn execute_add_escrow_contract(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    contract: String,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+    let contract = deps.api.addr_validate(&contract)?;
+    ESCROW_CONTRACTS.save(deps.storage, &contract, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::add_escrow_contract")
+        .add_event(
+            Event::new("consumption-unit::add_escrow_contract")
+                .add_attribute("contract", contract.to_string()),
+        ))
+}
+
+pub fn execute_remove_escrow_contract(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    contract: String,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+    let contract = deps.api.addr_validate(&contract)?;
+    ESCROW_CONTRACTS.remove(deps.storage, &contract);
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::remove_escrow_contract")
+        .add_event(
+            Event::new("consumption-unit::remove_escrow_contract")
+                .add_attribute("contract", contract.to_string()),
+        ))
+}
+
+pub fn query_escrow_contracts(deps: Deps) -> StdResult<EscrowContractsResponse> {
+    let contracts = ESCROW_CONTRACTS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    Ok(EscrowContractsResponse { contracts })
+}
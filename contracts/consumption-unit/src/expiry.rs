@@ -0,0 +1,376 @@
+use crate::contract::burn;
+use crate::error::ContractError;
+use crate::reserve::load_settlement_token;
+use crate::state::{EXPIRY_CONFIG, SWEEP_CURSOR};
+use crate::types::{
+    CUConfig, ConsumptionUnitData, ConsumptionUnitState, ExpiryConfig, HistoryAction,
+};
+use crate::{escrow, fee, history, pool, rewards, voting};
+use cosmwasm_std::{
+    Deps, DepsMut, Env, Event, MessageInfo, Order, Response, StdResult, Storage, Uint128,
+};
+use cw_storage_plus::Bound;
+use q_nft::execute::assert_creator;
+use q_nft::query::{DEFAULT_LIMIT, MAX_LIMIT};
+use q_nft::state::Cw721Config;
+use q_utils::asset::Asset;
+
+/// Fails if the CU is expired, whether or not it was swept yet
+pub fn assert_not_expired(
+    storage: &dyn Storage,
+    env: &Env,
+    token_id: &str,
+    data: &ConsumptionUnitData,
+) -> Result<(), ContractError> {
+    let expired = match EXPIRY_CONFIG.may_load(storage)? {
+        Some(config) => config.is_expired(data, env),
+        None => data.state == ConsumptionUnitState::Expired,
+    };
+    if expired {
+        return Err(ContractError::Expired {
+            token_id: token_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Sets or removes the expiry config, the ttl must be non-zero and the expiry of CUs
+/// created now must be representable
+pub fn execute_set_expiry_config(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    config: Option<ExpiryConfig>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let mut event = Event::new("consumption-unit::set_expiry_config");
+    match config {
+        Some(config) => {
+            if config.ttl == 0 || config.expires_at(env.block.time).is_none() {
+                return Err(ContractError::InvalidExpiryConfig {});
+            }
+            event = event
+                .add_attribute("ttl", config.ttl.to_string())
+                .add_attribute("reward", config.reward.unwrap_or_default().to_string())
+                .add_attribute("burn", config.burn.to_string());
+            EXPIRY_CONFIG.save(deps.storage, &config)?;
+        }
+        None => EXPIRY_CONFIG.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::set_expiry_config")
+        .add_event(event))
+}
+
+pub fn execute_sweep_expired(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let expiry_config = EXPIRY_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::ExpiryNotConfigured {})?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    let start = SWEEP_CURSOR
+        .may_load(deps.storage)?
+        .map(|s| Bound::ExclusiveRaw(s.into()));
+    let tokens = config
+        .nft_info
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    // start over from the first token once the end is reached
+    match tokens.last() {
+        Some((token_id, _)) if tokens.len() == limit => {
+            SWEEP_CURSOR.save(deps.storage, token_id)?
+        }
+        _ => SWEEP_CURSOR.remove(deps.storage),
+    }
+
+    let mut swept = vec![];
+    for (token_id, mut nft_info) in tokens {
+        let archived = nft_info.extension.state == ConsumptionUnitState::Expired;
+        if !expiry_config.is_expired(&nft_info.extension, env) || (archived && !expiry_config.burn)
+        {
+            continue;
+        }
+        if expiry_config.burn {
+            burn(deps.storage, env, &info.sender, &token_id, nft_info)?;
+        } else {
+            pool::remove_from_pool(deps.storage, &nft_info.extension)?;
+            let holder = escrow::holder(deps.storage, &token_id, &nft_info.owner)?;
+            rewards::remove_weight(deps.storage, &holder, nft_info.extension.consumption_value)?;
+            voting::remove_holding(deps.storage, env, &holder, &nft_info.extension)?;
+            history::record(
+                deps.storage,
+                env,
+                &token_id,
+                &info.sender,
+                HistoryAction::StateChange {
+                    old_state: nft_info.extension.state,
+                    new_state: ConsumptionUnitState::Expired,
+                },
+            )?;
+            nft_info.extension.state = ConsumptionUnitState::Expired;
+            config.nft_info.save(deps.storage, &token_id, &nft_info)?;
+        }
+        swept.push(token_id);
+    }
+
+    // the reward is capped by the collected fees so that sweeping never fails on it,
+    // the reserves backing settlements are left untouched
+    let settlement_token = load_settlement_token(deps.as_ref())?;
+    let reward = fee::take_collected_fees(
+        deps.storage,
+        &settlement_token,
+        expiry_config
+            .reward
+            .unwrap_or_default()
+            .checked_mul(Uint128::new(swept.len() as u128))?,
+    )?;
+
+    let mut response = Response::new().add_attribute("action", "consumption-unit::sweep_expired");
+    if !reward.is_zero() {
+        response =
+            response.add_message(Asset::new(settlement_token, reward).transfer_msg(&info.sender)?);
+    }
+
+    Ok(response.add_event(
+        Event::new("consumption-unit::sweep_expired")
+            .add_attribute("sweeper", info.sender.to_string())
+            .add_attribute("count", swept.len().to_string())
+            .add_attribute("token_ids", swept.join(","))
+            .add_attribute("burned", expiry_config.burn.to_string())
+            .add_attribute("reward", reward.to_string()),
+    ))
+}
+
+pub fn query_expiry_config(deps: Deps) -> StdResult<Option<ExpiryConfig>> {
+    EXPIRY_CONFIG.may_load(deps.storage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg, ReceiveMsg};
+    use crate::pool::query_pool_stats;
+    use crate::reserve::query_reserve;
+    use crate::rewards::query_pending_rewards;
+    use crate::testing::{cu_data, mint, setup};
+    use crate::types::{FeeAmount, FeeToken, MintFeeConfig};
+    use crate::voting::{query_total_power, query_voting_power};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{coins, Addr, BankMsg, Coin};
+    use cw20::Denom;
+
+    fn sweep(deps: DepsMut, env: &Env, sweeper: &Addr) -> Response {
+        execute(
+            deps,
+            env.clone(),
+            message_info(sweeper, &[]),
+            ExecuteMsg::SweepExpired { limit: Some(2) },
+        )
+        .unwrap()
+    }
+
+    fn swept(response: &Response) -> String {
+        response.events[0]
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "token_ids")
+            .unwrap()
+            .value
+            .clone()
+    }
+
+    #[test]
+    fn test_sweep_expired() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        let sweeper = deps.api.addr_make("sweeper");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &coins(15, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::FundReserve {}),
+        )
+        .unwrap();
+        // 4 CUs are minted for 4 settlement tokens each
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &coins(16, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::PayForMint {}),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetMintFee {
+                fee: Some(MintFeeConfig {
+                    token: FeeToken::Settlement,
+                    amount: FeeAmount::Flat {
+                        amount: Uint128::new(4),
+                    },
+                }),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetExpiryConfig {
+                config: Some(ExpiryConfig {
+                    ttl: 100,
+                    reward: Some(Uint128::new(10)),
+                    burn: false,
+                }),
+            },
+        )
+        .unwrap();
+
+        for token_id in ["1", "2", "3"] {
+            mint(deps.as_mut(), &creator, token_id, &owner, cu_data(100, 1)).unwrap();
+        }
+        let mut data = cu_data(100, 1);
+        data.created_at = env.block.time.plus_seconds(50);
+        mint(deps.as_mut(), &creator, "4", &owner, data).unwrap();
+
+        env.block.time = env.block.time.plus_seconds(100);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: "1".to_string(),
+                extension: ConsumptionUnitExtensionUpdate::Nominate {},
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Expired { .. }));
+
+        // the reward is capped by the collected fees, the reserve is not used
+        let response = sweep(deps.as_mut(), &env, &sweeper);
+        assert_eq!(swept(&response), "1,2");
+        assert_eq!(
+            response.messages[0].msg,
+            BankMsg::Send {
+                to_address: sweeper.to_string(),
+                amount: vec![Coin::new(16u128, "settlement")],
+            }
+            .into()
+        );
+        assert_eq!(
+            query_reserve(deps.as_ref()).unwrap().general,
+            Uint128::new(15)
+        );
+
+        let response = sweep(deps.as_mut(), &env, &sweeper);
+        assert_eq!(swept(&response), "3");
+        assert!(response.messages.is_empty());
+
+        let response = sweep(deps.as_mut(), &env, &sweeper);
+        assert_eq!(swept(&response), "");
+        assert!(SWEEP_CURSOR.may_load(&deps.storage).unwrap().is_none());
+
+        let stats = query_pool_stats(deps.as_ref(), 1).unwrap().stats;
+        assert_eq!(stats.cu_count, 1);
+        let nft_info =
+            q_nft::query::query_nft_info::<ConsumptionUnitData>(&deps.storage, "2".to_string())
+                .unwrap();
+        assert_eq!(nft_info.extension.state, ConsumptionUnitState::Expired);
+
+        // archived CUs no longer earn rewards or vote, also once transferred
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: sweeper.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        let rewards = query_pending_rewards(deps.as_ref(), owner.to_string()).unwrap();
+        assert_eq!(rewards.weight, Uint128::new(100));
+        let rewards = query_pending_rewards(deps.as_ref(), sweeper.to_string()).unwrap();
+        assert!(rewards.weight.is_zero());
+        env.block.height += 1;
+        let power = query_voting_power(deps.as_ref(), &env, owner.to_string(), None).unwrap();
+        assert_eq!(power.total, Uint128::new(100));
+        let total = query_total_power(deps.as_ref(), &env, None).unwrap();
+        assert_eq!(total.total, Uint128::new(100));
+
+        // the ttl must be non-zero and representable
+        for ttl in [0, u64::MAX] {
+            let err = execute(
+                deps.as_mut(),
+                env.clone(),
+                message_info(&creator, &[]),
+                ExecuteMsg::SetExpiryConfig {
+                    config: Some(ExpiryConfig {
+                        ttl,
+                        reward: None,
+                        burn: true,
+                    }),
+                },
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::InvalidExpiryConfig {}));
+        }
+        let never = ExpiryConfig {
+            ttl: u64::MAX,
+            reward: None,
+            burn: false,
+        };
+        assert!(!never.is_expired(&cu_data(100, 1), &env));
+
+        // archived CUs are burned once burning is enabled
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetExpiryConfig {
+                config: Some(ExpiryConfig {
+                    ttl: 100,
+                    reward: None,
+                    burn: true,
+                }),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sweeper, &[]),
+            ExecuteMsg::SweepExpired { limit: None },
+        )
+        .unwrap();
+        let count = q_nft::query::query_num_tokens(deps.as_ref().storage).unwrap();
+        assert_eq!(count.count, 1);
+        assert_eq!(
+            query_pool_stats(deps.as_ref(), 1).unwrap().stats.cu_count,
+            1
+        );
+        let rewards = query_pending_rewards(deps.as_ref(), owner.to_string()).unwrap();
+        assert_eq!(rewards.weight, Uint128::new(100));
+        env.block.height += 1;
+        let total = query_total_power(deps.as_ref(), &env, None).unwrap();
+        assert_eq!(total.total, Uint128::new(100));
+    }
+}
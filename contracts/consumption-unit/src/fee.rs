@@ -0,0 +1,330 @@
+use crate::error::ContractError;
+use crate::msg::{CollectedFeesResponse, MintFeeResponse};
+use crate::reserve::payment;
+use crate::state::{COLLECTED_FEES, MINT_DEPOSITS, MINT_FEE};
+use crate::types::{CUConfig, CollectedFee, ConsumptionUnitData, MintFeeConfig};
+use cosmwasm_std::{
+    Deps, DepsMut, Env, Event, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128,
+};
+use cw20::Denom;
+use q_nft::execute::assert_creator;
+use q_nft::state::Cw721Config;
+use q_utils::asset::{denom_to_string, Asset};
+
+fn load_config(storage: &dyn Storage) -> StdResult<CUConfig> {
+    Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+        .collection_config
+        .load(storage)
+}
+
+/// Collects the mint fee due for a CU of `consumption_value`.
+/// The fee is taken from the funds sent along or, if no funds were sent,
+/// from the mint deposit of the sender.
+pub fn collect_mint_fee(
+    storage: &mut dyn Storage,
+    info: &MessageInfo,
+    consumption_value: Uint128,
+) -> Result<Uint128, ContractError> {
+    let Some(fee_config) = MINT_FEE.may_load(storage)? else {
+        return Ok(Uint128::zero());
+    };
+    let config = load_config(storage)?;
+    let denom = fee_config.denom(&config);
+    let fee = fee_config.fee_for(consumption_value)?;
+
+    let paid = payment(info, denom)?;
+    if !paid.is_zero() || fee.is_zero() {
+        if paid != fee {
+            return Err(ContractError::InvalidFee {
+                expected: fee,
+                received: paid,
+            });
+        }
+    } else if *denom == config.settlement_token {
+        let available = MINT_DEPOSITS
+            .may_load(storage, &info.sender)?
+            .unwrap_or_default();
+        if available < fee {
+            return Err(ContractError::InsufficientDeposit {
+                required: fee,
+                available,
+            });
+        }
+        MINT_DEPOSITS.save(storage, &info.sender, &(available - fee))?;
+    } else {
+        return Err(ContractError::InvalidFee {
+            expected: fee,
+            received: paid,
+        });
+    }
+
+    if !fee.is_zero() {
+        let key = denom_to_string(denom);
+        let mut collected = COLLECTED_FEES
+            .may_load(storage, &key)?
+            .unwrap_or(CollectedFee {
+                denom: denom.clone(),
+                amount: Uint128::zero(),
+            });
+        collected.amount = collected.amount.checked_add(fee)?;
+        COLLECTED_FEES.save(storage, &key, &collected)?;
+    }
+
+    Ok(fee)
+}
+
+/// Takes up to `max` from the fees collected in `denom` and returns the amount taken
+pub fn take_collected_fees(
+    storage: &mut dyn Storage,
+    denom: &Denom,
+    max: Uint128,
+) -> StdResult<Uint128> {
+    let key = denom_to_string(denom);
+    let Some(mut collected) = COLLECTED_FEES.may_load(storage, &key)? else {
+        return Ok(Uint128::zero());
+    };
+    let taken = collected.amount.min(max);
+    collected.amount -= taken;
+    COLLECTED_FEES.save(storage, &key, &collected)?;
+    Ok(taken)
+}
+
+pub fn execute_set_mint_fee(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    fee: Option<MintFeeConfig>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let mut event = Event::new("consumption-unit::set_mint_fee");
+    match fee {
+        Some(fee) => {
+            // CW20 fees can only be paid from mint deposits, which are held in the settlement token
+            let config = load_config(deps.storage)?;
+            let denom = fee.denom(&config);
+            if matches!(denom, Denom::Cw20(_)) && *denom != config.settlement_token {
+                return Err(ContractError::UnsupportedToken {});
+            }
+            event = event.add_attribute("denom", denom_to_string(denom));
+            MINT_FEE.save(deps.storage, &fee)?;
+        }
+        None => MINT_FEE.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::set_mint_fee")
+        .add_event(event))
+}
+
+/// Sends all collected fees to `recipient`, or to the sender if not set
+pub fn execute_withdraw_fees(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender.clone(),
+    };
+
+    let collected = COLLECTED_FEES
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut response = Response::new().add_attribute("action", "consumption-unit::withdraw_fees");
+    let mut event = Event::new("consumption-unit::withdraw_fees")
+        .add_attribute("recipient", recipient.to_string());
+    for (key, fee) in collected {
+        COLLECTED_FEES.remove(deps.storage, &key);
+        if fee.amount.is_zero() {
+            continue;
+        }
+        response =
+            response.add_message(Asset::new(fee.denom, fee.amount).transfer_msg(&recipient)?);
+        event = event.add_attribute(key, fee.amount.to_string());
+    }
+
+    if response.messages.is_empty() {
+        return Err(ContractError::NoFeesToWithdraw {});
+    }
+
+    Ok(response.add_event(event))
+}
+
+pub fn query_mint_fee(deps: Deps, consumption_value: Uint128) -> StdResult<MintFeeResponse> {
+    let config = load_config(deps.storage)?;
+    match MINT_FEE.may_load(deps.storage)? {
+        Some(fee) => Ok(MintFeeResponse {
+            denom: fee.denom(&config).clone(),
+            amount: fee
+                .fee_for(consumption_value)
+                .map_err(|err| StdError::generic_err(err.to_string()))?,
+            config: Some(fee),
+        }),
+        None => Ok(MintFeeResponse {
+            denom: config.settlement_token,
+            amount: Uint128::zero(),
+            config: None,
+        }),
+    }
+}
+
+pub fn query_collected_fees(deps: Deps) -> StdResult<CollectedFeesResponse> {
+    let fees = COLLECTED_FEES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, fee)| fee))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(CollectedFeesResponse { fees })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ExecuteMsg, ReceiveMsg};
+    use crate::testing::{cu_data, setup};
+    use crate::types::{FeeAmount, FeeToken};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{coins, BankMsg, Coin, Decimal};
+
+    fn mint_msg(token_id: &str, owner: &str) -> ExecuteMsg {
+        ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            extension: cu_data(1_000, 1),
+        }
+    }
+
+    #[test]
+    fn test_proportional_native_fee() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner").to_string();
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetMintFee {
+                fee: Some(MintFeeConfig {
+                    token: FeeToken::Native,
+                    amount: FeeAmount::Proportional {
+                        rate: Decimal::permille(15),
+                    },
+                }),
+            },
+        )
+        .unwrap();
+
+        let fee = query_mint_fee(deps.as_ref(), Uint128::new(1_000)).unwrap();
+        assert_eq!(fee.denom, Denom::Native("native".to_string()));
+        assert_eq!(fee.amount, Uint128::new(15));
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &coins(10, "native")),
+            mint_msg("1", &owner),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFee { .. }));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &coins(15, "native")),
+            mint_msg("1", &owner),
+        )
+        .unwrap();
+
+        let response = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &[]),
+            ExecuteMsg::WithdrawFees { recipient: None },
+        )
+        .unwrap();
+        assert_eq!(
+            response.messages[0].msg,
+            BankMsg::Send {
+                to_address: creator.to_string(),
+                amount: vec![Coin::new(15u128, "native")],
+            }
+            .into()
+        );
+        assert!(query_collected_fees(deps.as_ref()).unwrap().fees.is_empty());
+    }
+
+    #[test]
+    fn test_flat_fee_from_deposit() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner").to_string();
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetMintFee {
+                fee: Some(MintFeeConfig {
+                    token: FeeToken::Settlement,
+                    amount: FeeAmount::Flat {
+                        amount: Uint128::new(5),
+                    },
+                }),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &coins(10, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::PayForMint {}),
+        )
+        .unwrap();
+
+        for token_id in ["1", "2"] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                message_info(&creator, &[]),
+                mint_msg(token_id, &owner),
+            )
+            .unwrap();
+        }
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &[]),
+            mint_msg("3", &owner),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientDeposit { available, .. } if available.is_zero()
+        ));
+
+        let fees = query_collected_fees(deps.as_ref()).unwrap().fees;
+        assert_eq!(
+            fees,
+            vec![CollectedFee {
+                denom: Denom::Native("settlement".to_string()),
+                amount: Uint128::new(10),
+            }]
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use crate::error::ContractError;
+use crate::msg::{FrozenAddress, FrozenAddressesResponse, GuardiansResponse, IsFrozenResponse};
+use crate::state::{FROZEN, GUARDIANS};
+use crate::types::FreezeInfo;
+use cosmwasm_std::{
+    Addr, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, Response, StdResult, Storage,
+};
+use cw_storage_plus::Bound;
+use q_nft::execute::assert_creator;
+use q_nft::query::{DEFAULT_LIMIT, MAX_LIMIT};
+
+/// Fails if the address is frozen, called in every execute path initiated by a CU owner
+pub fn assert_not_frozen(storage: &dyn Storage, address: &Addr) -> Result<(), ContractError> {
+    if FROZEN.has(storage, address) {
+        return Err(ContractError::Frozen {
+            address: address.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Fails if a CU of `holder` may not be moved by `sender`: either of them is frozen.
+/// `holder` is the depositor of escrowed CUs (see `escrow::holder`), so the CUs of a frozen
+/// holder cannot be sold through an escrow contract but can always return to the holder.
+pub fn assert_can_transfer(
+    storage: &dyn Storage,
+    sender: &Addr,
+    holder: &Addr,
+    recipient: &Addr,
+) -> Result<(), ContractError> {
+    if recipient == holder {
+        return Ok(());
+    }
+    assert_not_frozen(storage, sender)?;
+    assert_not_frozen(storage, holder)
+}
+
+fn assert_creator_or_guardian(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    if GUARDIANS.has(storage, sender) || assert_creator(storage, sender).is_ok() {
+        return Ok(());
+    }
+    Err(ContractError::NotCreatorOrGuardian {})
+}
+
+pub fn execute_add_guardian(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    guardian: String,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+    let guardian = deps.api.addr_validate(&guardian)?;
+    GUARDIANS.save(deps.storage, &guardian, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::add_guardian")
+        .add_event(
+            Event::new("consumption-unit::add_guardian")
+                .add_attribute("guardian", guardian.to_string()),
+        ))
+}
+
+pub fn execute_remove_guardian(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    guardian: String,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+    let guardian = deps.api.addr_validate(&guardian)?;
+    GUARDIANS.remove(deps.storage, &guardian);
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::remove_guardian")
+        .add_event(
+            Event::new("consumption-unit::remove_guardian")
+                .add_attribute("guardian", guardian.to_string()),
+        ))
+}
+
+/// Freezes the address, freezing it again replaces the reason
+pub fn execute_freeze(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    address: String,
+    reason: String,
+) -> Result<Response, ContractError> {
+    assert_creator_or_guardian(deps.storage, &info.sender)?;
+    let address = deps.api.addr_validate(&address)?;
+    FROZEN.save(
+        deps.storage,
+        &address,
+        &FreezeInfo {
+            reason: reason.clone(),
+            frozen_by: info.sender.clone(),
+            frozen_at: env.block.time,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::freeze")
+        .add_event(
+            Event::new("consumption-unit::freeze")
+                .add_attribute("address", address.to_string())
+                .add_attribute("frozen_by", info.sender.to_string())
+                .add_attribute("reason", reason),
+        ))
+}
+
+pub fn execute_unfreeze(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_creator_or_guardian(deps.storage, &info.sender)?;
+    let address = deps.api.addr_validate(&address)?;
+    FROZEN.remove(deps.storage, &address);
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::unfreeze")
+        .add_event(
+            Event::new("consumption-unit::unfreeze")
+                .add_attribute("address", address.to_string())
+                .add_attribute("unfrozen_by", info.sender.to_string()),
+        ))
+}
+
+pub fn query_guardians(deps: Deps) -> StdResult<GuardiansResponse> {
+    let guardians = GUARDIANS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(GuardiansResponse { guardians })
+}
+
+pub fn query_is_frozen(deps: Deps, address: String) -> StdResult<IsFrozenResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let info = FROZEN.may_load(deps.storage, &address)?;
+    Ok(IsFrozenResponse {
+        address,
+        frozen: info.is_some(),
+        info,
+    })
+}
+
+/// Returns the frozen addresses ordered by address
+pub fn query_frozen_addresses(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<FrozenAddressesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+
+    let addresses = FROZEN
+        .range(
+            deps.storage,
+            start.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(address, info)| FrozenAddress { address, info }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FrozenAddressesResponse { addresses })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg};
+    use crate::testing::{cu_data, mint, setup};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::to_json_binary;
+    use cw20::Denom;
+
+    fn exec(deps: DepsMut, sender: &Addr, msg: ExecuteMsg) -> Result<Response, ContractError> {
+        execute(deps, mock_env(), message_info(sender, &[]), msg)
+    }
+
+    #[test]
+    fn test_freeze() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let guardian = deps.api.addr_make("guardian");
+        let holder = deps.api.addr_make("holder");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        mint(deps.as_mut(), &creator, "1", &holder, cu_data(100, 1)).unwrap();
+        let freeze_msg = ExecuteMsg::FreezeAddress {
+            address: holder.to_string(),
+            reason: "court order".to_string(),
+        };
+
+        let err = exec(deps.as_mut(), &guardian, freeze_msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::NotCreatorOrGuardian {}));
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::AddGuardian {
+                guardian: guardian.to_string(),
+            },
+        )
+        .unwrap();
+        exec(deps.as_mut(), &guardian, freeze_msg).unwrap();
+        let frozen = query_is_frozen(deps.as_ref(), holder.to_string()).unwrap();
+        assert!(frozen.frozen);
+        assert_eq!(frozen.info.unwrap().frozen_by, guardian);
+
+        let transfer_msg = ExecuteMsg::TransferNft {
+            recipient: creator.to_string(),
+            token_id: "1".to_string(),
+        };
+        for msg in [
+            transfer_msg.clone(),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: "1".to_string(),
+                extension: ConsumptionUnitExtensionUpdate::UpdatePool {
+                    new_commitment_tier_id: 2,
+                },
+            },
+            ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+            ExecuteMsg::ClaimSettlement {
+                token_id: "1".to_string(),
+            },
+            ExecuteMsg::ClaimVested {},
+            ExecuteMsg::ClaimRewards {},
+        ] {
+            let err = exec(deps.as_mut(), &holder, msg).unwrap_err();
+            assert!(matches!(err, ContractError::Frozen { .. }));
+        }
+        assert_eq!(
+            query_frozen_addresses(deps.as_ref(), None, None)
+                .unwrap()
+                .addresses
+                .len(),
+            1
+        );
+
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::UnfreezeAddress {
+                address: holder.to_string(),
+            },
+        )
+        .unwrap();
+        exec(deps.as_mut(), &holder, transfer_msg).unwrap();
+        assert!(query_frozen_addresses(deps.as_ref(), None, None)
+            .unwrap()
+            .addresses
+            .is_empty());
+    }
+
+    #[test]
+    fn test_frozen_depositor() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let holder = deps.api.addr_make("holder");
+        let buyer = deps.api.addr_make("buyer");
+        let marketplace = deps.api.addr_make("marketplace");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::AddEscrowContract {
+                contract: marketplace.to_string(),
+            },
+        )
+        .unwrap();
+        mint(deps.as_mut(), &creator, "1", &holder, cu_data(100, 1)).unwrap();
+        exec(
+            deps.as_mut(),
+            &holder,
+            ExecuteMsg::SendNft {
+                contract: marketplace.to_string(),
+                token_id: "1".to_string(),
+                msg: to_json_binary("list").unwrap(),
+            },
+        )
+        .unwrap();
+        exec(
+            deps.as_mut(),
+            &creator,
+            ExecuteMsg::FreezeAddress {
+                address: holder.to_string(),
+                reason: "court order".to_string(),
+            },
+        )
+        .unwrap();
+
+        // the escrow contract cannot sell the CU of a frozen depositor
+        let err = exec(
+            deps.as_mut(),
+            &marketplace,
+            ExecuteMsg::TransferNft {
+                recipient: buyer.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Frozen { address } if address == holder.as_str()));
+
+        // but can return it
+        exec(
+            deps.as_mut(),
+            &marketplace,
+            ExecuteMsg::TransferNft {
+                recipient: holder.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,162 @@
+use crate::error::ContractError;
+use crate::msg::TokenHistoryResponse;
+use crate::state::{TOKEN_HISTORY, TOKEN_HISTORY_LENGTH};
+use crate::types::{HistoryAction, HistoryEntry};
+use cosmwasm_std::{Addr, Deps, Env, Order, StdResult, Storage};
+use cw_storage_plus::Bound;
+use q_nft::query::{DEFAULT_LIMIT, MAX_LIMIT};
+
+/// Appends an entry to the history of the token, entries are kept after the token is burned
+pub fn record(
+    storage: &mut dyn Storage,
+    env: &Env,
+    token_id: &str,
+    actor: &Addr,
+    action: HistoryAction,
+) -> Result<(), ContractError> {
+    let index = TOKEN_HISTORY_LENGTH
+        .may_load(storage, token_id)?
+        .unwrap_or_default();
+    let entry = HistoryEntry {
+        index,
+        height: env.block.height,
+        time: env.block.time,
+        actor: actor.clone(),
+        action,
+    };
+    TOKEN_HISTORY.save(storage, (token_id, index), &entry)?;
+    TOKEN_HISTORY_LENGTH.save(storage, token_id, &(index + 1))?;
+    Ok(())
+}
+
+/// Fails if the token id has a history, so that a burned token id is not minted again
+/// and its history does not merge with the one of the new token
+pub fn assert_unused(storage: &dyn Storage, token_id: &str) -> Result<(), ContractError> {
+    if TOKEN_HISTORY_LENGTH.has(storage, token_id) {
+        return Err(ContractError::TokenIdUsed {
+            token_id: token_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Returns the history of the token ordered from the oldest entry
+pub fn query_token_history(
+    deps: Deps,
+    token_id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TokenHistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let entries = TOKEN_HISTORY
+        .prefix(&token_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TokenHistoryResponse { token_id, entries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg};
+    use crate::testing::{cu_data, mint, setup};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::Decimal;
+    use cw20::Denom;
+
+    #[test]
+    fn test_token_history() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let alice = deps.api.addr_make("alice");
+        let bob = deps.api.addr_make("bob");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        mint(deps.as_mut(), &creator, "1", &alice, cu_data(100, 1)).unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&alice, &[]),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: "1".to_string(),
+                extension: ConsumptionUnitExtensionUpdate::UpdatePool {
+                    new_commitment_tier_id: 2,
+                },
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&alice, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: bob.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&bob, &[]),
+            ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+
+        // the history survives burning the token
+        let history = query_token_history(deps.as_ref(), "1".to_string(), None, None).unwrap();
+        let actions = history
+            .entries
+            .iter()
+            .map(|entry| (entry.actor.clone(), entry.action.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actions,
+            vec![
+                (
+                    creator.clone(),
+                    HistoryAction::Mint {
+                        owner: alice.clone()
+                    }
+                ),
+                (
+                    alice.clone(),
+                    HistoryAction::TierChange {
+                        old_tier: 1,
+                        new_tier: 2,
+                        old_floor_price: Decimal::percent(50),
+                        new_floor_price: Decimal::percent(50),
+                    }
+                ),
+                (
+                    alice.clone(),
+                    HistoryAction::Transfer {
+                        from: alice.clone(),
+                        to: bob.clone(),
+                    }
+                ),
+                (bob.clone(), HistoryAction::Burn { owner: bob.clone() }),
+            ]
+        );
+        assert_eq!(history.entries[0].height, mock_env().block.height);
+
+        let page = query_token_history(deps.as_ref(), "1".to_string(), Some(1), Some(1)).unwrap();
+        assert_eq!(page.entries.len(), 1);
+        assert_eq!(page.entries[0].index, 2);
+
+        // the burned token id cannot be minted again
+        let err = mint(deps.as_mut(), &creator, "1", &alice, cu_data(100, 1)).unwrap_err();
+        assert!(matches!(err, ContractError::TokenIdUsed { token_id } if token_id == "1"));
+    }
+}
@@ -1,5 +1,27 @@
+pub mod compliance;
 pub mod contract;
+pub mod dispute;
 pub mod error;
+pub mod escrow;
+pub mod expiry;
+pub mod fee;
+pub mod freeze;
+pub mod history;
+pub mod migration;
 pub mod msg;
+pub mod oracle;
+pub mod penalty;
+pub mod pool;
 pub mod query;
+pub mod reporter;
+pub mod reserve;
+pub mod rewards;
+pub mod round;
+pub mod settlement;
+pub mod state;
+pub mod tier;
 pub mod types;
+pub mod voting;
+
+#[cfg(test)]
+mod testing;
@@ -0,0 +1,284 @@
+use crate::error::ContractError;
+use crate::state::{POOL_STATS, REWARD_STATE, TIER_TOTALS};
+use crate::types::{
+    CUConfig, ConsumptionUnitData, ConsumptionUnitNft, ConsumptionUnitState, PoolStats,
+};
+use crate::{escrow, rewards, voting};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Env, Order, StdResult, Storage};
+use cw20::Denom;
+use cw_storage_plus::Item;
+use q_nft::state::Cw721Config;
+use std::collections::BTreeMap;
+
+/// `CUConfig` of deployments from before multiple price oracles were supported
+#[cw_serde]
+struct LegacyCUConfig {
+    settlement_token: Denom,
+    native_token: Denom,
+    price_oracle: Addr,
+}
+
+/// Same storage key as `Cw721Config::collection_config`
+const LEGACY_CONFIG: Item<LegacyCUConfig> = Item::new("cw721_collection_config");
+
+fn load_tokens(storage: &dyn Storage) -> StdResult<Vec<(String, ConsumptionUnitNft)>> {
+    Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+        .nft_info
+        .range(storage, None, None, Order::Ascending)
+        .collect()
+}
+
+/// Unexpired CUs, expired ones left their pool, rewards and votes when swept
+fn load_live_tokens(storage: &dyn Storage) -> StdResult<Vec<(String, ConsumptionUnitNft)>> {
+    let mut tokens = load_tokens(storage)?;
+    tokens.retain(|(_, nft_info)| nft_info.extension.state != ConsumptionUnitState::Expired);
+    Ok(tokens)
+}
+
+/// Converts a legacy config with a single price oracle, returns whether it was converted
+pub fn migrate_config(storage: &mut dyn Storage) -> Result<bool, ContractError> {
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    if config.collection_config.load(storage).is_ok() {
+        return Ok(false);
+    }
+
+    let legacy = LEGACY_CONFIG.load(storage)?;
+    config.collection_config.save(
+        storage,
+        &CUConfig {
+            settlement_token: legacy.settlement_token,
+            native_token: legacy.native_token,
+            price_oracles: vec![legacy.price_oracle],
+            oracle_quorum: 1,
+        },
+    )?;
+    Ok(true)
+}
+
+/// Computes the pool stats from all existing CUs if none are recorded yet.
+/// Capacities are not enforced, CUs minted before they were set stay in their tier.
+/// Returns the number of tiers backfilled.
+pub fn backfill_pool_stats(storage: &mut dyn Storage) -> Result<usize, ContractError> {
+    if !POOL_STATS.is_empty(storage) {
+        return Ok(0);
+    }
+
+    let mut pools = BTreeMap::<u16, PoolStats>::new();
+    for (_, nft_info) in load_tokens(storage)? {
+        let data = nft_info.extension;
+        // expired CUs left their pool when they were swept
+        if data.state == ConsumptionUnitState::Expired {
+            continue;
+        }
+        let stats = pools.entry(data.commitment_tier).or_default();
+        stats.cu_count += 1;
+        stats.total_consumption_value = stats
+            .total_consumption_value
+            .checked_add(data.consumption_value)?;
+        stats.total_floor_value = stats.total_floor_value.checked_add(data.floor_value()?)?;
+    }
+
+    for (tier, stats) in &pools {
+        POOL_STATS.save(storage, *tier, stats)?;
+    }
+    Ok(pools.len())
+}
+
+/// Adds the reward weights of all unexpired CUs if no reward state is recorded yet.
+/// Returns the number of CUs backfilled.
+pub fn backfill_reward_weights(storage: &mut dyn Storage) -> Result<usize, ContractError> {
+    if REWARD_STATE.exists(storage) {
+        return Ok(0);
+    }
+
+    let tokens = load_live_tokens(storage)?;
+    for (token_id, nft_info) in &tokens {
+        let holder = escrow::holder(storage, token_id, &nft_info.owner)?;
+        rewards::add_weight(storage, &holder, nft_info.extension.consumption_value)?;
+    }
+    Ok(tokens.len())
+}
+
+/// Records the voting holdings of all unexpired CUs if none are recorded yet.
+/// Returns the number of CUs backfilled.
+pub fn backfill_holdings(storage: &mut dyn Storage, env: &Env) -> Result<usize, ContractError> {
+    if TIER_TOTALS
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .is_some()
+    {
+        return Ok(0);
+    }
+
+    let tokens = load_live_tokens(storage)?;
+    for (token_id, nft_info) in &tokens {
+        let holder = escrow::holder(storage, token_id, &nft_info.owner)?;
+        voting::add_holding(storage, env, &holder, &nft_info.extension)?;
+    }
+    Ok(tokens.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, migrate};
+    use crate::msg::{ExecuteMsg, MigrateMsg};
+    use crate::pool::query_pool_stats;
+    use crate::state::{HOLDINGS, REWARD_ACCOUNTS};
+    use crate::testing::{cu_data, mint, setup};
+    use crate::voting::query_total_power;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{OwnedDeps, Uint128};
+    use q_nft::query::{query_balance_at_height, query_owner_of_at_height};
+
+    #[test]
+    fn test_migrate_legacy_state() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        let oracle = deps.api.addr_make("oracle");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(100, 1)).unwrap();
+        mint(deps.as_mut(), &creator, "2", &owner, cu_data(300, 1)).unwrap();
+        mint(deps.as_mut(), &creator, "3", &owner, cu_data(50, 2)).unwrap();
+
+        // state as left by a deployment without multiple oracles and pool stats
+        LEGACY_CONFIG
+            .save(
+                &mut deps.storage,
+                &LegacyCUConfig {
+                    settlement_token: Denom::Native("settlement".to_string()),
+                    native_token: Denom::Native("native".to_string()),
+                    price_oracle: oracle.clone(),
+                },
+            )
+            .unwrap();
+        POOL_STATS.clear(&mut deps.storage);
+        REWARD_STATE.remove(&mut deps.storage);
+        REWARD_ACCOUNTS.clear(&mut deps.storage);
+        for tier in [1, 2] {
+            HOLDINGS
+                .remove(&mut deps.storage, (&owner, tier), env.block.height)
+                .unwrap();
+            TIER_TOTALS
+                .remove(&mut deps.storage, tier, env.block.height)
+                .unwrap();
+        }
+
+        env.block.height += 1;
+        migrate(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::Migrate { snapshots: None },
+        )
+        .unwrap();
+
+        let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+            .collection_config
+            .load(&deps.storage)
+            .unwrap();
+        assert_eq!(config.price_oracles, vec![oracle]);
+        assert_eq!(config.oracle_quorum, 1);
+
+        let stats = query_pool_stats(deps.as_ref(), 1).unwrap().stats;
+        assert_eq!(stats.cu_count, 2);
+        assert_eq!(stats.total_consumption_value, Uint128::new(400));
+        assert_eq!(stats.total_floor_value, Uint128::new(200));
+        assert_eq!(
+            query_pool_stats(deps.as_ref(), 2).unwrap().stats.cu_count,
+            1
+        );
+
+        let total_weight = REWARD_STATE.load(&deps.storage).unwrap().total_weight;
+        assert_eq!(total_weight, Uint128::new(450));
+        assert_eq!(
+            REWARD_ACCOUNTS.load(&deps.storage, &owner).unwrap().weight,
+            total_weight
+        );
+
+        env.block.height += 1;
+        let power = query_total_power(deps.as_ref(), &env, None).unwrap().total;
+        assert_eq!(power, Uint128::new(450));
+
+        // migrating again leaves the state untouched
+        migrate(
+            deps.as_mut(),
+            env.clone(),
+            MigrateMsg::Migrate { snapshots: None },
+        )
+        .unwrap();
+        assert_eq!(query_pool_stats(deps.as_ref(), 1).unwrap().stats, stats);
+        assert_eq!(
+            REWARD_STATE.load(&deps.storage).unwrap().total_weight,
+            total_weight
+        );
+        env.block.height += 1;
+        assert_eq!(
+            query_total_power(deps.as_ref(), &env, None).unwrap().total,
+            power
+        );
+    }
+
+    #[test]
+    fn test_enable_snapshots() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let alice = deps.api.addr_make("alice");
+        let bob = deps.api.addr_make("bob");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        q_nft::execute::initialize_snapshots(&mut deps.storage, false).unwrap();
+        mint(deps.as_mut(), &creator, "1", &alice, cu_data(100, 1)).unwrap();
+        mint(deps.as_mut(), &creator, "2", &alice, cu_data(100, 1)).unwrap();
+
+        env.block.height += 1;
+        let migrate_msg = MigrateMsg::Migrate {
+            snapshots: Some(true),
+        };
+        let response = migrate(deps.as_mut(), env.clone(), migrate_msg.clone()).unwrap();
+        let recorded = &response.events[0].attributes.last().unwrap();
+        assert_eq!(
+            (recorded.key.as_str(), recorded.value.as_str()),
+            ("snapshots_recorded", "2")
+        );
+
+        env.block.height += 1;
+        let balance = |deps: &OwnedDeps<_, _, _>, owner: &Addr, height| {
+            query_balance_at_height(deps.as_ref(), owner.to_string(), height)
+                .unwrap()
+                .count
+        };
+        assert_eq!(balance(&deps, &alice, env.block.height), 2);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&alice, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: bob.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        assert_eq!(balance(&deps, &alice, env.block.height + 1), 1);
+        assert_eq!(balance(&deps, &bob, env.block.height + 1), 1);
+        let owner = query_owner_of_at_height(&deps.storage, "2".to_string(), env.block.height)
+            .unwrap()
+            .owner;
+        assert_eq!(owner, alice.to_string());
+
+        // enabling again records nothing
+        let response = migrate(deps.as_mut(), env, migrate_msg).unwrap();
+        let recorded = &response.events[0].attributes.last().unwrap();
+        assert_eq!(recorded.value, "0");
+    }
+}
@@ -1,22 +1,34 @@
-use crate::types::ConsumptionUnitData;
-use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
-use cw20::Denom;
+use crate::types::{
+    CUConfig, CollectedFee, ComplianceConfig, ConsumptionReport, ConsumptionUnitData, Dispute,
+    ExpiryConfig, FreezeInfo, HistoryEntry, MintFeeConfig, OracleConfig, PenaltyDestination,
+    PoolCapacity, PoolStats, Reporter, ReporterKey, RoundConfig, TierConfig, VestingConfig,
+    VestingSchedule,
+};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Binary, Decimal, Timestamp, Uint128};
+use cw2::ContractVersion;
+use cw20::{Cw20ReceiveMsg, Denom};
+use cw_ownable::Ownership;
 use q_nft::msg::Cw721InstantiateMsg;
+use q_nft::state::CollectionInfo;
 
 #[cw_serde]
 pub struct ConsumptionUnitCollectionExtension {
     pub settlement_token: Denom,
     pub native_token: Denom,
-    /// Address of the price Oracle to query floor prices
-    pub price_oracle: Addr,
+    /// Addresses of the price Oracles to query floor prices
+    pub price_oracles: Vec<Addr>,
+    /// Minimum number of Oracles which must report a fresh price
+    pub oracle_quorum: u32,
 }
 
 pub type InstantiateMsg = Cw721InstantiateMsg<ConsumptionUnitCollectionExtension>;
 
 #[cw_serde]
 pub enum ExecuteMsg {
-    /// Mint a new NFT, can only be called by the contract minter
+    /// Mint a new NFT, can only be called by the contract minter.
+    /// The mint fee, if configured, must be sent along or is taken from the minter's mint deposit.
+    /// Token ids of burned CUs cannot be minted again.
     Mint {
         /// Unique ID of the NFT
         token_id: String,
@@ -26,24 +38,448 @@ pub enum ExecuteMsg {
         extension: ConsumptionUnitData,
     },
 
-    /// Burn an NFT the sender has access to
+    /// Burn an NFT owned by the sender
     Burn { token_id: String },
 
+    /// Transfer an NFT owned by the sender to `recipient`
+    TransferNft { recipient: String, token_id: String },
+
+    /// Transfer an NFT owned by the sender to `contract` and call its `ReceiveNft` hook with `msg`
+    SendNft {
+        contract: String,
+        token_id: String,
+        msg: Binary,
+    },
+
     /// Extension msg
     UpdateNftInfo {
         token_id: String,
         extension: ConsumptionUnitExtensionUpdate,
     },
+
+    /// Updates the collection config, can only be called by the collection creator.
+    /// Fields set to `None` are left unchanged.
+    UpdateConfig {
+        /// Can only change while the contract holds no settlement tokens
+        settlement_token: Option<Denom>,
+        native_token: Option<Denom>,
+        /// Addresses of the price Oracles to query floor prices
+        price_oracles: Option<Vec<String>>,
+        /// Minimum number of Oracles which must report a fresh price
+        oracle_quorum: Option<u32>,
+    },
+
+    /// Receives settlement tokens from the configured CW20 contract.
+    /// The embedded message must be a `ReceiveMsg`.
+    Receive(Cw20ReceiveMsg),
+
+    /// Same as `Receive`, but for a native settlement token sent along as funds
+    Deposit(ReceiveMsg),
+
+    /// Sets or removes (`None`) the capacity caps of a commitment tier,
+    /// can only be called by the collection creator.
+    SetPoolCapacity {
+        tier: u16,
+        capacity: Option<PoolCapacity>,
+    },
+
+    /// Sets the switching rules of a commitment tier,
+    /// can only be called by the collection creator.
+    SetTierConfig { tier: u16, config: TierConfig },
+
+    /// Sets the schedule of the raffle rounds, can only be called by the collection creator.
+    /// The running round keeps its window, the new schedule takes over from the next round
+    /// and must not start before the running round ends.
+    SetRoundConfig { config: RoundConfig },
+
+    /// Marks the given CUs nominated in the round as selected,
+    /// can only be called by the collection creator once nominations for the round are closed.
+    SelectWinners {
+        round_id: u64,
+        token_ids: Vec<String>,
+    },
+
+    /// Sets or removes (`None`) the mint fee, can only be called by the collection creator
+    SetMintFee { fee: Option<MintFeeConfig> },
+
+    /// Sends all collected fees to the recipient (the sender by default),
+    /// can only be called by the collection creator
+    WithdrawFees { recipient: Option<String> },
+
+    /// Registers or updates a consumption reporter,
+    /// can only be called by the collection creator
+    RegisterReporter {
+        id: String,
+        key: ReporterKey,
+        /// Nominal currencies the reporter may report, any currency if empty
+        allowed_currencies: Vec<String>,
+        /// Maximum total `consumption_value` the reporter may mint
+        quota: Option<Uint128>,
+    },
+
+    /// Removes a consumption reporter, can only be called by the collection creator
+    RemoveReporter { id: String },
+
+    /// Mints a new NFT from the report of a registered reporter.
+    /// The signature over the canonical report digest is required for key reporters,
+    /// so reports can be relayed by anyone. Each report can be minted once before it expires.
+    /// The floor price is based on the oracle price checked as set by `SetOracleConfig`,
+    /// or on the median price of the price oracles if no checks are configured.
+    MintFromReport {
+        report: ConsumptionReport,
+        signature: Option<Binary>,
+    },
+
+    /// Sets or removes (`None`) the sanity checks of oracle prices,
+    /// can only be called by the collection creator.
+    /// Floor prices are recomputed from the oracle price on tier changes only while set.
+    SetOracleConfig { config: Option<OracleConfig> },
+
+    /// Fetches the oracle price and records it for the TWAP, can be called by anyone
+    ObservePrice {},
+
+    /// Sets or removes (`None`) the expiry of unselected CUs,
+    /// can only be called by the collection creator
+    SetExpiryConfig { config: Option<ExpiryConfig> },
+
+    /// Burns or archives up to `limit` expired CUs, can be called by anyone.
+    /// Each call continues where the previous one stopped, the sweeper is paid the
+    /// configured reward per swept CU as long as the collected mint fees cover it.
+    SweepExpired { limit: Option<u32> },
+
+    /// Sets or removes (`None`) the vesting of settlement payouts,
+    /// can only be called by the collection creator
+    SetVestingConfig { config: Option<VestingConfig> },
+
+    /// Claims the settlement of a selected CU owned by the sender and burns the CU.
+    /// The floor value of the CU is paid from the reserve of its tier, topped up from the
+    /// general reserve. While vesting is configured a vesting schedule is created instead.
+    ClaimSettlement { token_id: String },
+
+    /// Releases the unlocked part of all vesting settlements of the sender
+    ClaimVested {},
+
+    /// Sends the rewards accrued by the CUs of the sender
+    ClaimRewards {},
+
+    /// Registers a contract holding CUs on behalf of their depositors, can only be called
+    /// by the collection creator. CUs sent to it afterwards keep earning rewards
+    /// for the depositor until they leave the escrow.
+    AddEscrowContract { contract: String },
+
+    /// Removes an escrow contract, can only be called by the collection creator.
+    /// CUs already escrowed stay attributed to their depositors.
+    RemoveEscrowContract { contract: String },
+
+    /// Transfers, accepts or renounces the creator role, e.g. to hand the config over
+    /// to a governance contract
+    UpdateCreatorOwnership(cw_ownable::Action),
+
+    /// Registers an auditor, can only be called by the collection creator
+    AddAuditor { auditor: String },
+
+    /// Removes an auditor, can only be called by the collection creator.
+    /// Disputes opened by the auditor stay open.
+    RemoveAuditor { auditor: String },
+
+    /// Disputes the consumption records of a CU, can only be called by an auditor.
+    /// The CU is frozen in the `Disputed` state until the dispute is resolved.
+    OpenDispute { token_id: String, reason: String },
+
+    /// Resolves the dispute of a CU by burning it or by restoring its previous state,
+    /// can only be called by the collection creator
+    ResolveDispute {
+        token_id: String,
+        burn: bool,
+        reason: String,
+    },
+
+    /// Sets or removes (`None`) the compliance mode, can only be called by the collection creator.
+    /// While set, CUs can only be minted and transferred to allowed addresses,
+    /// including contracts receiving CUs through `SendNft`.
+    SetComplianceConfig { config: Option<ComplianceConfig> },
+
+    /// Adds and removes addresses of the local allowlist,
+    /// can only be called by the collection creator
+    UpdateAllowlist {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+
+    /// Registers a guardian, can only be called by the collection creator
+    AddGuardian { guardian: String },
+
+    /// Removes a guardian, can only be called by the collection creator
+    RemoveGuardian { guardian: String },
+
+    /// Freezes a holder: its CUs cannot be transferred, burned, nominated, settled or
+    /// change tier, and it cannot claim payouts. Its CUs held by escrow contracts cannot
+    /// be transferred either, except back to the holder.
+    /// Can only be called by the collection creator or a guardian.
+    FreezeAddress { address: String, reason: String },
+
+    /// Unfreezes a holder, can only be called by the collection creator or a guardian
+    UnfreezeAddress { address: String },
+}
+
+/// Actions performed with the received settlement tokens
+#[cw_serde]
+pub enum ReceiveMsg {
+    /// Adds the tokens to the general reserve
+    FundReserve {},
+    /// Adds the tokens to the reserve of the given commitment tier
+    FundTier { tier: u16 },
+    /// Prepays mint costs of the sender
+    PayForMint {},
+    /// Same as `ConsumptionUnitExtensionUpdate::UpdatePool`, paying the switching fee
+    UpdatePool {
+        token_id: String,
+        new_commitment_tier_id: u16,
+    },
+    /// Distributes the tokens to all CU holders pro rata to their `consumption_value`
+    DistributeRewards {},
 }
 
 #[cw_serde]
 pub enum ConsumptionUnitExtensionUpdate {
     /// Updates the pool id for the given NFT, can be performed by user only.
     /// When updating the pool a new price will be fetched.
+    /// The switching fee of the new tier, if any, must be sent along in the settlement token.
     UpdatePool { new_commitment_tier_id: u16 },
+    /// Nominates the NFT for the current raffle round, can be performed by user only
+    /// while nominations for the round are open.
+    Nominate {},
+}
+
+/// Full contract configuration: CU config together with the Cw721 collection info,
+/// minter/creator ownership and the cw2 contract version.
+#[cw_serde]
+pub struct ConfigResponse {
+    pub collection_info: CollectionInfo,
+    pub collection_config: CUConfig,
+    pub minter_ownership: Ownership<Addr>,
+    pub creator_ownership: Ownership<Addr>,
+    pub contract_version: ContractVersion,
+}
+
+#[cw_serde]
+pub struct TierReserve {
+    pub tier: u16,
+    pub amount: Uint128,
+}
+
+/// Settlement token balances held by the contract, split by purpose
+#[cw_serde]
+pub struct ReserveResponse {
+    pub settlement_token: Denom,
+    pub general: Uint128,
+    pub tiers: Vec<TierReserve>,
+}
+
+#[cw_serde]
+pub struct MintDepositResponse {
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct PoolStatsResponse {
+    pub tier: u16,
+    pub stats: PoolStats,
+    pub capacity: Option<PoolCapacity>,
+}
+
+#[cw_serde]
+pub struct AllPoolStatsResponse {
+    pub pools: Vec<PoolStatsResponse>,
+}
+
+#[cw_serde]
+pub struct MintFeeResponse {
+    pub config: Option<MintFeeConfig>,
+    pub denom: Denom,
+    /// Fee due for the queried `consumption_value`
+    pub amount: Uint128,
+}
+
+#[cw_serde]
+pub struct CollectedFeesResponse {
+    pub fees: Vec<CollectedFee>,
+}
+
+#[cw_serde]
+pub struct ReporterResponse {
+    pub id: String,
+    pub reporter: Reporter,
+}
+
+#[cw_serde]
+pub struct ReportersResponse {
+    pub reporters: Vec<ReporterResponse>,
+}
+
+#[cw_serde]
+pub struct RoundResponse {
+    pub id: u64,
+    pub start: Timestamp,
+    /// Nominations are accepted until this time, followed by the draw
+    pub nomination_end: Timestamp,
+    pub end: Timestamp,
+    pub nominated: u64,
+    pub selected: u64,
+}
+
+/// Query interface of the price oracle
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum OracleQueryMsg {
+    /// Returns the price of the native coin in settlement tokens
+    #[returns(PriceResponse)]
+    Price {},
+}
+
+/// Query interface of the compliance registry
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum RegistryQueryMsg {
+    /// Returns whether the address may hold CUs
+    #[returns(IsAllowedResponse)]
+    IsAllowed { address: String },
+}
+
+#[cw_serde]
+pub struct IsAllowedResponse {
+    pub allowed: bool,
+}
+
+#[cw_serde]
+pub struct AllowlistResponse {
+    pub addresses: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct PenaltyResponse {
+    /// Penalty in settlement tokens due if the CU leaves its tier now
+    pub penalty: Uint128,
+    /// End of the lock period of the CU's tier
+    pub unlocked_at: Timestamp,
+    /// Where the penalty goes, `None` if no penalty is due
+    pub destination: Option<PenaltyDestination>,
+}
+
+#[cw_serde]
+pub struct VestingScheduleResponse {
+    pub owner: Addr,
+    pub schedules: Vec<VestingSchedule>,
+    /// Sum of all vesting payouts
+    pub total: Uint128,
+    /// Part of the payouts already released
+    pub released: Uint128,
+    /// Part of the payouts unlocked and not released yet
+    pub claimable: Uint128,
+}
+
+#[cw_serde]
+pub struct PendingRewardsResponse {
+    pub owner: Addr,
+    /// Sum of the `consumption_value` of the CUs held
+    pub weight: Uint128,
+    /// Rewards accrued and not claimed yet
+    pub pending: Uint128,
+}
+
+#[cw_serde]
+pub struct TierHolding {
+    pub tier: u16,
+    pub consumption_value: Uint128,
+}
+
+#[cw_serde]
+pub struct VotingPowerResponse {
+    pub address: Addr,
+    pub height: u64,
+    /// Holdings per commitment tier, tiers without holdings are omitted
+    pub tiers: Vec<TierHolding>,
+    /// Sum of the `consumption_value` of all holdings
+    pub total: Uint128,
+}
+
+#[cw_serde]
+pub struct TotalPowerResponse {
+    pub height: u64,
+    /// Holdings of all addresses per commitment tier, tiers without holdings are omitted
+    pub tiers: Vec<TierHolding>,
+    pub total: Uint128,
+}
+
+#[cw_serde]
+pub struct TwapResponse {
+    pub window: u64,
+    pub price: Decimal,
+}
+
+#[cw_serde]
+pub struct PriceResponse {
+    pub price: Decimal,
+    pub updated_at: Timestamp,
 }
 
 #[cw_serde]
 pub enum MigrateMsg {
-    Migrate {},
+    Migrate {
+        /// Enables ownership snapshots from the current block if `true`,
+        /// they cannot be disabled once enabled
+        snapshots: Option<bool>,
+    },
+}
+
+#[cw_serde]
+pub struct EscrowContractsResponse {
+    pub contracts: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct TokenHistoryResponse {
+    pub token_id: String,
+    /// Entries ordered from the oldest
+    pub entries: Vec<HistoryEntry>,
+}
+
+#[cw_serde]
+pub struct AuditorsResponse {
+    pub auditors: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct DisputeResponse {
+    pub token_id: String,
+    pub dispute: Dispute,
+}
+
+#[cw_serde]
+pub struct DisputesResponse {
+    pub disputes: Vec<DisputeResponse>,
+}
+
+#[cw_serde]
+pub struct GuardiansResponse {
+    pub guardians: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct IsFrozenResponse {
+    pub address: Addr,
+    pub frozen: bool,
+    /// Reason and time of the freeze, `None` if not frozen
+    pub info: Option<FreezeInfo>,
+}
+
+#[cw_serde]
+pub struct FrozenAddress {
+    pub address: Addr,
+    pub info: FreezeInfo,
+}
+
+#[cw_serde]
+pub struct FrozenAddressesResponse {
+    pub addresses: Vec<FrozenAddress>,
 }
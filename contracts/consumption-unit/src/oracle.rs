@@ -0,0 +1,657 @@
+use crate::error::ContractError;
+use crate::msg::{OracleQueryMsg, PriceResponse, TwapResponse};
+use crate::state::{LAST_PRICE, ORACLE_CONFIG, PRICE_OBSERVATIONS};
+use crate::types::{AcceptedPrice, CUConfig, ConsumptionUnitData, OracleConfig, PriceObservation};
+use cosmwasm_std::{
+    Addr, Decimal, Decimal256, Deps, DepsMut, Env, Event, MessageInfo, Order, OverflowError,
+    OverflowOperation, Response, StdError, StdResult, Storage,
+};
+use cw_storage_plus::Bound;
+use q_nft::execute::assert_creator;
+use q_nft::state::Cw721Config;
+
+/// Observes the oracle price and returns the price floor prices are based on,
+/// the TWAP over the configured window or the spot price if no window is set.
+/// Returns `None` if no checks are configured, in which case oracle prices are not used.
+pub fn fetch_price(mut deps: DepsMut, env: &Env) -> Result<Option<Decimal>, ContractError> {
+    let Some(oracle_config) = ORACLE_CONFIG.may_load(deps.storage)? else {
+        return Ok(None);
+    };
+    let price = observe_price(deps.branch(), env, &oracle_config)?;
+    if oracle_config.twap_window == 0 {
+        return Ok(Some(price));
+    }
+    Ok(Some(twap(deps.storage, env, oracle_config.twap_window)?))
+}
+
+/// Median of the prices of all price oracles, without any checks and without recording it.
+/// Used as the floor price basis of reported CUs while oracle checks are not configured.
+pub fn spot_median(deps: Deps, env: &Env) -> Result<Decimal, ContractError> {
+    let responses = query_prices(deps, env, None)?;
+    median(
+        responses
+            .into_iter()
+            .map(|(_, response)| response.price)
+            .collect(),
+    )
+}
+
+/// Queries all price oracles, those which fail to respond or report a price older than
+/// `max_age` seconds are skipped. Fails if less than the oracle quorum responded.
+fn query_prices(
+    deps: Deps,
+    env: &Env,
+    max_age: Option<u64>,
+) -> Result<Vec<(Addr, PriceResponse)>, ContractError> {
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+        .collection_config
+        .load(deps.storage)?;
+
+    let mut responses = vec![];
+    for oracle in config.price_oracles {
+        let Ok(response) = deps
+            .querier
+            .query_wasm_smart::<PriceResponse>(&oracle, &OracleQueryMsg::Price {})
+        else {
+            continue;
+        };
+        let age = env
+            .block
+            .time
+            .seconds()
+            .saturating_sub(response.updated_at.seconds());
+        if max_age.map_or(true, |max_age| age <= max_age) {
+            responses.push((oracle, response));
+        }
+    }
+
+    let sources = responses.len() as u32;
+    if sources < config.oracle_quorum {
+        return Err(ContractError::OracleQuorumNotMet {
+            sources,
+            quorum: config.oracle_quorum,
+        });
+    }
+    Ok(responses)
+}
+
+/// Queries all price oracles and aggregates their fresh prices into the median,
+/// which is verified against the configured checks and stored as the last accepted price.
+/// Oracles which fail to respond or report a stale price are skipped.
+fn observe_price(
+    deps: DepsMut,
+    env: &Env,
+    oracle_config: &OracleConfig,
+) -> Result<Decimal, ContractError> {
+    let responses = query_prices(deps.as_ref(), env, Some(oracle_config.max_price_age))?;
+    let price = median(
+        responses
+            .iter()
+            .map(|(_, response)| response.price)
+            .collect(),
+    )?;
+    validate_price(deps.as_ref(), env, oracle_config, price)?;
+
+    let updated_at = responses
+        .iter()
+        .map(|(_, response)| response.updated_at)
+        .min()
+        .unwrap_or(env.block.time);
+    LAST_PRICE.save(
+        deps.storage,
+        &AcceptedPrice {
+            price,
+            updated_at,
+            accepted_at: env.block.time,
+            sources: responses.into_iter().map(|(oracle, _)| oracle).collect(),
+        },
+    )?;
+    record_observation(deps.storage, env, price, oracle_config.twap_window)?;
+    Ok(price)
+}
+
+/// Cumulative price of an observation made at `time`, extrapolated to `at`
+fn cumulative_at(
+    observation: &PriceObservation,
+    time: u64,
+    at: u64,
+) -> Result<Decimal256, ContractError> {
+    let elapsed = Decimal256::from_ratio(at - time, 1u64);
+    Ok(observation
+        .cumulative
+        .checked_add(Decimal256::from(observation.price).checked_mul(elapsed)?)?)
+}
+
+fn last_observation(storage: &dyn Storage) -> StdResult<Option<(u64, PriceObservation)>> {
+    PRICE_OBSERVATIONS
+        .range(storage, None, None, Order::Descending)
+        .next()
+        .transpose()
+}
+
+fn record_observation(
+    storage: &mut dyn Storage,
+    env: &Env,
+    price: Decimal,
+    window: u64,
+) -> Result<(), ContractError> {
+    let now = env.block.time.seconds();
+    let cumulative = match last_observation(storage)? {
+        Some((time, last)) => cumulative_at(&last, time, now)?,
+        None => Decimal256::zero(),
+    };
+    PRICE_OBSERVATIONS.save(storage, now, &PriceObservation { price, cumulative })?;
+
+    // only the newest observation before the start of the window is needed
+    let start = now.saturating_sub(window);
+    let expired = PRICE_OBSERVATIONS
+        .keys(
+            storage,
+            None,
+            Some(Bound::inclusive(start)),
+            Order::Descending,
+        )
+        .skip(1)
+        .collect::<StdResult<Vec<_>>>()?;
+    for time in expired {
+        PRICE_OBSERVATIONS.remove(storage, time);
+    }
+    Ok(())
+}
+
+/// Time-weighted average price over the last `window` seconds
+fn twap(storage: &dyn Storage, env: &Env, window: u64) -> Result<Decimal, ContractError> {
+    let now = env.block.time.seconds();
+    let (end_time, end) =
+        last_observation(storage)?.ok_or(ContractError::InsufficientPriceHistory { window })?;
+    if window == 0 {
+        return Ok(end.price);
+    }
+
+    let start = now
+        .checked_sub(window)
+        .ok_or(ContractError::InsufficientPriceHistory { window })?;
+    let (start_time, start_observation) = PRICE_OBSERVATIONS
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive(start)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?
+        .ok_or(ContractError::InsufficientPriceHistory { window })?;
+
+    let total = cumulative_at(&end, end_time, now)?.checked_sub(cumulative_at(
+        &start_observation,
+        start_time,
+        start,
+    )?)?;
+    Ok(Decimal::try_from(
+        total / Decimal256::from_ratio(window, 1u64),
+    )?)
+}
+
+/// Median of the prices, the mean of the two middle prices for an even count
+fn median(mut prices: Vec<Decimal>) -> Result<Decimal, ContractError> {
+    prices.sort();
+    let mid = prices.len() / 2;
+    if prices.len() % 2 == 1 {
+        return Ok(prices[mid]);
+    }
+    Ok(prices[mid - 1]
+        .checked_add(prices[mid])?
+        .checked_mul(Decimal::percent(50))?)
+}
+
+fn validate_price(
+    deps: Deps,
+    env: &Env,
+    oracle_config: &OracleConfig,
+    price: Decimal,
+) -> Result<(), ContractError> {
+    let below_min = oracle_config.min_price.is_some_and(|min| price < min);
+    let above_max = oracle_config.max_price.is_some_and(|max| price > max);
+    if price.is_zero() || below_min || above_max {
+        return Err(ContractError::PriceOutOfBounds { price });
+    }
+
+    // circuit breaker against the last accepted price
+    if let (Some(max_deviation), Some(last)) = (
+        oracle_config.max_deviation,
+        LAST_PRICE.may_load(deps.storage)?,
+    ) {
+        let window_end = last
+            .accepted_at
+            .seconds()
+            .checked_add(oracle_config.deviation_window)
+            .ok_or_else(|| OverflowError::new(OverflowOperation::Add))?;
+        let in_window = env.block.time.seconds() <= window_end;
+        if in_window && price.abs_diff(last.price) > last.price.checked_mul(max_deviation)? {
+            return Err(ContractError::PriceDeviationExceeded {
+                price,
+                reference: last.price,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn execute_set_oracle_config(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    config: Option<OracleConfig>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let mut event = Event::new("consumption-unit::set_oracle_config");
+    match config {
+        Some(config) => {
+            event = event
+                .add_attribute("max_price_age", config.max_price_age.to_string())
+                .add_attribute("max_deviation", format!("{:?}", config.max_deviation))
+                .add_attribute("deviation_window", config.deviation_window.to_string())
+                .add_attribute("min_price", format!("{:?}", config.min_price))
+                .add_attribute("max_price", format!("{:?}", config.max_price))
+                .add_attribute("twap_window", config.twap_window.to_string());
+            ORACLE_CONFIG.save(deps.storage, &config)?;
+        }
+        None => ORACLE_CONFIG.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::set_oracle_config")
+        .add_event(event))
+}
+
+/// Records the current oracle price for the TWAP
+pub fn execute_observe_price(
+    deps: DepsMut,
+    env: &Env,
+    _info: &MessageInfo,
+) -> Result<Response, ContractError> {
+    let oracle_config = ORACLE_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::OracleNotConfigured {})?;
+    let price = observe_price(deps, env, &oracle_config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::observe_price")
+        .add_event(
+            Event::new("consumption-unit::observe_price")
+                .add_attribute("price", price.to_string())
+                .add_attribute("time", env.block.time.seconds().to_string()),
+        ))
+}
+
+pub fn query_oracle_config(deps: Deps) -> StdResult<Option<OracleConfig>> {
+    ORACLE_CONFIG.may_load(deps.storage)
+}
+
+pub fn query_last_price(deps: Deps) -> StdResult<Option<AcceptedPrice>> {
+    LAST_PRICE.may_load(deps.storage)
+}
+
+/// Observations are only kept for the configured TWAP window, longer windows are rejected
+pub fn query_twap(deps: Deps, env: &Env, window: u64) -> StdResult<TwapResponse> {
+    let max_window = ORACLE_CONFIG
+        .may_load(deps.storage)?
+        .map_or(0, |config| config.twap_window);
+    if window > max_window {
+        return Err(StdError::generic_err(
+            ContractError::TwapWindowTooLong { window, max_window }.to_string(),
+        ));
+    }
+    let price =
+        twap(deps.storage, env, window).map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(TwapResponse { window, price })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg};
+    use crate::testing::{cu_data, mint, setup};
+    use crate::types::TierConfig;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi, MockQuerier};
+    use cosmwasm_std::{
+        to_json_binary, Addr, ContractResult, MemoryStorage, OwnedDeps, SystemError, SystemResult,
+        Timestamp, WasmQuery,
+    };
+    use cw20::Denom;
+
+    /// Makes each oracle report the given price, oracles not listed are down
+    fn set_prices(
+        deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier>,
+        prices: &[(&Addr, Decimal, Timestamp)],
+    ) {
+        let prices: Vec<_> = prices
+            .iter()
+            .map(|(oracle, price, updated_at)| (oracle.to_string(), *price, *updated_at))
+            .collect();
+        deps.querier.update_wasm(move |query| {
+            let WasmQuery::Smart { contract_addr, .. } = query else {
+                panic!("unexpected query");
+            };
+            match prices.iter().find(|(oracle, ..)| oracle == contract_addr) {
+                Some((_, price, updated_at)) => SystemResult::Ok(ContractResult::Ok(
+                    to_json_binary(&PriceResponse {
+                        price: *price,
+                        updated_at: *updated_at,
+                    })
+                    .unwrap(),
+                )),
+                None => SystemResult::Err(SystemError::NoSuchContract {
+                    addr: contract_addr.clone(),
+                }),
+            }
+        });
+    }
+
+    fn update_pool_msg(tier: u16) -> ExecuteMsg {
+        ExecuteMsg::UpdateNftInfo {
+            token_id: "1".to_string(),
+            extension: ConsumptionUnitExtensionUpdate::UpdatePool {
+                new_commitment_tier_id: tier,
+            },
+        }
+    }
+
+    fn floor_price(deps: Deps) -> Decimal {
+        q_nft::query::query_nft_info::<ConsumptionUnitData>(deps.storage, "1".to_string())
+            .unwrap()
+            .extension
+            .floor_price
+    }
+
+    #[test]
+    fn test_oracle_price_checks() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        let oracles: Vec<_> = ["oracle1", "oracle2", "oracle3"]
+            .map(|oracle| deps.api.addr_make(oracle))
+            .into();
+        let [a, b, c] = [&oracles[0], &oracles[1], &oracles[2]];
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(100, 1)).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::UpdateConfig {
+                settlement_token: None,
+                native_token: None,
+                price_oracles: Some(vec![a.to_string()]),
+                oracle_quorum: Some(2),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidOracleQuorum {}));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::UpdateConfig {
+                settlement_token: None,
+                native_token: None,
+                price_oracles: Some(oracles.iter().map(Addr::to_string).collect()),
+                oracle_quorum: Some(2),
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetOracleConfig {
+                config: Some(OracleConfig {
+                    max_price_age: 60,
+                    max_deviation: Some(Decimal::percent(10)),
+                    deviation_window: 600,
+                    min_price: Some(Decimal::percent(10)),
+                    max_price: Some(Decimal::percent(1_000)),
+                    twap_window: 0,
+                }),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetTierConfig {
+                tier: 2,
+                config: TierConfig {
+                    floor_multiplier: Some(Decimal::percent(80)),
+                    ..TierConfig::default()
+                },
+            },
+        )
+        .unwrap();
+
+        // oracle2 is stale and oracle3 is down
+        let now = env.block.time;
+        set_prices(
+            &mut deps,
+            &[
+                (a, Decimal::one(), now),
+                (b, Decimal::one(), now.minus_seconds(61)),
+            ],
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            update_pool_msg(2),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::OracleQuorumNotMet {
+                sources: 1,
+                quorum: 2
+            }
+        ));
+
+        set_prices(
+            &mut deps,
+            &[(a, Decimal::percent(5), now), (b, Decimal::percent(5), now)],
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            update_pool_msg(2),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::PriceOutOfBounds { .. }));
+
+        // the outlier of oracle3 does not move the median
+        set_prices(
+            &mut deps,
+            &[
+                (a, Decimal::percent(190), now.minus_seconds(10)),
+                (b, Decimal::percent(200), now),
+                (c, Decimal::percent(900), now),
+            ],
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            update_pool_msg(2),
+        )
+        .unwrap();
+        assert_eq!(floor_price(deps.as_ref()), Decimal::percent(160));
+        let last_price = query_last_price(deps.as_ref()).unwrap().unwrap();
+        assert_eq!(last_price.price, Decimal::percent(200));
+        assert_eq!(last_price.updated_at, now.minus_seconds(10));
+        assert_eq!(last_price.sources, oracles);
+
+        // a jump of more than 10% within the window trips the circuit breaker
+        env.block.time = env.block.time.plus_seconds(300);
+        let now = env.block.time;
+        set_prices(
+            &mut deps,
+            &[
+                (a, Decimal::percent(250), now),
+                (b, Decimal::percent(250), now),
+            ],
+        );
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            update_pool_msg(1),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::PriceDeviationExceeded { .. }));
+
+        // outside of the window the price is accepted, the mean of an even count is used
+        env.block.time = env.block.time.plus_seconds(301);
+        let now = env.block.time;
+        set_prices(
+            &mut deps,
+            &[
+                (a, Decimal::percent(240), now),
+                (c, Decimal::percent(260), now),
+            ],
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            update_pool_msg(1),
+        )
+        .unwrap();
+        assert_eq!(floor_price(deps.as_ref()), Decimal::percent(250));
+        assert_eq!(
+            query_last_price(deps.as_ref()).unwrap().unwrap().sources,
+            vec![a.clone(), c.clone()]
+        );
+    }
+
+    #[test]
+    fn test_twap() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        let oracle = deps.api.addr_make("price_oracle");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(100, 1)).unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            ExecuteMsg::ObservePrice {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::OracleNotConfigured {}));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetOracleConfig {
+                config: Some(OracleConfig {
+                    max_price_age: 60,
+                    max_deviation: None,
+                    deviation_window: 0,
+                    min_price: None,
+                    max_price: None,
+                    twap_window: 100,
+                }),
+            },
+        )
+        .unwrap();
+
+        let start = env.block.time;
+        set_prices(&mut deps, &[(&oracle, Decimal::one(), start)]);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            ExecuteMsg::ObservePrice {},
+        )
+        .unwrap();
+        assert!(query_twap(deps.as_ref(), &env, 100).is_err());
+
+        // a spike to 3 halfway through the window
+        env.block.time = start.plus_seconds(50);
+        set_prices(
+            &mut deps,
+            &[(&oracle, Decimal::percent(300), env.block.time)],
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            ExecuteMsg::ObservePrice {},
+        )
+        .unwrap();
+
+        env.block.time = start.plus_seconds(100);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            update_pool_msg(2),
+        )
+        .unwrap();
+        assert_eq!(floor_price(deps.as_ref()), Decimal::percent(200));
+        assert_eq!(
+            query_twap(deps.as_ref(), &env, 20).unwrap().price,
+            Decimal::percent(300)
+        );
+
+        // observations older than the window are pruned
+        env.block.time = start.plus_seconds(200);
+        set_prices(
+            &mut deps,
+            &[(&oracle, Decimal::percent(300), env.block.time)],
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            ExecuteMsg::ObservePrice {},
+        )
+        .unwrap();
+        let observations = PRICE_OBSERVATIONS
+            .keys(deps.as_ref().storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            observations,
+            vec![
+                start.plus_seconds(100).seconds(),
+                start.plus_seconds(200).seconds()
+            ]
+        );
+        assert_eq!(
+            query_twap(deps.as_ref(), &env, 100).unwrap().price,
+            Decimal::percent(300)
+        );
+        let err = query_twap(deps.as_ref(), &env, 101).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("exceeds the configured TWAP window"));
+    }
+}
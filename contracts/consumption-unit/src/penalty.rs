@@ -0,0 +1,236 @@
+use crate::error::ContractError;
+use crate::msg::PenaltyResponse;
+use crate::state::TIER_RESERVES;
+use crate::tier::load_tier_config;
+use crate::types::{CUConfig, ConsumptionUnitData, PenaltyBase, PenaltyConfig, PenaltyDestination};
+use cosmwasm_std::{CosmosMsg, Deps, Env, StdError, StdResult, Storage, Uint128};
+use q_nft::state::Cw721Config;
+use q_utils::asset::Asset;
+use q_utils::fixed_point::{mul_decimal, Rounding};
+
+/// Early exit penalty of the CU's tier if the CU leaves it now, `None` once the lock period is over
+fn active_penalty(
+    storage: &dyn Storage,
+    env: &Env,
+    data: &ConsumptionUnitData,
+) -> StdResult<Option<PenaltyConfig>> {
+    let config = load_tier_config(storage, data.commitment_tier)?;
+    let unlocked_at = data.updated_at.plus_seconds(config.lock_period);
+    if env.block.time >= unlocked_at {
+        return Ok(None);
+    }
+    Ok(config.early_exit_penalty)
+}
+
+/// Penalty in settlement tokens due if the CU leaves its tier now
+pub fn early_exit_penalty(
+    storage: &dyn Storage,
+    env: &Env,
+    data: &ConsumptionUnitData,
+) -> Result<Uint128, ContractError> {
+    let Some(penalty) = active_penalty(storage, env, data)? else {
+        return Ok(Uint128::zero());
+    };
+    let base = match penalty.base {
+        PenaltyBase::ConsumptionValue => data.consumption_value,
+        PenaltyBase::FloorValue => data.floor_value()?,
+    };
+    Ok(mul_decimal(base, penalty.rate, Rounding::Up)?)
+}
+
+/// Routes a penalty paid for leaving `tier` early to its destination.
+/// Returns the message sending it to the beneficiary, if any.
+pub fn route_penalty(
+    storage: &mut dyn Storage,
+    tier: u16,
+    amount: Uint128,
+) -> Result<Option<CosmosMsg>, ContractError> {
+    if amount.is_zero() {
+        return Ok(None);
+    }
+    let penalty = load_tier_config(storage, tier)?
+        .early_exit_penalty
+        .ok_or(ContractError::InvalidPenalty {})?;
+    match penalty.destination {
+        PenaltyDestination::Beneficiary(beneficiary) => {
+            let settlement_token = Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+                .collection_config
+                .load(storage)?
+                .settlement_token;
+            Ok(Some(
+                Asset::new(settlement_token, amount).transfer_msg(beneficiary)?,
+            ))
+        }
+        PenaltyDestination::Pool => {
+            let reserve = TIER_RESERVES.may_load(storage, tier)?.unwrap_or_default();
+            TIER_RESERVES.save(storage, tier, &reserve.checked_add(amount)?)?;
+            Ok(None)
+        }
+    }
+}
+
+pub fn query_simulate_penalty(
+    deps: Deps,
+    env: &Env,
+    token_id: String,
+) -> StdResult<PenaltyResponse> {
+    let nft_info = Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+        .nft_info
+        .load(deps.storage, &token_id)?;
+    let data = &nft_info.extension;
+    let config = load_tier_config(deps.storage, data.commitment_tier)?;
+    let penalty = early_exit_penalty(deps.storage, env, data)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(PenaltyResponse {
+        penalty,
+        unlocked_at: data.updated_at.plus_seconds(config.lock_period),
+        destination: active_penalty(deps.storage, env, data)?.map(|penalty| penalty.destination),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg};
+    use crate::reserve::query_reserve;
+    use crate::testing::{cu_data, mint, setup};
+    use crate::types::TierConfig;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{coins, BankMsg, Coin, Decimal};
+    use cw20::Denom;
+
+    fn update_pool_msg(tier: u16) -> ExecuteMsg {
+        ExecuteMsg::UpdateNftInfo {
+            token_id: "1".to_string(),
+            extension: ConsumptionUnitExtensionUpdate::UpdatePool {
+                new_commitment_tier_id: tier,
+            },
+        }
+    }
+
+    #[test]
+    fn test_early_exit_penalty() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        let beneficiary = deps.api.addr_make("beneficiary");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetTierConfig {
+                tier: 1,
+                config: TierConfig {
+                    early_exit_penalty: Some(PenaltyConfig {
+                        rate: Decimal::percent(101),
+                        base: PenaltyBase::ConsumptionValue,
+                        destination: PenaltyDestination::Pool,
+                    }),
+                    ..TierConfig::default()
+                },
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPenalty {}));
+
+        for (tier, penalty) in [
+            (
+                1,
+                PenaltyConfig {
+                    rate: Decimal::percent(10),
+                    base: PenaltyBase::FloorValue,
+                    destination: PenaltyDestination::Pool,
+                },
+            ),
+            (
+                2,
+                PenaltyConfig {
+                    rate: Decimal::percent(5),
+                    base: PenaltyBase::ConsumptionValue,
+                    destination: PenaltyDestination::Beneficiary(beneficiary.clone()),
+                },
+            ),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                message_info(&creator, &[]),
+                ExecuteMsg::SetTierConfig {
+                    tier,
+                    config: TierConfig {
+                        lock_period: 100,
+                        early_exit_penalty: Some(penalty),
+                        ..TierConfig::default()
+                    },
+                },
+            )
+            .unwrap();
+        }
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(1_000, 1)).unwrap();
+
+        // 10% of the floor value of 500
+        let simulated = query_simulate_penalty(deps.as_ref(), &env, "1".to_string()).unwrap();
+        assert_eq!(
+            simulated,
+            PenaltyResponse {
+                penalty: Uint128::new(50),
+                unlocked_at: env.block.time.plus_seconds(100),
+                destination: Some(PenaltyDestination::Pool),
+            }
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            update_pool_msg(2),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InvalidFee { expected, .. } if expected == Uint128::new(50)
+        ));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &coins(50, "settlement")),
+            update_pool_msg(2),
+        )
+        .unwrap();
+        let reserve = query_reserve(deps.as_ref()).unwrap();
+        assert_eq!(reserve.general, Uint128::zero());
+        assert_eq!(reserve.tiers[0].amount, Uint128::new(50));
+
+        // 5% of the consumption value goes to the beneficiary
+        let response = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &coins(50, "settlement")),
+            update_pool_msg(3),
+        )
+        .unwrap();
+        assert_eq!(
+            response.messages[0].msg,
+            BankMsg::Send {
+                to_address: beneficiary.to_string(),
+                amount: vec![Coin::new(50u128, "settlement")],
+            }
+            .into()
+        );
+
+        // tier 3 has no penalty
+        env.block.time = env.block.time.plus_seconds(10);
+        let simulated = query_simulate_penalty(deps.as_ref(), &env, "1".to_string()).unwrap();
+        assert_eq!(simulated.penalty, Uint128::zero());
+        assert_eq!(simulated.destination, None);
+    }
+}
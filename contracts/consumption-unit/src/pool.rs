@@ -0,0 +1,237 @@
+use crate::error::ContractError;
+use crate::msg::{AllPoolStatsResponse, PoolStatsResponse};
+use crate::state::{POOL_CAPACITIES, POOL_STATS};
+use crate::types::{ConsumptionUnitData, PoolCapacity};
+use cosmwasm_std::{
+    Deps, DepsMut, Env, Event, MessageInfo, Order, OverflowError, OverflowOperation, Response,
+    StdResult, Storage,
+};
+use cw_storage_plus::Bound;
+use q_nft::execute::assert_creator;
+use q_nft::query::{DEFAULT_LIMIT, MAX_LIMIT};
+
+/// Adds the CU to the stats of its commitment tier, enforcing the tier capacity
+pub fn add_to_pool(
+    storage: &mut dyn Storage,
+    data: &ConsumptionUnitData,
+) -> Result<(), ContractError> {
+    let tier = data.commitment_tier;
+    let mut stats = POOL_STATS.may_load(storage, tier)?.unwrap_or_default();
+    stats.cu_count = stats
+        .cu_count
+        .checked_add(1)
+        .ok_or(OverflowError::new(OverflowOperation::Add))?;
+    stats.total_consumption_value = stats
+        .total_consumption_value
+        .checked_add(data.consumption_value)?;
+    stats.total_floor_value = stats.total_floor_value.checked_add(data.floor_value()?)?;
+
+    if let Some(capacity) = POOL_CAPACITIES.may_load(storage, tier)? {
+        let count_exceeded = capacity
+            .max_cu_count
+            .is_some_and(|max| stats.cu_count > max);
+        let value_exceeded = capacity
+            .max_consumption_value
+            .is_some_and(|max| stats.total_consumption_value > max);
+        if count_exceeded || value_exceeded {
+            return Err(ContractError::PoolCapacityExceeded { tier });
+        }
+    }
+
+    POOL_STATS.save(storage, tier, &stats)?;
+    Ok(())
+}
+
+/// Removes the CU from the stats of its commitment tier
+pub fn remove_from_pool(
+    storage: &mut dyn Storage,
+    data: &ConsumptionUnitData,
+) -> Result<(), ContractError> {
+    let tier = data.commitment_tier;
+    let mut stats = POOL_STATS.load(storage, tier)?;
+    stats.cu_count = stats
+        .cu_count
+        .checked_sub(1)
+        .ok_or(OverflowError::new(OverflowOperation::Sub))?;
+    stats.total_consumption_value = stats
+        .total_consumption_value
+        .checked_sub(data.consumption_value)?;
+    stats.total_floor_value = stats.total_floor_value.checked_sub(data.floor_value()?)?;
+
+    POOL_STATS.save(storage, tier, &stats)?;
+    Ok(())
+}
+
+pub fn execute_set_pool_capacity(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    tier: u16,
+    capacity: Option<PoolCapacity>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let mut event =
+        Event::new("consumption-unit::set_pool_capacity").add_attribute("tier", tier.to_string());
+    match capacity {
+        Some(capacity) => {
+            POOL_CAPACITIES.save(deps.storage, tier, &capacity)?;
+            event = event
+                .add_attribute("max_cu_count", format!("{:?}", capacity.max_cu_count))
+                .add_attribute(
+                    "max_consumption_value",
+                    format!("{:?}", capacity.max_consumption_value),
+                );
+        }
+        None => POOL_CAPACITIES.remove(deps.storage, tier),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::set_pool_capacity")
+        .add_event(event))
+}
+
+pub fn query_pool_stats(deps: Deps, tier: u16) -> StdResult<PoolStatsResponse> {
+    Ok(PoolStatsResponse {
+        tier,
+        stats: POOL_STATS.may_load(deps.storage, tier)?.unwrap_or_default(),
+        capacity: POOL_CAPACITIES.may_load(deps.storage, tier)?,
+    })
+}
+
+pub fn query_all_pool_stats(
+    deps: Deps,
+    start_after: Option<u16>,
+    limit: Option<u32>,
+) -> StdResult<AllPoolStatsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let pools = POOL_STATS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (tier, stats) = item?;
+            Ok(PoolStatsResponse {
+                tier,
+                stats,
+                capacity: POOL_CAPACITIES.may_load(deps.storage, tier)?,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AllPoolStatsResponse { pools })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg};
+    use crate::testing::{cu_data, mint, setup};
+    use crate::types::PoolStats;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::Uint128;
+    use cw20::Denom;
+    use cw_ownable::OwnershipError;
+    use q_nft::error::Cw721ContractError;
+
+    #[test]
+    fn test_pool_accounting() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetPoolCapacity {
+                tier: 2,
+                capacity: Some(PoolCapacity {
+                    max_cu_count: Some(1),
+                    max_consumption_value: None,
+                }),
+            },
+        )
+        .unwrap();
+
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(100, 1)).unwrap();
+        mint(deps.as_mut(), &creator, "2", &owner, cu_data(300, 1)).unwrap();
+        mint(deps.as_mut(), &creator, "3", &owner, cu_data(50, 2)).unwrap();
+
+        let stats = query_pool_stats(deps.as_ref(), 1).unwrap().stats;
+        assert_eq!(
+            stats,
+            PoolStats {
+                cu_count: 2,
+                total_consumption_value: Uint128::new(400),
+                total_floor_value: Uint128::new(200),
+            }
+        );
+
+        // tier 2 is full
+        let err = mint(deps.as_mut(), &creator, "4", &owner, cu_data(50, 2)).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::PoolCapacityExceeded { tier: 2 }
+        ));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: "1".to_string(),
+                extension: ConsumptionUnitExtensionUpdate::UpdatePool {
+                    new_commitment_tier_id: 3,
+                },
+            },
+        )
+        .unwrap();
+        let burn_msg = ExecuteMsg::Burn {
+            token_id: "2".to_string(),
+        };
+        // only the owner can burn the CU
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &[]),
+            burn_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::Cw721ContractError(Cw721ContractError::Ownership(
+                OwnershipError::NotOwner
+            ))
+        ));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            burn_msg,
+        )
+        .unwrap();
+
+        let pools = query_all_pool_stats(deps.as_ref(), None, None)
+            .unwrap()
+            .pools;
+        let counts: Vec<_> = pools
+            .iter()
+            .map(|pool| (pool.tier, pool.stats.cu_count))
+            .collect();
+        assert_eq!(counts, vec![(1, 0), (2, 1), (3, 1)]);
+        assert_eq!(pools[0].stats.total_consumption_value, Uint128::zero());
+        assert_eq!(pools[2].stats.total_floor_value, Uint128::new(50));
+
+        // the stats of an empty tier cannot underflow
+        let err = remove_from_pool(&mut deps.storage, &cu_data(0, 1)).unwrap_err();
+        assert!(matches!(err, ContractError::Overflow(_)));
+    }
+}
@@ -1,8 +1,16 @@
-use crate::types::{CUConfig, ConsumptionUnitData};
+use crate::msg::ConfigResponse;
+use crate::types::{CUConfig, ConsumptionUnitData, ConsumptionUnitNft};
+use crate::{
+    compliance, dispute, escrow, expiry, fee, freeze, history, oracle, penalty, pool, reporter,
+    reserve, rewards, round, settlement, tier, voting,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env, StdResult};
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Binary, Deps, Env, QuerierWrapper, StdResult, Uint128,
+};
+use q_nft::state::Cw721Config;
 
 #[cw_serde]
 #[derive(QueryResponses)]
@@ -10,10 +18,24 @@ pub enum QueryMsg {
     #[returns(q_nft::msg::ContractInfoResponse<CUConfig>)]
     ContractInfo {},
 
-    // TODO add Cw721 config as well
+    /// Returns the CU config together with the Cw721 collection info,
+    /// minter/creator ownership and the contract version.
+    #[returns(crate::msg::ConfigResponse)]
+    Config {},
+
     #[returns(q_nft::msg::OwnerOfResponse)]
     OwnerOf { token_id: String },
 
+    /// Returns the owner of the token at the start of block `height`,
+    /// requires snapshots to be enabled at instantiation or on migration
+    #[returns(q_nft::msg::OwnerOfResponse)]
+    OwnerOfAtHeight { token_id: String, height: u64 },
+
+    /// Returns the number of tokens held by the owner at the start of block `height`,
+    /// requires snapshots to be enabled at instantiation or on migration
+    #[returns(q_nft::msg::BalanceResponse)]
+    BalanceAtHeight { owner: String, height: u64 },
+
     #[returns(q_nft::msg::NumTokensResponse)]
     NumTokens {},
 
@@ -41,6 +63,164 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+
+    /// Returns the settlement token reserves held by the contract
+    #[returns(crate::msg::ReserveResponse)]
+    Reserve {},
+
+    /// Returns the settlement tokens prepaid by the given address for minting
+    #[returns(crate::msg::MintDepositResponse)]
+    MintDeposit { address: String },
+
+    /// Returns the aggregated figures of the given commitment tier
+    #[returns(crate::msg::PoolStatsResponse)]
+    PoolStats { tier: u16 },
+
+    /// Requires pagination. Lists the aggregated figures of all commitment tiers.
+    #[returns(crate::msg::AllPoolStatsResponse)]
+    AllPoolStats {
+        start_after: Option<u16>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the switching rules of the given commitment tier
+    #[returns(crate::types::TierConfig)]
+    TierConfig { tier: u16 },
+
+    /// Returns the raffle round running at the current block time, if any
+    #[returns(Option<crate::msg::RoundResponse>)]
+    CurrentRound {},
+
+    #[returns(crate::msg::RoundResponse)]
+    Round { id: u64 },
+
+    /// Requires pagination. Lists the token_ids nominated in the given round.
+    #[returns(q_nft::msg::TokensResponse)]
+    RoundNominations {
+        id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the mint fee due for a CU of the given `consumption_value`
+    #[returns(crate::msg::MintFeeResponse)]
+    MintFee { consumption_value: Uint128 },
+
+    /// Returns the mint fees collected and not withdrawn yet
+    #[returns(crate::msg::CollectedFeesResponse)]
+    CollectedFees {},
+
+    #[returns(crate::msg::ReporterResponse)]
+    Reporter { id: String },
+
+    /// Requires pagination. Lists all registered reporters.
+    #[returns(crate::msg::ReportersResponse)]
+    Reporters {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the sanity checks of oracle prices, if set
+    #[returns(Option<crate::types::OracleConfig>)]
+    OracleConfig {},
+
+    /// Returns the last oracle price which passed all checks
+    #[returns(Option<crate::types::AcceptedPrice>)]
+    LastPrice {},
+
+    /// Returns the time-weighted average oracle price over the last `window` seconds,
+    /// which may not exceed the TWAP window of the oracle config
+    #[returns(crate::msg::TwapResponse)]
+    Twap { window: u64 },
+
+    /// Returns the early exit penalty due if the CU leaves its tier now
+    #[returns(crate::msg::PenaltyResponse)]
+    SimulatePenalty { token_id: String },
+
+    /// Returns the expiry of unselected CUs, if set
+    #[returns(Option<crate::types::ExpiryConfig>)]
+    ExpiryConfig {},
+
+    /// Returns the vesting of settlement payouts, if set
+    #[returns(Option<crate::types::VestingConfig>)]
+    VestingConfig {},
+
+    /// Returns the vesting settlements of the given owner
+    #[returns(crate::msg::VestingScheduleResponse)]
+    VestingSchedule { owner: String },
+
+    /// Returns the rewards accrued by the CUs of the given owner
+    #[returns(crate::msg::PendingRewardsResponse)]
+    PendingRewards { owner: String },
+
+    /// Returns the `consumption_value` held by the address per commitment tier
+    /// at the start of block `height`, the current holdings if not set
+    #[returns(crate::msg::VotingPowerResponse)]
+    VotingPower {
+        address: String,
+        height: Option<u64>,
+    },
+
+    /// Returns the `consumption_value` held by all addresses per commitment tier
+    /// at the start of block `height`, the current holdings if not set
+    #[returns(crate::msg::TotalPowerResponse)]
+    TotalPower { height: Option<u64> },
+
+    /// Returns the history of the token ordered from the oldest entry,
+    /// also available once the token is burned
+    #[returns(crate::msg::TokenHistoryResponse)]
+    TokenHistory {
+        token_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(crate::msg::AuditorsResponse)]
+    Auditors {},
+
+    /// Returns the latest dispute of the CU, also once it is resolved
+    #[returns(Option<crate::types::Dispute>)]
+    Dispute { token_id: String },
+
+    /// Returns the latest dispute of each CU ordered by token id,
+    /// only unresolved disputes if `open` is set
+    #[returns(crate::msg::DisputesResponse)]
+    Disputes {
+        open: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Option<crate::types::ComplianceConfig>)]
+    ComplianceConfig {},
+
+    /// Returns whether the address may hold CUs under the current compliance mode
+    #[returns(crate::msg::IsAllowedResponse)]
+    IsAllowed { address: String },
+
+    /// Returns the local allowlist ordered by address
+    #[returns(crate::msg::AllowlistResponse)]
+    Allowlist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(crate::msg::GuardiansResponse)]
+    Guardians {},
+
+    /// Returns whether the address is frozen, with the reason and time of the freeze
+    #[returns(crate::msg::IsFrozenResponse)]
+    IsFrozen { address: String },
+
+    /// Returns the frozen addresses ordered by address
+    #[returns(crate::msg::FrozenAddressesResponse)]
+    FrozenAddresses {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(crate::msg::EscrowContractsResponse)]
+    EscrowContracts {},
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -49,9 +229,16 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::ContractInfo {} => to_json_binary(
             &q_nft::query::query_contract_info::<CUConfig>(deps.storage)?,
         ),
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
         QueryMsg::OwnerOf { token_id } => {
             to_json_binary(&q_nft::query::query_owner_of(deps.storage, &env, token_id)?)
         }
+        QueryMsg::OwnerOfAtHeight { token_id, height } => to_json_binary(
+            &q_nft::query::query_owner_of_at_height(deps.storage, token_id, height)?,
+        ),
+        QueryMsg::BalanceAtHeight { owner, height } => {
+            to_json_binary(&q_nft::query::query_balance_at_height(deps, owner, height)?)
+        }
         QueryMsg::NumTokens {} => to_json_binary(&q_nft::query::query_num_tokens(deps.storage)?),
         QueryMsg::GetMinterOwnership {} => {
             to_json_binary(&q_nft::query::query_minter_ownership(deps.storage)?)
@@ -73,14 +260,128 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             start_after,
             limit,
         )?),
+        QueryMsg::Auditors {} => to_json_binary(&dispute::query_auditors(deps)?),
+        QueryMsg::Dispute { token_id } => to_json_binary(&dispute::query_dispute(deps, token_id)?),
+        QueryMsg::Disputes {
+            open,
+            start_after,
+            limit,
+        } => to_json_binary(&dispute::query_disputes(deps, open, start_after, limit)?),
+        QueryMsg::ComplianceConfig {} => {
+            to_json_binary(&compliance::query_compliance_config(deps)?)
+        }
+        QueryMsg::IsAllowed { address } => {
+            to_json_binary(&compliance::query_is_allowed(deps, address)?)
+        }
+        QueryMsg::Allowlist { start_after, limit } => {
+            to_json_binary(&compliance::query_allowlist(deps, start_after, limit)?)
+        }
+        QueryMsg::Guardians {} => to_json_binary(&freeze::query_guardians(deps)?),
+        QueryMsg::IsFrozen { address } => to_json_binary(&freeze::query_is_frozen(deps, address)?),
+        QueryMsg::FrozenAddresses { start_after, limit } => {
+            to_json_binary(&freeze::query_frozen_addresses(deps, start_after, limit)?)
+        }
         QueryMsg::AllTokens { start_after, limit } => to_json_binary(
             &q_nft::query::query_all_tokens(deps, &env, start_after, limit)?,
         ),
+        QueryMsg::Reserve {} => to_json_binary(&reserve::query_reserve(deps)?),
+        QueryMsg::MintDeposit { address } => {
+            to_json_binary(&reserve::query_mint_deposit(deps, address)?)
+        }
+        QueryMsg::PoolStats { tier } => to_json_binary(&pool::query_pool_stats(deps, tier)?),
+        QueryMsg::AllPoolStats { start_after, limit } => {
+            to_json_binary(&pool::query_all_pool_stats(deps, start_after, limit)?)
+        }
+        QueryMsg::TierConfig { tier } => to_json_binary(&tier::query_tier_config(deps, tier)?),
+        QueryMsg::CurrentRound {} => to_json_binary(&round::query_current_round(deps, &env)?),
+        QueryMsg::Round { id } => to_json_binary(&round::query_round(deps, id)?),
+        QueryMsg::RoundNominations {
+            id,
+            start_after,
+            limit,
+        } => to_json_binary(&round::query_round_nominations(
+            deps,
+            id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::MintFee { consumption_value } => {
+            to_json_binary(&fee::query_mint_fee(deps, consumption_value)?)
+        }
+        QueryMsg::CollectedFees {} => to_json_binary(&fee::query_collected_fees(deps)?),
+        QueryMsg::Reporter { id } => to_json_binary(&reporter::query_reporter(deps, id)?),
+        QueryMsg::Reporters { start_after, limit } => {
+            to_json_binary(&reporter::query_reporters(deps, start_after, limit)?)
+        }
+        QueryMsg::OracleConfig {} => to_json_binary(&oracle::query_oracle_config(deps)?),
+        QueryMsg::LastPrice {} => to_json_binary(&oracle::query_last_price(deps)?),
+        QueryMsg::Twap { window } => to_json_binary(&oracle::query_twap(deps, &env, window)?),
+        QueryMsg::SimulatePenalty { token_id } => {
+            to_json_binary(&penalty::query_simulate_penalty(deps, &env, token_id)?)
+        }
+        QueryMsg::ExpiryConfig {} => to_json_binary(&expiry::query_expiry_config(deps)?),
+        QueryMsg::VestingConfig {} => to_json_binary(&settlement::query_vesting_config(deps)?),
+        QueryMsg::VestingSchedule { owner } => {
+            to_json_binary(&settlement::query_vesting_schedule(deps, &env, owner)?)
+        }
+        QueryMsg::PendingRewards { owner } => {
+            to_json_binary(&rewards::query_pending_rewards(deps, owner)?)
+        }
+        QueryMsg::EscrowContracts {} => to_json_binary(&escrow::query_escrow_contracts(deps)?),
+        QueryMsg::VotingPower { address, height } => {
+            to_json_binary(&voting::query_voting_power(deps, &env, address, height)?)
+        }
+        QueryMsg::TotalPower { height } => {
+            to_json_binary(&voting::query_total_power(deps, &env, height)?)
+        }
+        QueryMsg::TokenHistory {
+            token_id,
+            start_after,
+            limit,
+        } => to_json_binary(&history::query_token_history(
+            deps,
+            token_id,
+            start_after,
+            limit,
+        )?),
     }
 }
 
 // Query
 
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    Ok(ConfigResponse {
+        collection_info: config.collection_info.load(deps.storage)?,
+        collection_config: config.collection_config.load(deps.storage)?,
+        minter_ownership: q_nft::query::query_minter_ownership(deps.storage)?,
+        creator_ownership: q_nft::query::query_creator_ownership(deps.storage)?,
+        contract_version: cw2::get_contract_version(deps.storage)?,
+    })
+}
+
+/// Storage key of the CU `token_id`, see `query_nft_info_raw`
+pub fn nft_info_key(token_id: &str) -> Vec<u8> {
+    Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+        .nft_info
+        .key(token_id)
+        .to_vec()
+}
+
+/// Loads the CU `token_id` from the storage of the collection `contract`, for other
+/// contracts. Unlike `NftInfo`, which fails on any error, a missing (e.g. burned) CU
+/// is `None` so that it can be told apart from other failures.
+pub fn query_nft_info_raw(
+    querier: &QuerierWrapper,
+    contract: &Addr,
+    token_id: &str,
+) -> StdResult<Option<ConsumptionUnitNft>> {
+    querier
+        .query_wasm_raw(contract, nft_info_key(token_id))?
+        .map(from_json)
+        .transpose()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::contract::{execute, instantiate};
@@ -105,10 +406,12 @@ mod tests {
             collection_info_extension: ConsumptionUnitCollectionExtension {
                 settlement_token: Denom::Cw20(Addr::unchecked("settlement")),
                 native_token: Denom::Native("native".to_string()),
-                price_oracle: Addr::unchecked("price_oracle"),
+                price_oracles: vec![app.api().addr_make("price_oracle")],
+                oracle_quorum: 1,
             },
             minter: None,
             creator: None,
+            snapshots: false,
         };
 
         let contract_addr = app
@@ -0,0 +1,518 @@
+use crate::contract::mint;
+use crate::error::ContractError;
+use crate::msg::{ReporterResponse, ReportersResponse};
+use crate::state::{CONSUMED_REPORTS, REPORTERS};
+use crate::types::{ConsumptionReport, ConsumptionUnitState, Reporter, ReporterKey};
+use crate::{oracle, tier};
+use cosmwasm_std::{
+    to_json_vec, Binary, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, Response, StdResult,
+    Uint128,
+};
+use cw_storage_plus::Bound;
+use q_nft::execute::assert_creator;
+use q_nft::query::{DEFAULT_LIMIT, MAX_LIMIT};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Document signed by reporters, binds the report to the chain and the contract
+#[derive(Serialize)]
+struct ReportSignDoc<'a> {
+    chain_id: &'a str,
+    contract: &'a str,
+    report: &'a ConsumptionReport,
+}
+
+/// Canonical encoding of a report: SHA-256 digest of the JSON encoded sign document.
+/// Both secp256k1 and ed25519 reporters sign this digest.
+pub fn report_digest(env: &Env, report: &ConsumptionReport) -> StdResult<[u8; 32]> {
+    let doc = ReportSignDoc {
+        chain_id: &env.block.chain_id,
+        contract: env.contract.address.as_str(),
+        report,
+    };
+    Ok(Sha256::digest(to_json_vec(&doc)?).into())
+}
+
+pub fn execute_register_reporter(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    id: String,
+    key: ReporterKey,
+    allowed_currencies: Vec<String>,
+    quota: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let key = match key {
+        ReporterKey::Address(addr) => ReporterKey::Address(deps.api.addr_validate(addr.as_str())?),
+        // compressed or uncompressed SEC1 encoding
+        ReporterKey::Secp256k1(public_key) if ![33, 65].contains(&public_key.len()) => {
+            return Err(ContractError::InvalidReporterKey {});
+        }
+        ReporterKey::Ed25519(public_key) if public_key.len() != 32 => {
+            return Err(ContractError::InvalidReporterKey {});
+        }
+        key => key,
+    };
+    // re-registering keeps track of what was already reported
+    let reported = REPORTERS
+        .may_load(deps.storage, &id)?
+        .map(|reporter| reporter.reported)
+        .unwrap_or_default();
+
+    REPORTERS.save(
+        deps.storage,
+        &id,
+        &Reporter {
+            key,
+            allowed_currencies,
+            quota,
+            reported,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::register_reporter")
+        .add_event(Event::new("consumption-unit::register_reporter").add_attribute("id", id)))
+}
+
+pub fn execute_remove_reporter(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    if !REPORTERS.has(deps.storage, &id) {
+        return Err(ContractError::UnknownReporter { id });
+    }
+    REPORTERS.remove(deps.storage, &id);
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::remove_reporter")
+        .add_event(Event::new("consumption-unit::remove_reporter").add_attribute("id", id)))
+}
+
+/// Mints a CU from a report of a registered reporter. Reports of address reporters must be
+/// sent by that address, reports of key reporters can be relayed by anyone with a valid signature.
+/// Each report can be minted once, the CU starts `Reflected` at the floor price of its tier.
+pub fn execute_mint_from_report(
+    mut deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    report: ConsumptionReport,
+    signature: Option<Binary>,
+) -> Result<Response, ContractError> {
+    let mut reporter = REPORTERS.may_load(deps.storage, &report.reporter)?.ok_or(
+        ContractError::UnknownReporter {
+            id: report.reporter.clone(),
+        },
+    )?;
+
+    if env.block.time >= report.expires_at {
+        return Err(ContractError::ReportExpired {});
+    }
+    let digest = report_digest(env, &report)?;
+    if CONSUMED_REPORTS.has(deps.storage, &digest) {
+        return Err(ContractError::ReportAlreadyConsumed {});
+    }
+
+    let verified = match &reporter.key {
+        ReporterKey::Address(addr) => *addr == info.sender,
+        ReporterKey::Secp256k1(public_key) => {
+            let signature = signature.ok_or(ContractError::InvalidSignature {})?;
+            deps.api
+                .secp256k1_verify(&digest, &signature, public_key)
+                .unwrap_or(false)
+        }
+        ReporterKey::Ed25519(public_key) => {
+            let signature = signature.ok_or(ContractError::InvalidSignature {})?;
+            deps.api
+                .ed25519_verify(&digest, &signature, public_key)
+                .unwrap_or(false)
+        }
+    };
+    if !verified {
+        return Err(match reporter.key {
+            ReporterKey::Address(_) => ContractError::UnauthorizedReporter {},
+            _ => ContractError::InvalidSignature {},
+        });
+    }
+
+    let currency = &report.data.nominal_currency;
+    if !reporter.allowed_currencies.is_empty() && !reporter.allowed_currencies.contains(currency) {
+        return Err(ContractError::CurrencyNotAllowed {
+            currency: currency.clone(),
+        });
+    }
+
+    reporter.reported = reporter
+        .reported
+        .checked_add(report.data.consumption_value)?;
+    if reporter
+        .quota
+        .is_some_and(|quota| reporter.reported > quota)
+    {
+        return Err(ContractError::ReporterQuotaExceeded {});
+    }
+    REPORTERS.save(deps.storage, &report.reporter, &reporter)?;
+    CONSUMED_REPORTS.save(deps.storage, &digest, &Empty {})?;
+
+    // only the consumption data is taken from the report
+    let price = match oracle::fetch_price(deps.branch(), env)? {
+        Some(price) => price,
+        None => oracle::spot_median(deps.as_ref(), env)?,
+    };
+    let mut data = report.data;
+    data.state = ConsumptionUnitState::Reflected;
+    data.floor_price = tier::floor_price(deps.storage, data.commitment_tier, price)?;
+    data.created_at = env.block.time;
+    data.updated_at = env.block.time;
+
+    let response = mint(deps, env, info, report.token_id.clone(), report.owner, data)?;
+
+    Ok(response.add_event(
+        Event::new("consumption-unit::mint_from_report")
+            .add_attribute("reporter", report.reporter)
+            .add_attribute("token_id", report.token_id)
+            .add_attribute("relayer", info.sender.to_string()),
+    ))
+}
+
+pub fn query_reporter(deps: Deps, id: String) -> StdResult<ReporterResponse> {
+    let reporter = REPORTERS.load(deps.storage, &id)?;
+    Ok(ReporterResponse { id, reporter })
+}
+
+pub fn query_reporters(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ReportersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let reporters = REPORTERS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(id, reporter)| ReporterResponse { id, reporter }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ReportersResponse { reporters })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ExecuteMsg, PriceResponse};
+    use crate::testing::{cu_data, setup};
+    use crate::types::{ConsumptionUnitData, OracleConfig, TierConfig};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi, MockQuerier};
+    use cosmwasm_std::{
+        to_json_binary, Addr, ContractResult, Decimal, MemoryStorage, OwnedDeps, SystemResult,
+    };
+    use cw20::Denom;
+    use k256::ecdsa::signature::hazmat::PrehashSigner;
+
+    fn report(reporter: &str, token_id: &str, owner: &str, value: u128) -> ConsumptionReport {
+        let mut data = cu_data(value, 1);
+        // ignored in favor of the values set by the contract
+        data.state = ConsumptionUnitState::Selected;
+        data.floor_price = Decimal::zero();
+        ConsumptionReport {
+            reporter: reporter.to_string(),
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            expires_at: mock_env().block.time.plus_seconds(60),
+            data,
+        }
+    }
+
+    /// Configures the oracle to report a price of 2 and a floor multiplier of 0.5 for tier 1
+    fn setup_oracle(deps: &mut OwnedDeps<MemoryStorage, MockApi, MockQuerier>, creator: &Addr) {
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&PriceResponse {
+                    price: Decimal::percent(200),
+                    updated_at: mock_env().block.time,
+                })
+                .unwrap(),
+            ))
+        });
+        for msg in [
+            ExecuteMsg::SetOracleConfig {
+                config: Some(OracleConfig {
+                    max_price_age: 60,
+                    max_deviation: None,
+                    deviation_window: 0,
+                    min_price: None,
+                    max_price: None,
+                    twap_window: 0,
+                }),
+            },
+            ExecuteMsg::SetTierConfig {
+                tier: 1,
+                config: TierConfig {
+                    floor_multiplier: Some(Decimal::percent(50)),
+                    ..TierConfig::default()
+                },
+            },
+        ] {
+            execute(deps.as_mut(), mock_env(), message_info(creator, &[]), msg).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_mint_from_signed_report() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let relayer = deps.api.addr_make("relayer");
+        let owner_addr = deps.api.addr_make("owner");
+        let owner = owner_addr.to_string();
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        setup_oracle(&mut deps, &creator);
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::RegisterReporter {
+                id: "ed".to_string(),
+                key: ReporterKey::Ed25519(Binary::from([1u8; 33])),
+                allowed_currencies: vec![],
+                quota: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidReporterKey {}));
+
+        let ed25519_key = ed25519_zebra::SigningKey::from([7u8; 32]);
+        let ed25519_public = ed25519_zebra::VerificationKey::from(&ed25519_key);
+        let secp256k1_key = k256::ecdsa::SigningKey::from_slice(&[9u8; 32]).unwrap();
+        let secp256k1_public = secp256k1_key.verifying_key().to_sec1_bytes();
+
+        for (id, key) in [
+            (
+                "ed",
+                ReporterKey::Ed25519(Binary::from(<[u8; 32]>::from(ed25519_public))),
+            ),
+            (
+                "secp",
+                ReporterKey::Secp256k1(Binary::from(secp256k1_public.to_vec())),
+            ),
+        ] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                message_info(&creator, &[]),
+                ExecuteMsg::RegisterReporter {
+                    id: id.to_string(),
+                    key,
+                    allowed_currencies: vec!["usd".to_string()],
+                    quota: Some(Uint128::new(1_500)),
+                },
+            )
+            .unwrap();
+        }
+
+        let ed_report = report("ed", "1", &owner, 1_000);
+        let digest = report_digest(&env, &ed_report).unwrap();
+        let signature = Binary::from(<[u8; 64]>::from(ed25519_key.sign(&digest)));
+
+        // the signature does not cover a different owner
+        let mut tampered = ed_report.clone();
+        tampered.owner = relayer.to_string();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&relayer, &[]),
+            ExecuteMsg::MintFromReport {
+                report: tampered,
+                signature: Some(signature.clone()),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidSignature {}));
+
+        let mint_msg = ExecuteMsg::MintFromReport {
+            report: ed_report,
+            signature: Some(signature),
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&relayer, &[]),
+            mint_msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            query_reporter(deps.as_ref(), "ed".to_string())
+                .unwrap()
+                .reporter
+                .reported,
+            Uint128::new(1_000)
+        );
+        let data =
+            q_nft::query::query_nft_info::<ConsumptionUnitData>(deps.as_ref().storage, "1".into())
+                .unwrap()
+                .extension;
+        assert_eq!(data.state, ConsumptionUnitState::Reflected);
+        assert_eq!(data.floor_price, Decimal::one());
+
+        // the report cannot be replayed once the CU is burned
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner_addr, &[]),
+            ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&relayer, &[]),
+            mint_msg,
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ReportAlreadyConsumed {}));
+
+        let mut expired = env.clone();
+        expired.block.time = expired.block.time.plus_seconds(60);
+        let err = execute(
+            deps.as_mut(),
+            expired,
+            message_info(&relayer, &[]),
+            ExecuteMsg::MintFromReport {
+                report: report("ed", "2", &owner, 100),
+                signature: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ReportExpired {}));
+
+        let ed_report = report("ed", "2", &owner, 1_000);
+        let digest = report_digest(&env, &ed_report).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&relayer, &[]),
+            ExecuteMsg::MintFromReport {
+                report: ed_report,
+                signature: Some(Binary::from(<[u8; 64]>::from(ed25519_key.sign(&digest)))),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ReporterQuotaExceeded {}));
+
+        let secp_report = report("secp", "3", &owner, 1_000);
+        let digest = report_digest(&env, &secp_report).unwrap();
+        let signature: k256::ecdsa::Signature = secp256k1_key.sign_prehash(&digest).unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&relayer, &[]),
+            ExecuteMsg::MintFromReport {
+                report: secp_report,
+                signature: Some(Binary::from(signature.to_bytes().to_vec())),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_mint_from_address_reporter() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let provider = deps.api.addr_make("provider");
+        let owner = deps.api.addr_make("owner").to_string();
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &[]),
+            ExecuteMsg::RegisterReporter {
+                id: "provider".to_string(),
+                key: ReporterKey::Address(provider.clone()),
+                allowed_currencies: vec!["eur".to_string()],
+                quota: None,
+            },
+        )
+        .unwrap();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &[]),
+            ExecuteMsg::MintFromReport {
+                report: report("provider", "1", &owner, 10),
+                signature: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnauthorizedReporter {}));
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&provider, &[]),
+            ExecuteMsg::MintFromReport {
+                report: report("provider", "1", &owner, 10),
+                signature: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::CurrencyNotAllowed { .. }));
+
+        // without oracle checks the floor price is based on the spot median
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Ok(
+                to_json_binary(&PriceResponse {
+                    price: Decimal::percent(200),
+                    updated_at: mock_env().block.time,
+                })
+                .unwrap(),
+            ))
+        });
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &[]),
+            ExecuteMsg::RegisterReporter {
+                id: "any".to_string(),
+                key: ReporterKey::Address(provider.clone()),
+                allowed_currencies: vec![],
+                quota: None,
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&provider, &[]),
+            ExecuteMsg::MintFromReport {
+                report: report("any", "1", &owner, 10),
+                signature: None,
+            },
+        )
+        .unwrap();
+        let nft_info =
+            q_nft::query::query_nft_info::<ConsumptionUnitData>(&deps.storage, "1".to_string())
+                .unwrap();
+        assert_eq!(nft_info.extension.floor_price, Decimal::percent(200));
+    }
+}
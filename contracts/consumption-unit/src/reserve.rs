@@ -0,0 +1,275 @@
+use crate::contract::update_pool;
+use crate::error::ContractError;
+use crate::msg::{MintDepositResponse, ReceiveMsg, ReserveResponse, TierReserve};
+use crate::rewards;
+use crate::state::{
+    COLLECTED_FEES, MINT_DEPOSITS, RESERVE, REWARD_STATE, TIER_RESERVES, VESTING_SCHEDULES,
+};
+use crate::types::{CUConfig, ConsumptionUnitData};
+use cosmwasm_std::{
+    from_json, Addr, Deps, DepsMut, Env, Event, MessageInfo, Order, Response, StdResult, Storage,
+    Uint128,
+};
+use cw20::{Cw20ReceiveMsg, Denom};
+use q_nft::state::Cw721Config;
+use q_utils::asset::{denom_to_string, Asset};
+
+pub fn load_settlement_token(deps: Deps) -> StdResult<Denom> {
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    Ok(config
+        .collection_config
+        .load(deps.storage)?
+        .settlement_token)
+}
+
+/// Whether the contract holds settlement tokens in reserves, deposits, collected fees,
+/// vesting settlements or rewards. Rewards count once any were received, since accrued
+/// rewards are only known per holder.
+pub fn holds_settlement_tokens(storage: &dyn Storage, settlement_token: &Denom) -> StdResult<bool> {
+    let reward_state = REWARD_STATE.may_load(storage)?.unwrap_or_default();
+    let collected_fees = COLLECTED_FEES
+        .may_load(storage, &denom_to_string(settlement_token))?
+        .map(|fee| fee.amount)
+        .unwrap_or_default();
+    if !RESERVE.may_load(storage)?.unwrap_or_default().is_zero()
+        || !collected_fees.is_zero()
+        || !VESTING_SCHEDULES.is_empty(storage)
+        || !reward_state.reward_per_unit.is_zero()
+        || !reward_state.undistributed.is_zero()
+    {
+        return Ok(true);
+    }
+
+    let tier_reserves = TIER_RESERVES
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, amount)| amount));
+    let deposits = MINT_DEPOSITS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, amount)| amount));
+    for amount in tier_reserves.chain(deposits) {
+        if !amount?.is_zero() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Handles settlement tokens sent through the CW20 `Send` hook
+pub fn execute_receive(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let settlement_token = load_settlement_token(deps.as_ref())?;
+    let asset = Asset::from_cw20_receive(&settlement_token, info, &wrapper)?;
+
+    let sender = deps.api.addr_validate(&wrapper.sender)?;
+    let msg: ReceiveMsg = from_json(&wrapper.msg)?;
+    route(deps, env, sender, asset.amount, msg)
+}
+
+/// Handles a native settlement token sent along as funds
+pub fn execute_deposit(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    msg: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let settlement_token = load_settlement_token(deps.as_ref())?;
+    let asset = Asset::from_funds(&settlement_token, info)?;
+
+    route(deps, env, info.sender.clone(), asset.amount, msg)
+}
+
+/// Returns the amount of native settlement tokens sent along, zero if no funds were sent
+pub fn native_payment(deps: Deps, info: &MessageInfo) -> Result<Uint128, ContractError> {
+    payment(info, &load_settlement_token(deps)?)
+}
+
+/// Returns the amount of `denom` sent along as funds, zero if no funds were sent
+pub fn payment(info: &MessageInfo, denom: &Denom) -> Result<Uint128, ContractError> {
+    if info.funds.is_empty() {
+        return Ok(Uint128::zero());
+    }
+    Ok(Asset::from_funds(denom, info)?.amount)
+}
+
+pub fn add_to_reserve(storage: &mut dyn Storage, amount: Uint128) -> Result<(), ContractError> {
+    let reserve = RESERVE.may_load(storage)?.unwrap_or_default();
+    RESERVE.save(storage, &reserve.checked_add(amount)?)?;
+    Ok(())
+}
+
+fn route(
+    deps: DepsMut,
+    env: &Env,
+    sender: Addr,
+    amount: Uint128,
+    msg: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let event = match msg {
+        ReceiveMsg::FundReserve {} => {
+            add_to_reserve(deps.storage, amount)?;
+            Event::new("consumption-unit::fund_reserve")
+        }
+        ReceiveMsg::FundTier { tier } => {
+            TIER_RESERVES.update(deps.storage, tier, |reserve| -> StdResult<_> {
+                Ok(reserve.unwrap_or_default().checked_add(amount)?)
+            })?;
+            Event::new("consumption-unit::fund_tier").add_attribute("tier", tier.to_string())
+        }
+        ReceiveMsg::PayForMint {} => {
+            MINT_DEPOSITS.update(deps.storage, &sender, |deposit| -> StdResult<_> {
+                Ok(deposit.unwrap_or_default().checked_add(amount)?)
+            })?;
+            Event::new("consumption-unit::pay_for_mint")
+        }
+        ReceiveMsg::UpdatePool {
+            token_id,
+            new_commitment_tier_id,
+        } => {
+            return update_pool(deps, env, &sender, token_id, new_commitment_tier_id, amount);
+        }
+        ReceiveMsg::DistributeRewards {} => {
+            rewards::distribute(deps.storage, amount)?;
+            Event::new("consumption-unit::distribute_rewards")
+        }
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::deposit")
+        .add_event(
+            event
+                .add_attribute("sender", sender.to_string())
+                .add_attribute("amount", amount.to_string()),
+        ))
+}
+
+pub fn query_reserve(deps: Deps) -> StdResult<ReserveResponse> {
+    let tiers = TIER_RESERVES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(tier, amount)| TierReserve { tier, amount }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ReserveResponse {
+        settlement_token: load_settlement_token(deps)?,
+        general: RESERVE.may_load(deps.storage)?.unwrap_or_default(),
+        tiers,
+    })
+}
+
+pub fn query_mint_deposit(deps: Deps, address: String) -> StdResult<MintDepositResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(MintDepositResponse {
+        amount: MINT_DEPOSITS
+            .may_load(deps.storage, &address)?
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::ExecuteMsg;
+    use crate::testing::setup;
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{coins, to_json_binary};
+
+    #[test]
+    fn test_cw20_receive() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let token = deps.api.addr_make("settlement");
+        let user = deps.api.addr_make("user");
+        setup(deps.as_mut(), &creator, Denom::Cw20(token.clone()));
+
+        let receive = |msg: ReceiveMsg| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: user.to_string(),
+                amount: Uint128::new(100),
+                msg: to_json_binary(&msg).unwrap(),
+            })
+        };
+
+        // only the settlement token contract can call the hook
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user, &[]),
+            receive(ReceiveMsg::FundReserve {}),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnsupportedToken {}));
+
+        for msg in [
+            ReceiveMsg::FundReserve {},
+            ReceiveMsg::FundTier { tier: 2 },
+            ReceiveMsg::FundTier { tier: 2 },
+            ReceiveMsg::PayForMint {},
+        ] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                message_info(&token, &[]),
+                receive(msg),
+            )
+            .unwrap();
+        }
+
+        let reserve = query_reserve(deps.as_ref()).unwrap();
+        assert_eq!(reserve.general, Uint128::new(100));
+        assert_eq!(
+            reserve.tiers,
+            vec![TierReserve {
+                tier: 2,
+                amount: Uint128::new(200)
+            }]
+        );
+        let deposit = query_mint_deposit(deps.as_ref(), user.to_string()).unwrap();
+        assert_eq!(deposit.amount, Uint128::new(100));
+
+        // native deposits are rejected for a CW20 settlement token
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user, &coins(100, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::FundReserve {}),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnsupportedToken {}));
+    }
+
+    #[test]
+    fn test_native_deposit() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let user = deps.api.addr_make("user");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user, &coins(100, "other")),
+            ExecuteMsg::Deposit(ReceiveMsg::FundReserve {}),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Payment(_)));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&user, &coins(100, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::FundReserve {}),
+        )
+        .unwrap();
+
+        let reserve = query_reserve(deps.as_ref()).unwrap();
+        assert_eq!(reserve.general, Uint128::new(100));
+    }
+}
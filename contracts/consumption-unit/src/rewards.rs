@@ -0,0 +1,336 @@
+use crate::error::ContractError;
+use crate::freeze;
+use crate::msg::PendingRewardsResponse;
+use crate::reserve::load_settlement_token;
+use crate::state::{REWARD_ACCOUNTS, REWARD_STATE};
+use crate::types::{RewardAccount, RewardState};
+use cosmwasm_std::{
+    Addr, Decimal256, Deps, DepsMut, Env, Event, MessageInfo, Response, StdError, StdResult,
+    Storage, Uint128,
+};
+use q_utils::asset::Asset;
+use q_utils::fixed_point::{mul_decimal256, FixedPointError, Rounding};
+
+/// Accrues the rewards distributed since the last checkpoint of the account
+fn accrue(state: &RewardState, account: &mut RewardAccount) -> Result<(), FixedPointError> {
+    let accrued = mul_decimal256(
+        account.weight,
+        state.reward_per_unit - account.reward_per_unit,
+        Rounding::Down,
+    )?;
+    account.pending = account
+        .pending
+        .checked_add(accrued)
+        .map_err(|_| FixedPointError::Overflow {})?;
+    account.reward_per_unit = state.reward_per_unit;
+    Ok(())
+}
+
+/// Adds `weight` to the rewards weight of `owner`, called whenever a CU is minted
+/// or received
+pub fn add_weight(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    weight: Uint128,
+) -> Result<(), ContractError> {
+    let mut state = REWARD_STATE.may_load(storage)?.unwrap_or_default();
+    let mut account = REWARD_ACCOUNTS
+        .may_load(storage, owner)?
+        .unwrap_or_default();
+    accrue(&state, &mut account)?;
+
+    account.weight = account.weight.checked_add(weight)?;
+    state.total_weight = state.total_weight.checked_add(weight)?;
+    REWARD_ACCOUNTS.save(storage, owner, &account)?;
+    REWARD_STATE.save(storage, &state)?;
+    Ok(())
+}
+
+/// Removes `weight` from the rewards weight of `owner`, called whenever a CU is burned
+/// or sent away. Rewards accrued so far stay claimable by `owner`.
+pub fn remove_weight(
+    storage: &mut dyn Storage,
+    owner: &Addr,
+    weight: Uint128,
+) -> Result<(), ContractError> {
+    let mut state = REWARD_STATE.may_load(storage)?.unwrap_or_default();
+    let mut account = REWARD_ACCOUNTS
+        .may_load(storage, owner)?
+        .unwrap_or_default();
+    accrue(&state, &mut account)?;
+
+    account.weight = account.weight.checked_sub(weight)?;
+    state.total_weight = state.total_weight.checked_sub(weight)?;
+    REWARD_ACCOUNTS.save(storage, owner, &account)?;
+    REWARD_STATE.save(storage, &state)?;
+    Ok(())
+}
+
+/// Moves `weight` from `from` to `to`, called on CU transfers
+pub fn transfer_weight(
+    storage: &mut dyn Storage,
+    from: &Addr,
+    to: &Addr,
+    weight: Uint128,
+) -> Result<(), ContractError> {
+    remove_weight(storage, from, weight)?;
+    add_weight(storage, to, weight)
+}
+
+/// Distributes `amount` to all CU holders pro rata to their weight.
+/// Without any CU the amount is kept for the next distribution, as is the remainder
+/// which cannot be credited due to the precision of `reward_per_unit`.
+pub fn distribute(storage: &mut dyn Storage, amount: Uint128) -> Result<(), ContractError> {
+    let mut state = REWARD_STATE.may_load(storage)?.unwrap_or_default();
+    let amount = amount.checked_add(state.undistributed)?;
+    if state.total_weight.is_zero() {
+        state.undistributed = amount;
+    } else {
+        let increment = Decimal256::checked_from_ratio(amount, state.total_weight)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        let credited = mul_decimal256(state.total_weight, increment, Rounding::Down)?;
+        state.reward_per_unit = state.reward_per_unit.checked_add(increment)?;
+        state.undistributed = amount.checked_sub(credited)?;
+    }
+    REWARD_STATE.save(storage, &state)?;
+    Ok(())
+}
+
+pub fn execute_claim_rewards(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+) -> Result<Response, ContractError> {
+    freeze::assert_not_frozen(deps.storage, &info.sender)?;
+    let state = REWARD_STATE.may_load(deps.storage)?.unwrap_or_default();
+    let mut account = REWARD_ACCOUNTS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    accrue(&state, &mut account)?;
+
+    let claimed = account.pending;
+    if claimed.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    account.pending = Uint128::zero();
+    if account.weight.is_zero() {
+        REWARD_ACCOUNTS.remove(deps.storage, &info.sender);
+    } else {
+        REWARD_ACCOUNTS.save(deps.storage, &info.sender, &account)?;
+    }
+
+    let settlement_token = load_settlement_token(deps.as_ref())?;
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::claim_rewards")
+        .add_message(Asset::new(settlement_token, claimed).transfer_msg(&info.sender)?)
+        .add_event(
+            Event::new("consumption-unit::claim_rewards")
+                .add_attribute("owner", info.sender.to_string())
+                .add_attribute("amount", claimed.to_string()),
+        ))
+}
+
+pub fn query_pending_rewards(deps: Deps, owner: String) -> StdResult<PendingRewardsResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let state = REWARD_STATE.may_load(deps.storage)?.unwrap_or_default();
+    let mut account = REWARD_ACCOUNTS
+        .may_load(deps.storage, &owner)?
+        .unwrap_or_default();
+    accrue(&state, &mut account).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(PendingRewardsResponse {
+        owner,
+        weight: account.weight,
+        pending: account.pending,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ExecuteMsg, ReceiveMsg};
+    use crate::testing::{cu_data, mint, setup};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{coins, to_json_binary, BankMsg, Coin};
+    use cw20::Denom;
+
+    fn distribute_msg(deps: DepsMut, sender: &Addr, amount: u128) {
+        execute(
+            deps,
+            mock_env(),
+            message_info(sender, &coins(amount, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::DistributeRewards {}),
+        )
+        .unwrap();
+    }
+
+    fn pending(deps: Deps, owner: &Addr) -> u128 {
+        query_pending_rewards(deps, owner.to_string())
+            .unwrap()
+            .pending
+            .u128()
+    }
+
+    #[test]
+    fn test_reward_distribution() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let alice = deps.api.addr_make("alice");
+        let bob = deps.api.addr_make("bob");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+
+        // nobody holds a CU yet, the rewards are carried over
+        distribute_msg(deps.as_mut(), &creator, 100);
+        mint(deps.as_mut(), &creator, "1", &alice, cu_data(100, 1)).unwrap();
+        mint(deps.as_mut(), &creator, "2", &bob, cu_data(300, 1)).unwrap();
+        distribute_msg(deps.as_mut(), &creator, 300);
+        assert_eq!(pending(deps.as_ref(), &alice), 100);
+        assert_eq!(pending(deps.as_ref(), &bob), 300);
+
+        // rewards accrued before a transfer stay with the previous owner
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&bob, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: alice.to_string(),
+                token_id: "2".to_string(),
+            },
+        )
+        .unwrap();
+        distribute_msg(deps.as_mut(), &creator, 400);
+        assert_eq!(pending(deps.as_ref(), &alice), 500);
+        assert_eq!(pending(deps.as_ref(), &bob), 300);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&alice, &[]),
+            ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        distribute_msg(deps.as_mut(), &creator, 30);
+        assert_eq!(pending(deps.as_ref(), &alice), 530);
+
+        let response = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&bob, &[]),
+            ExecuteMsg::ClaimRewards {},
+        )
+        .unwrap();
+        assert_eq!(
+            response.messages[0].msg,
+            BankMsg::Send {
+                to_address: bob.to_string(),
+                amount: vec![Coin::new(300u128, "settlement")],
+            }
+            .into()
+        );
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&bob, &[]),
+            ExecuteMsg::ClaimRewards {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NothingToClaim {}));
+    }
+
+    #[test]
+    fn test_escrowed_rewards() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let alice = deps.api.addr_make("alice");
+        let bob = deps.api.addr_make("bob");
+        let staking = deps.api.addr_make("staking");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&creator, &[]),
+            ExecuteMsg::AddEscrowContract {
+                contract: staking.to_string(),
+            },
+        )
+        .unwrap();
+        mint(deps.as_mut(), &creator, "1", &alice, cu_data(100, 1)).unwrap();
+
+        // the depositor keeps earning while the CU is escrowed
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&alice, &[]),
+            ExecuteMsg::SendNft {
+                contract: staking.to_string(),
+                token_id: "1".to_string(),
+                msg: to_json_binary("stake").unwrap(),
+            },
+        )
+        .unwrap();
+        distribute_msg(deps.as_mut(), &creator, 100);
+        assert_eq!(pending(deps.as_ref(), &alice), 100);
+        assert_eq!(pending(deps.as_ref(), &staking), 0);
+
+        // and the new owner earns once the CU leaves the escrow
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&staking, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: bob.to_string(),
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        distribute_msg(deps.as_mut(), &creator, 50);
+        assert_eq!(pending(deps.as_ref(), &alice), 100);
+        assert_eq!(pending(deps.as_ref(), &bob), 50);
+    }
+
+    #[test]
+    fn test_distribution_remainder() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let alice = deps.api.addr_make("alice");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        mint(
+            deps.as_mut(),
+            &creator,
+            "1",
+            &alice,
+            cu_data(3_000_000_000_000_000_000, 1),
+        )
+        .unwrap();
+
+        // less than one unit of `reward_per_unit` is carried over
+        distribute_msg(deps.as_mut(), &creator, 2);
+        assert_eq!(pending(deps.as_ref(), &alice), 0);
+        assert_eq!(
+            REWARD_STATE.load(&deps.storage).unwrap().undistributed,
+            Uint128::new(2)
+        );
+
+        distribute_msg(deps.as_mut(), &creator, 5);
+        assert_eq!(pending(deps.as_ref(), &alice), 6);
+        assert_eq!(
+            REWARD_STATE.load(&deps.storage).unwrap().undistributed,
+            Uint128::new(1)
+        );
+    }
+}
@@ -0,0 +1,440 @@
+use crate::dispute;
+use crate::error::ContractError;
+use crate::expiry::assert_not_expired;
+use crate::freeze;
+use crate::history;
+use crate::msg::RoundResponse;
+use crate::state::{NOMINATIONS, ROUNDS, ROUND_NOMINATIONS, ROUND_SCHEDULES};
+use crate::types::{
+    CUConfig, ConsumptionUnitData, ConsumptionUnitState, HistoryAction, RoundConfig,
+};
+use cosmwasm_std::{
+    Addr, Deps, DepsMut, Empty, Env, Event, MessageInfo, Order, Response, StdError, StdResult,
+    Storage, Timestamp,
+};
+use cw_ownable::OwnershipError;
+use cw_storage_plus::Bound;
+use q_nft::error::Cw721ContractError;
+use q_nft::execute::assert_creator;
+use q_nft::msg::TokensResponse;
+use q_nft::query::{DEFAULT_LIMIT, MAX_LIMIT};
+use q_nft::state::Cw721Config;
+
+/// Schedule of `round_id` and the index of the round within it
+fn schedule_of(storage: &dyn Storage, round_id: u64) -> StdResult<Option<(RoundConfig, u64)>> {
+    Ok(ROUND_SCHEDULES
+        .range(
+            storage,
+            None,
+            Some(Bound::inclusive(round_id)),
+            Order::Descending,
+        )
+        .next()
+        .transpose()?
+        .map(|(first_round, config)| (config, round_id - first_round)))
+}
+
+/// Id of the round running at `time`, `None` before the first round and between schedules
+fn round_at(storage: &dyn Storage, time: Timestamp) -> StdResult<Option<u64>> {
+    let mut next_first_round = None;
+    for item in ROUND_SCHEDULES.range(storage, None, None, Order::Descending) {
+        let (first_round, config) = item?;
+        if let Some(index) = config.round_at(time) {
+            // the rounds of a schedule end where the next schedule takes over
+            return Ok(first_round
+                .checked_add(index)
+                .filter(|round_id| next_first_round.map_or(true, |next| *round_id < next)));
+        }
+        next_first_round = Some(first_round);
+    }
+    Ok(None)
+}
+
+/// Sets the schedule of the rounds which have not started yet. The round running now
+/// keeps its window, the new schedule takes over from the next round and cannot start
+/// before the running round ends.
+pub fn execute_set_round_config(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    config: RoundConfig,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    if config.duration == 0 || config.draw_period >= config.duration {
+        return Err(ContractError::InvalidRoundConfig {});
+    }
+
+    let latest = ROUND_SCHEDULES
+        .range(deps.storage, None, None, Order::Descending)
+        .next()
+        .transpose()?;
+    let (first_round, earliest_start) = match latest {
+        None => (0, None),
+        Some((first_round, latest)) => match latest.round_at(env.block.time) {
+            Some(index) => (
+                first_round
+                    .checked_add(index)
+                    .and_then(|round_id| round_id.checked_add(1))
+                    .ok_or(ContractError::InvalidRoundConfig {})?,
+                Some(latest.round_end(index)?),
+            ),
+            // the latest schedule has not started yet and is replaced
+            None if first_round == 0 => (0, None),
+            None => {
+                let (previous, index) = schedule_of(deps.storage, first_round - 1)?
+                    .ok_or(ContractError::InvalidRoundConfig {})?;
+                (first_round, Some(previous.round_end(index)?))
+            }
+        },
+    };
+    if earliest_start.is_some_and(|earliest| config.start < earliest) {
+        return Err(ContractError::InvalidRoundConfig {});
+    }
+    ROUND_SCHEDULES.save(deps.storage, first_round, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::set_round_config")
+        .add_event(
+            Event::new("consumption-unit::set_round_config")
+                .add_attribute("first_round", first_round.to_string())
+                .add_attribute("start", config.start.to_string())
+                .add_attribute("duration", config.duration.to_string())
+                .add_attribute("draw_period", config.draw_period.to_string()),
+        ))
+}
+
+/// Nominates the CU for the current round, can be performed by the owner only
+/// while the nomination window of the round is open.
+pub fn nominate(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    let mut nft_info = config.nft_info.load(deps.storage, &token_id)?;
+    if nft_info.owner != sender {
+        return Err(ContractError::Cw721ContractError(
+            Cw721ContractError::Ownership(OwnershipError::NotOwner),
+        ));
+    }
+    if nft_info.extension.state == ConsumptionUnitState::Selected {
+        return Err(ContractError::WrongInput {});
+    }
+    dispute::assert_not_disputed(&token_id, &nft_info.extension)?;
+    freeze::assert_not_frozen(deps.storage, sender)?;
+    assert_not_expired(deps.storage, env, &token_id, &nft_info.extension)?;
+
+    let round_id =
+        round_at(deps.storage, env.block.time)?.ok_or(ContractError::NoActiveRound {})?;
+    let (round_config, index) =
+        schedule_of(deps.storage, round_id)?.ok_or(ContractError::NoActiveRound {})?;
+    if env.block.time >= round_config.nomination_end(index)? {
+        return Err(ContractError::NominationClosed { round_id });
+    }
+    if NOMINATIONS.may_load(deps.storage, &token_id)? == Some(round_id) {
+        return Err(ContractError::AlreadyNominated { token_id, round_id });
+    }
+
+    history::record(
+        deps.storage,
+        env,
+        &token_id,
+        sender,
+        HistoryAction::StateChange {
+            old_state: nft_info.extension.state,
+            new_state: ConsumptionUnitState::Nominated,
+        },
+    )?;
+    nft_info.extension.state = ConsumptionUnitState::Nominated;
+    config.nft_info.save(deps.storage, &token_id, &nft_info)?;
+
+    NOMINATIONS.save(deps.storage, &token_id, &round_id)?;
+    ROUND_NOMINATIONS.save(deps.storage, (round_id, &token_id), &Empty {})?;
+    let mut round = ROUNDS.may_load(deps.storage, round_id)?.unwrap_or_default();
+    round.nominated += 1;
+    ROUNDS.save(deps.storage, round_id, &round)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::nominate")
+        .add_event(
+            Event::new("consumption-unit::nominate")
+                .add_attribute("token_id", token_id)
+                .add_attribute("round_id", round_id.to_string()),
+        ))
+}
+
+/// Marks the drawn CUs of a round as selected, can only be called by the collection creator
+/// once the nomination window of the round is closed.
+pub fn execute_select_winners(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    round_id: u64,
+    token_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let (round_config, index) =
+        schedule_of(deps.storage, round_id)?.ok_or(ContractError::NoActiveRound {})?;
+    if env.block.time < round_config.nomination_end(index)? {
+        return Err(ContractError::NominationOpen { round_id });
+    }
+
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    for token_id in &token_ids {
+        let mut nft_info = config.nft_info.load(deps.storage, token_id)?;
+        if nft_info.extension.state != ConsumptionUnitState::Nominated
+            || NOMINATIONS.may_load(deps.storage, token_id)? != Some(round_id)
+        {
+            return Err(ContractError::NotNominated {
+                token_id: token_id.clone(),
+                round_id,
+            });
+        }
+        history::record(
+            deps.storage,
+            env,
+            token_id,
+            &info.sender,
+            HistoryAction::StateChange {
+                old_state: nft_info.extension.state,
+                new_state: ConsumptionUnitState::Selected,
+            },
+        )?;
+        nft_info.extension.state = ConsumptionUnitState::Selected;
+        config.nft_info.save(deps.storage, token_id, &nft_info)?;
+    }
+
+    let mut round = ROUNDS.may_load(deps.storage, round_id)?.unwrap_or_default();
+    round.selected += token_ids.len() as u64;
+    ROUNDS.save(deps.storage, round_id, &round)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::select_winners")
+        .add_event(
+            Event::new("consumption-unit::select_winners")
+                .add_attribute("round_id", round_id.to_string())
+                .add_attribute("token_ids", token_ids.join(",")),
+        ))
+}
+
+pub fn query_round(deps: Deps, round_id: u64) -> StdResult<RoundResponse> {
+    let (config, index) =
+        schedule_of(deps.storage, round_id)?.ok_or_else(|| StdError::not_found("round"))?;
+    let round = ROUNDS.may_load(deps.storage, round_id)?.unwrap_or_default();
+    Ok(RoundResponse {
+        id: round_id,
+        start: config.round_start(index)?,
+        nomination_end: config.nomination_end(index)?,
+        end: config.round_end(index)?,
+        nominated: round.nominated,
+        selected: round.selected,
+    })
+}
+
+pub fn query_current_round(deps: Deps, env: &Env) -> StdResult<Option<RoundResponse>> {
+    match round_at(deps.storage, env.block.time)? {
+        Some(round_id) => Ok(Some(query_round(deps, round_id)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn query_round_nominations(
+    deps: Deps,
+    round_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(|s| Bound::ExclusiveRaw(s.into()));
+
+    let tokens = ROUND_NOMINATIONS
+        .prefix(round_id)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(TokensResponse { tokens })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg};
+    use crate::testing::{cu_data, mint, setup};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cw20::Denom;
+
+    fn nominate_msg(token_id: &str) -> ExecuteMsg {
+        ExecuteMsg::UpdateNftInfo {
+            token_id: token_id.to_string(),
+            extension: ConsumptionUnitExtensionUpdate::Nominate {},
+        }
+    }
+
+    #[test]
+    fn test_round_lifecycle() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(100, 1)).unwrap();
+        mint(deps.as_mut(), &creator, "2", &owner, cu_data(100, 1)).unwrap();
+
+        let mut env = mock_env();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            nominate_msg("1"),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NoActiveRound {}));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetRoundConfig {
+                config: RoundConfig {
+                    start: env.block.time.minus_seconds(100),
+                    duration: 100,
+                    draw_period: 20,
+                },
+            },
+        )
+        .unwrap();
+
+        // round 1 is open
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            nominate_msg("1"),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            nominate_msg("1"),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::AlreadyNominated { round_id: 1, .. }
+        ));
+
+        let select_msg = ExecuteMsg::SelectWinners {
+            round_id: 1,
+            token_ids: vec!["1".to_string()],
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            select_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NominationOpen { round_id: 1 }));
+
+        // draw period of round 1
+        env.block.time = env.block.time.plus_seconds(85);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            nominate_msg("2"),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::NominationClosed { round_id: 1 }
+        ));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            select_msg,
+        )
+        .unwrap();
+
+        let round = query_current_round(deps.as_ref(), &env).unwrap().unwrap();
+        assert_eq!(round.id, 1);
+        assert_eq!(round.nominated, 1);
+        assert_eq!(round.selected, 1);
+        assert_eq!(
+            query_round_nominations(deps.as_ref(), 1, None, None)
+                .unwrap()
+                .tokens,
+            vec!["1".to_string()]
+        );
+
+        let nft_info = q_nft::query::query_nft_info::<ConsumptionUnitData>(
+            deps.as_ref().storage,
+            "1".to_string(),
+        )
+        .unwrap();
+        assert_eq!(nft_info.extension.state, ConsumptionUnitState::Selected);
+    }
+
+    #[test]
+    fn test_round_config_change() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        let now = env.block.time;
+        let set_round_config = |deps: DepsMut, env: &Env, start: Timestamp, duration: u64| {
+            execute(
+                deps,
+                env.clone(),
+                message_info(&creator, &[]),
+                ExecuteMsg::SetRoundConfig {
+                    config: RoundConfig {
+                        start,
+                        duration,
+                        draw_period: 20,
+                    },
+                },
+            )
+        };
+        set_round_config(deps.as_mut(), &env, now.minus_seconds(100), 100).unwrap();
+
+        // round 1 runs until now + 100
+        let err = set_round_config(deps.as_mut(), &env, now, 50).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidRoundConfig {}));
+        set_round_config(deps.as_mut(), &env, now.plus_seconds(100), 50).unwrap();
+
+        let round = query_current_round(deps.as_ref(), &env).unwrap().unwrap();
+        assert_eq!((round.id, round.end), (1, now.plus_seconds(100)));
+        let round = query_round(deps.as_ref(), 2).unwrap();
+        assert_eq!(
+            (round.start, round.end),
+            (now.plus_seconds(100), now.plus_seconds(150))
+        );
+
+        // the pending schedule can be replaced until it starts
+        set_round_config(deps.as_mut(), &env, now.plus_seconds(200), 50).unwrap();
+        env.block.time = now.plus_seconds(150);
+        assert!(query_current_round(deps.as_ref(), &env).unwrap().is_none());
+        env.block.time = now.plus_seconds(260);
+        let round = query_current_round(deps.as_ref(), &env).unwrap().unwrap();
+        assert_eq!((round.id, round.start), (3, now.plus_seconds(250)));
+
+        // rounds beyond the range of timestamps do not panic
+        assert!(query_round(deps.as_ref(), u64::MAX).is_err());
+    }
+}
@@ -0,0 +1,472 @@
+use crate::contract::burn;
+use crate::error::ContractError;
+use crate::msg::VestingScheduleResponse;
+use crate::reserve::load_settlement_token;
+use crate::state::{RESERVE, TIER_RESERVES, VESTING_CONFIG, VESTING_SCHEDULES};
+use crate::types::{
+    CUConfig, ConsumptionUnitData, ConsumptionUnitState, VestingConfig, VestingSchedule,
+};
+use crate::{dispute, freeze, penalty, tier};
+use cosmwasm_std::{
+    Deps, DepsMut, Env, Event, MessageInfo, Response, StdError, StdResult, Storage, Uint128,
+};
+use cw_ownable::OwnershipError;
+use q_nft::error::Cw721ContractError;
+use q_nft::execute::assert_creator;
+use q_nft::state::Cw721Config;
+use q_utils::asset::Asset;
+
+/// Takes `amount` from the reserve of `tier`, the general reserve covers the rest
+fn take_from_reserves(
+    storage: &mut dyn Storage,
+    tier: u16,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let tier_reserve = TIER_RESERVES.may_load(storage, tier)?.unwrap_or_default();
+    let general = RESERVE.may_load(storage)?.unwrap_or_default();
+    let available = tier_reserve.checked_add(general)?;
+    if available < amount {
+        return Err(ContractError::InsufficientReserve {
+            required: amount,
+            available,
+        });
+    }
+
+    let from_tier = tier_reserve.min(amount);
+    TIER_RESERVES.save(storage, tier, &(tier_reserve - from_tier))?;
+    RESERVE.save(storage, &(general - (amount - from_tier)))?;
+    Ok(())
+}
+
+pub fn execute_set_vesting_config(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    config: Option<VestingConfig>,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let mut event = Event::new("consumption-unit::set_vesting_config");
+    match config {
+        Some(config) => {
+            if config.duration == 0 || config.cliff > config.duration {
+                return Err(ContractError::InvalidVestingConfig {});
+            }
+            event = event
+                .add_attribute("cliff", config.cliff.to_string())
+                .add_attribute("duration", config.duration.to_string());
+            VESTING_CONFIG.save(deps.storage, &config)?;
+        }
+        None => VESTING_CONFIG.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::set_vesting_config")
+        .add_event(event))
+}
+
+/// Pays out the floor value of a selected CU to its owner, or vests it while vesting
+/// is configured, and burns the CU. Settling leaves the tier of the CU, so its lock
+/// applies and an early exit penalty is deducted from the payout.
+pub fn execute_claim_settlement(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let config = Cw721Config::<ConsumptionUnitData, CUConfig>::default();
+    let nft_info = config.nft_info.load(deps.storage, &token_id)?;
+    if nft_info.owner != info.sender {
+        return Err(ContractError::Cw721ContractError(
+            Cw721ContractError::Ownership(OwnershipError::NotOwner),
+        ));
+    }
+    dispute::assert_not_disputed(&token_id, &nft_info.extension)?;
+    freeze::assert_not_frozen(deps.storage, &info.sender)?;
+    if nft_info.extension.state != ConsumptionUnitState::Selected {
+        return Err(ContractError::NotSelected { token_id });
+    }
+
+    tier::assert_can_leave_tier(deps.storage, env, &nft_info.extension)?;
+
+    let tier = nft_info.extension.commitment_tier;
+    let floor_value = nft_info.extension.floor_value()?;
+    let penalty =
+        penalty::early_exit_penalty(deps.storage, env, &nft_info.extension)?.min(floor_value);
+    take_from_reserves(deps.storage, tier, floor_value)?;
+    let penalty_msg = penalty::route_penalty(deps.storage, tier, penalty)?;
+    let amount = floor_value - penalty;
+
+    burn(deps.storage, env, &info.sender, &token_id, nft_info)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "consumption-unit::claim_settlement")
+        .add_messages(penalty_msg);
+    let mut event = Event::new("consumption-unit::claim_settlement")
+        .add_attribute("token_id", &token_id)
+        .add_attribute("owner", info.sender.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("penalty", penalty.to_string());
+
+    match VESTING_CONFIG.may_load(deps.storage)? {
+        Some(vesting) if !amount.is_zero() => {
+            let schedule = VestingSchedule {
+                amount,
+                released: Uint128::zero(),
+                start: env.block.time,
+                cliff: env.block.time.plus_seconds(vesting.cliff),
+                end: env.block.time.plus_seconds(vesting.duration),
+            };
+            event = event.add_attribute("vesting_end", schedule.end.to_string());
+            let mut schedules = VESTING_SCHEDULES
+                .may_load(deps.storage, &info.sender)?
+                .unwrap_or_default();
+            schedules.push(schedule);
+            VESTING_SCHEDULES.save(deps.storage, &info.sender, &schedules)?;
+        }
+        _ if !amount.is_zero() => {
+            let settlement_token = load_settlement_token(deps.as_ref())?;
+            response = response
+                .add_message(Asset::new(settlement_token, amount).transfer_msg(&info.sender)?);
+        }
+        _ => {}
+    }
+
+    Ok(response.add_event(event))
+}
+
+/// Releases the unlocked part of all vesting settlements of the sender
+pub fn execute_claim_vested(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+) -> Result<Response, ContractError> {
+    freeze::assert_not_frozen(deps.storage, &info.sender)?;
+    let schedules = VESTING_SCHEDULES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    let mut claimed = Uint128::zero();
+    let mut remaining = vec![];
+    for mut schedule in schedules {
+        let vested = schedule.vested(env)?;
+        claimed = claimed.checked_add(vested - schedule.released)?;
+        schedule.released = vested;
+        if schedule.released < schedule.amount {
+            remaining.push(schedule);
+        }
+    }
+    if claimed.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    if remaining.is_empty() {
+        VESTING_SCHEDULES.remove(deps.storage, &info.sender);
+    } else {
+        VESTING_SCHEDULES.save(deps.storage, &info.sender, &remaining)?;
+    }
+
+    let settlement_token = load_settlement_token(deps.as_ref())?;
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::claim_vested")
+        .add_message(Asset::new(settlement_token, claimed).transfer_msg(&info.sender)?)
+        .add_event(
+            Event::new("consumption-unit::claim_vested")
+                .add_attribute("owner", info.sender.to_string())
+                .add_attribute("amount", claimed.to_string()),
+        ))
+}
+
+pub fn query_vesting_config(deps: Deps) -> StdResult<Option<VestingConfig>> {
+    VESTING_CONFIG.may_load(deps.storage)
+}
+
+pub fn query_vesting_schedule(
+    deps: Deps,
+    env: &Env,
+    owner: String,
+) -> StdResult<VestingScheduleResponse> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let schedules = VESTING_SCHEDULES
+        .may_load(deps.storage, &owner)?
+        .unwrap_or_default();
+
+    let mut total = Uint128::zero();
+    let mut released = Uint128::zero();
+    let mut claimable = Uint128::zero();
+    for schedule in &schedules {
+        let vested = schedule
+            .vested(env)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        total = total.checked_add(schedule.amount)?;
+        released = released.checked_add(schedule.released)?;
+        claimable = claimable.checked_add(vested - schedule.released)?;
+    }
+
+    Ok(VestingScheduleResponse {
+        owner,
+        schedules,
+        total,
+        released,
+        claimable,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ExecuteMsg, ReceiveMsg};
+    use crate::testing::{cu_data, mint, setup};
+    use crate::types::{PenaltyBase, PenaltyConfig, PenaltyDestination, TierConfig};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{coins, Addr, BankMsg, Coin, Decimal};
+    use cw20::Denom;
+
+    /// Mints CU `token_id` worth a floor value of 500 and marks it as selected
+    fn mint_selected(deps: DepsMut, creator: &Addr, token_id: &str, owner: &Addr) {
+        let mut data = cu_data(1_000, 1);
+        data.state = ConsumptionUnitState::Selected;
+        mint(deps, creator, token_id, owner, data).unwrap();
+    }
+
+    fn claim(deps: DepsMut, env: &Env, sender: &Addr, msg: ExecuteMsg) -> Response {
+        execute(deps, env.clone(), message_info(sender, &[]), msg).unwrap()
+    }
+
+    fn payout(recipient: &Addr, amount: u128) -> BankMsg {
+        BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin::new(amount, "settlement")],
+        }
+    }
+
+    #[test]
+    fn test_claim_settlement() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(1_000, 1)).unwrap();
+        mint_selected(deps.as_mut(), &creator, "2", &owner);
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            ExecuteMsg::ClaimSettlement {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotSelected { .. }));
+
+        let claim_msg = ExecuteMsg::ClaimSettlement {
+            token_id: "2".to_string(),
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &coins(300, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::FundTier { tier: 1 }),
+        )
+        .unwrap();
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            claim_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::InsufficientReserve { available, .. } if available.u128() == 300
+        ));
+
+        // the general reserve tops up the tier reserve
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &coins(250, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::FundReserve {}),
+        )
+        .unwrap();
+        let response = claim(deps.as_mut(), &env, &owner, claim_msg);
+        assert_eq!(response.messages[0].msg, payout(&owner, 500).into());
+        assert_eq!(
+            TIER_RESERVES.load(&deps.storage, 1).unwrap(),
+            Uint128::zero()
+        );
+        assert_eq!(RESERVE.load(&deps.storage).unwrap(), Uint128::new(50));
+        assert!(!Cw721Config::<ConsumptionUnitData, CUConfig>::default()
+            .nft_info
+            .has(&deps.storage, "2"));
+    }
+
+    #[test]
+    fn test_settlement_in_locked_tier() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &coins(500, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::FundReserve {}),
+        )
+        .unwrap();
+        mint_selected(deps.as_mut(), &creator, "1", &owner);
+
+        let set_tier_config = |deps: DepsMut, early_exit_penalty| {
+            execute(
+                deps,
+                env.clone(),
+                message_info(&creator, &[]),
+                ExecuteMsg::SetTierConfig {
+                    tier: 1,
+                    config: TierConfig {
+                        lock_period: 100,
+                        early_exit_penalty,
+                        ..TierConfig::default()
+                    },
+                },
+            )
+            .unwrap();
+        };
+        let claim_msg = ExecuteMsg::ClaimSettlement {
+            token_id: "1".to_string(),
+        };
+
+        set_tier_config(deps.as_mut(), None);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            claim_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::TierLocked { tier: 1, .. }));
+
+        // leaving the tier early costs 10% of the floor value, which goes to the tier reserve
+        set_tier_config(
+            deps.as_mut(),
+            Some(PenaltyConfig {
+                rate: Decimal::percent(10),
+                base: PenaltyBase::FloorValue,
+                destination: PenaltyDestination::Pool,
+            }),
+        );
+        let response = claim(deps.as_mut(), &env, &owner, claim_msg);
+        assert_eq!(response.messages[0].msg, payout(&owner, 450).into());
+        assert_eq!(
+            TIER_RESERVES.load(&deps.storage, 1).unwrap(),
+            Uint128::new(50)
+        );
+        assert_eq!(RESERVE.load(&deps.storage).unwrap(), Uint128::zero());
+    }
+
+    #[test]
+    fn test_vested_settlement() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &coins(1_000, "settlement")),
+            ExecuteMsg::Deposit(ReceiveMsg::FundReserve {}),
+        )
+        .unwrap();
+        mint_selected(deps.as_mut(), &creator, "1", &owner);
+        mint_selected(deps.as_mut(), &creator, "2", &owner);
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::SetVestingConfig {
+                config: Some(VestingConfig {
+                    cliff: 200,
+                    duration: 100,
+                }),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidVestingConfig {}));
+        claim(
+            deps.as_mut(),
+            &env,
+            &creator,
+            ExecuteMsg::SetVestingConfig {
+                config: Some(VestingConfig {
+                    cliff: 25,
+                    duration: 100,
+                }),
+            },
+        );
+
+        let response = claim(
+            deps.as_mut(),
+            &env,
+            &owner,
+            ExecuteMsg::ClaimSettlement {
+                token_id: "1".to_string(),
+            },
+        );
+        assert!(response.messages.is_empty());
+
+        // nothing is released before the cliff
+        env.block.time = env.block.time.plus_seconds(20);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&owner, &[]),
+            ExecuteMsg::ClaimVested {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NothingToClaim {}));
+        claim(
+            deps.as_mut(),
+            &env,
+            &owner,
+            ExecuteMsg::ClaimSettlement {
+                token_id: "2".to_string(),
+            },
+        );
+
+        env.block.time = env.block.time.plus_seconds(20);
+        let vesting = query_vesting_schedule(deps.as_ref(), &env, owner.to_string()).unwrap();
+        assert_eq!(vesting.total, Uint128::new(1_000));
+        // 500 * 40 / 100 of the first claim, the second one is still before its cliff
+        assert_eq!(vesting.claimable, Uint128::new(200));
+        let response = claim(deps.as_mut(), &env, &owner, ExecuteMsg::ClaimVested {});
+        assert_eq!(response.messages[0].msg, payout(&owner, 200).into());
+
+        env.block.time = env.block.time.plus_seconds(60);
+        let response = claim(deps.as_mut(), &env, &owner, ExecuteMsg::ClaimVested {});
+        // the rest of the first claim and 500 * 80 / 100 of the second one
+        assert_eq!(response.messages[0].msg, payout(&owner, 700).into());
+
+        env.block.time = env.block.time.plus_seconds(20);
+        claim(deps.as_mut(), &env, &owner, ExecuteMsg::ClaimVested {});
+        let vesting = query_vesting_schedule(deps.as_ref(), &env, owner.to_string()).unwrap();
+        assert!(vesting.schedules.is_empty());
+    }
+}
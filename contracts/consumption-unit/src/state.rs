@@ -0,0 +1,92 @@
+use crate::types::{
+    AcceptedPrice, CollectedFee, ComplianceConfig, Dispute, ExpiryConfig, FreezeInfo, HistoryEntry,
+    MintFeeConfig, OracleConfig, PoolCapacity, PoolStats, PriceObservation, Reporter,
+    RewardAccount, RewardState, RoundConfig, RoundInfo, TierConfig, VestingConfig, VestingSchedule,
+};
+use cosmwasm_std::{Addr, Empty, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy};
+
+/// Settlement tokens funded into the general reserve used to pay out CUs
+pub const RESERVE: Item<Uint128> = Item::new("reserve");
+/// Settlement tokens funded into the reserve of a specific commitment tier
+pub const TIER_RESERVES: Map<u16, Uint128> = Map::new("tier_reserves");
+/// Settlement tokens prepaid by an address to cover its mint costs
+pub const MINT_DEPOSITS: Map<&Addr, Uint128> = Map::new("mint_deposits");
+/// Aggregated figures per commitment tier
+pub const POOL_STATS: Map<u16, PoolStats> = Map::new("pool_stats");
+/// Optional capacity caps per commitment tier
+pub const POOL_CAPACITIES: Map<u16, PoolCapacity> = Map::new("pool_capacities");
+/// Switching rules per commitment tier
+pub const TIER_CONFIGS: Map<u16, TierConfig> = Map::new("tier_configs");
+/// Schedules of the raffle rounds keyed by the id of their first round,
+/// nominations are disabled until one is set
+pub const ROUND_SCHEDULES: Map<u64, RoundConfig> = Map::new("round_schedules");
+/// Counters per round id
+pub const ROUNDS: Map<u64, RoundInfo> = Map::new("rounds");
+/// Token ids nominated in each round
+pub const ROUND_NOMINATIONS: Map<(u64, &str), Empty> = Map::new("round_nominations");
+/// Round id in which a token was last nominated
+pub const NOMINATIONS: Map<&str, u64> = Map::new("nominations");
+/// Fee charged for every minted CU, minting is free if not set
+pub const MINT_FEE: Item<MintFeeConfig> = Item::new("mint_fee");
+/// Collected mint fees keyed by the string representation of their denom
+pub const COLLECTED_FEES: Map<&str, CollectedFee> = Map::new("collected_fees");
+/// Registered consumption reporters keyed by their id
+pub const REPORTERS: Map<&str, Reporter> = Map::new("reporters");
+/// Digests of the reports already minted, a report can only be submitted once
+pub const CONSUMED_REPORTS: Map<&[u8], Empty> = Map::new("consumed_reports");
+/// Sanity checks of oracle prices, floor prices are not recomputed while it is not set
+pub const ORACLE_CONFIG: Item<OracleConfig> = Item::new("oracle_config");
+/// Last oracle price which passed all checks
+pub const LAST_PRICE: Item<AcceptedPrice> = Item::new("last_price");
+/// Accepted prices by the time in seconds they were observed,
+/// only observations needed to cover the TWAP window are kept
+pub const PRICE_OBSERVATIONS: Map<u64, PriceObservation> = Map::new("price_observations");
+/// Expiry of unselected CUs, CUs never expire while it is not set
+pub const EXPIRY_CONFIG: Item<ExpiryConfig> = Item::new("expiry_config");
+/// Last token checked by `SweepExpired`, the next sweep continues after it
+pub const SWEEP_CURSOR: Item<String> = Item::new("sweep_cursor");
+/// Vesting of settlement payouts, settlements are paid out at once while it is not set
+pub const VESTING_CONFIG: Item<VestingConfig> = Item::new("vesting_config");
+/// Settlements vesting for each owner, fully released schedules are removed
+pub const VESTING_SCHEDULES: Map<&Addr, Vec<VestingSchedule>> = Map::new("vesting_schedules");
+/// Global accumulator of the rewards distributed to CU holders
+pub const REWARD_STATE: Item<RewardState> = Item::new("reward_state");
+/// Reward checkpoints of CU holders
+pub const REWARD_ACCOUNTS: Map<&Addr, RewardAccount> = Map::new("reward_accounts");
+/// `consumption_value` held per owner and commitment tier, snapshotted for voting.
+/// Escrowed CUs count for their depositor.
+pub const HOLDINGS: SnapshotMap<(&Addr, u16), Uint128> = SnapshotMap::new(
+    "holdings",
+    "holdings__checkpoints",
+    "holdings__changelog",
+    Strategy::EveryBlock,
+);
+/// `consumption_value` held per commitment tier, snapshotted for voting
+pub const TIER_TOTALS: SnapshotMap<u16, Uint128> = SnapshotMap::new(
+    "tier_totals",
+    "tier_totals__checkpoints",
+    "tier_totals__changelog",
+    Strategy::EveryBlock,
+);
+/// History of each CU by token id and entry index, kept after the token is burned
+pub const TOKEN_HISTORY: Map<(&str, u64), HistoryEntry> = Map::new("token_history");
+/// Number of history entries of each CU
+pub const TOKEN_HISTORY_LENGTH: Map<&str, u64> = Map::new("token_history_length");
+/// Auditors allowed to open disputes
+pub const AUDITORS: Map<&Addr, Empty> = Map::new("auditors");
+/// Latest dispute of each CU, kept after it is resolved
+pub const DISPUTES: Map<&str, Dispute> = Map::new("disputes");
+/// Compliance mode, any address may hold CUs while it is not set
+pub const COMPLIANCE_CONFIG: Item<ComplianceConfig> = Item::new("compliance_config");
+/// Addresses allowed to hold CUs in the `Local` compliance mode
+pub const ALLOWLIST: Map<&Addr, Empty> = Map::new("allowlist");
+/// Addresses allowed to freeze and unfreeze holders besides the creator
+pub const GUARDIANS: Map<&Addr, Empty> = Map::new("guardians");
+/// Frozen holders, their CUs cannot be transferred, burned, nominated,
+/// settled or change tier and they cannot claim payouts
+pub const FROZEN: Map<&Addr, FreezeInfo> = Map::new("frozen");
+/// Contracts holding CUs on behalf of their depositors, e.g. marketplace, auction and staking
+pub const ESCROW_CONTRACTS: Map<&Addr, Empty> = Map::new("escrow_contracts");
+/// Depositors of the CUs held by escrow contracts, who keep their rewards
+pub const ESCROWED: Map<&str, Addr> = Map::new("escrowed");
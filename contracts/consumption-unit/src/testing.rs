@@ -0,0 +1,60 @@
+use crate::contract::{execute, instantiate};
+use crate::error::ContractError;
+use crate::msg::{ConsumptionUnitCollectionExtension, ExecuteMsg, InstantiateMsg};
+use crate::types::{ConsumptionUnitData, ConsumptionUnitState};
+use cosmwasm_std::testing::{message_info, mock_env, MockApi};
+use cosmwasm_std::{Addr, Decimal, DepsMut, Response, Uint128};
+use cw20::Denom;
+
+/// Instantiates the contract with `creator` as both minter and creator
+pub fn setup(deps: DepsMut, creator: &Addr, settlement_token: Denom) {
+    let msg = InstantiateMsg {
+        name: "consumption unit".to_string(),
+        symbol: "cu".to_string(),
+        collection_info_extension: ConsumptionUnitCollectionExtension {
+            settlement_token,
+            native_token: Denom::Native("native".to_string()),
+            price_oracles: vec![MockApi::default().addr_make("price_oracle")],
+            oracle_quorum: 1,
+        },
+        minter: None,
+        creator: None,
+        snapshots: true,
+    };
+    instantiate(deps, mock_env(), message_info(creator, &[]), msg).unwrap();
+}
+
+/// CU data with a floor price of 0.5 allocated to the given tier
+pub fn cu_data(consumption_value: u128, commitment_tier: u16) -> ConsumptionUnitData {
+    let env = mock_env();
+    ConsumptionUnitData {
+        consumption_value: Uint128::new(consumption_value),
+        nominal_quantity: Uint128::new(consumption_value),
+        nominal_currency: "usd".to_string(),
+        commitment_tier,
+        state: ConsumptionUnitState::Reflected,
+        floor_price: Decimal::percent(50),
+        hashes: vec!["hash".to_string()],
+        created_at: env.block.time,
+        updated_at: env.block.time,
+    }
+}
+
+pub fn mint(
+    deps: DepsMut,
+    minter: &Addr,
+    token_id: &str,
+    owner: &Addr,
+    extension: ConsumptionUnitData,
+) -> Result<Response, ContractError> {
+    execute(
+        deps,
+        mock_env(),
+        message_info(minter, &[]),
+        ExecuteMsg::Mint {
+            token_id: token_id.to_string(),
+            owner: owner.to_string(),
+            extension,
+        },
+    )
+}
@@ -0,0 +1,235 @@
+use crate::error::ContractError;
+use crate::state::TIER_CONFIGS;
+use crate::types::{ConsumptionUnitData, PenaltyDestination, TierConfig};
+use cosmwasm_std::{
+    Decimal, Deps, DepsMut, Env, Event, MessageInfo, Response, StdResult, Storage, Uint128,
+};
+use q_nft::execute::assert_creator;
+use q_utils::fixed_point::{decimal_mul, Rounding};
+
+pub fn load_tier_config(storage: &dyn Storage, tier: u16) -> StdResult<TierConfig> {
+    Ok(TIER_CONFIGS.may_load(storage, tier)?.unwrap_or_default())
+}
+
+/// Fails while the CU is in the lock period of its tier, unless the tier allows
+/// leaving early against a penalty. The lock period is counted from the last tier
+/// change (`updated_at`), early exit penalties are computed by `penalty`.
+pub fn assert_can_leave_tier(
+    storage: &dyn Storage,
+    env: &Env,
+    data: &ConsumptionUnitData,
+) -> Result<(), ContractError> {
+    let now = env.block.time;
+    let current = load_tier_config(storage, data.commitment_tier)?;
+    let unlocked_at = data.updated_at.plus_seconds(current.lock_period);
+    // with an early exit penalty the lock can be left by paying it
+    if now < unlocked_at && current.early_exit_penalty.is_none() {
+        return Err(ContractError::TierLocked {
+            tier: data.commitment_tier,
+            remaining: unlocked_at.seconds() - now.seconds(),
+        });
+    }
+    Ok(())
+}
+
+/// Verifies the tier rules of moving the CU to `new_tier` and returns the switching fee due.
+///
+/// Besides the lock of the current tier, the cooldown of the new tier is counted
+/// from the last tier change.
+pub fn assert_can_change_tier(
+    storage: &dyn Storage,
+    env: &Env,
+    data: &ConsumptionUnitData,
+    new_tier: u16,
+) -> Result<Uint128, ContractError> {
+    let now = env.block.time;
+    assert_can_leave_tier(storage, env, data)?;
+
+    let target = load_tier_config(storage, new_tier)?;
+    let available_at = data.updated_at.plus_seconds(target.cooldown);
+    if now < available_at {
+        return Err(ContractError::TierChangeCooldown {
+            remaining: available_at.seconds() - now.seconds(),
+        });
+    }
+
+    Ok(target.switch_fee.unwrap_or_default())
+}
+
+/// Floor price of CUs entering `tier` at the given native coin price
+pub fn floor_price(
+    storage: &dyn Storage,
+    tier: u16,
+    price: Decimal,
+) -> Result<Decimal, ContractError> {
+    let multiplier = load_tier_config(storage, tier)?
+        .floor_multiplier
+        .unwrap_or(Decimal::one());
+    Ok(decimal_mul(price, multiplier, Rounding::Down)?)
+}
+
+pub fn execute_set_tier_config(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    tier: u16,
+    config: TierConfig,
+) -> Result<Response, ContractError> {
+    assert_creator(deps.storage, &info.sender)?;
+
+    let mut config = config;
+    if let Some(penalty) = &mut config.early_exit_penalty {
+        if penalty.rate > Decimal::one() {
+            return Err(ContractError::InvalidPenalty {});
+        }
+        if let PenaltyDestination::Beneficiary(addr) = &penalty.destination {
+            penalty.destination =
+                PenaltyDestination::Beneficiary(deps.api.addr_validate(addr.as_str())?);
+        }
+    }
+    TIER_CONFIGS.save(deps.storage, tier, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "consumption-unit::set_tier_config")
+        .add_event(
+            Event::new("consumption-unit::set_tier_config")
+                .add_attribute("tier", tier.to_string())
+                .add_attribute("cooldown", config.cooldown.to_string())
+                .add_attribute("lock_period", config.lock_period.to_string())
+                .add_attribute(
+                    "switch_fee",
+                    config.switch_fee.unwrap_or_default().to_string(),
+                )
+                .add_attribute(
+                    "floor_multiplier",
+                    config
+                        .floor_multiplier
+                        .unwrap_or(Decimal::one())
+                        .to_string(),
+                )
+                .add_attribute(
+                    "early_exit_penalty",
+                    config
+                        .early_exit_penalty
+                        .map(|penalty| penalty.rate)
+                        .unwrap_or_default()
+                        .to_string(),
+                ),
+        ))
+}
+
+pub fn query_tier_config(deps: Deps, tier: u16) -> StdResult<TierConfig> {
+    load_tier_config(deps.storage, tier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg};
+    use crate::reserve::query_reserve;
+    use crate::testing::{cu_data, mint, setup};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::{coins, Addr, Coin};
+    use cw20::Denom;
+
+    fn update_pool(
+        deps: DepsMut,
+        env: Env,
+        owner: &Addr,
+        tier: u16,
+        funds: &[Coin],
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            env,
+            message_info(owner, funds),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: "1".to_string(),
+                extension: ConsumptionUnitExtensionUpdate::UpdatePool {
+                    new_commitment_tier_id: tier,
+                },
+            },
+        )
+    }
+
+    #[test]
+    fn test_tier_rules() {
+        let mut deps = mock_dependencies();
+        let creator = deps.api.addr_make("creator");
+        let owner = deps.api.addr_make("owner");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+
+        for (tier, config) in [
+            (
+                1,
+                TierConfig {
+                    lock_period: 100,
+                    ..TierConfig::default()
+                },
+            ),
+            (
+                2,
+                TierConfig {
+                    cooldown: 50,
+                    switch_fee: Some(Uint128::new(10)),
+                    ..TierConfig::default()
+                },
+            ),
+            (
+                3,
+                TierConfig {
+                    cooldown: 1000,
+                    ..TierConfig::default()
+                },
+            ),
+        ] {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                message_info(&creator, &[]),
+                ExecuteMsg::SetTierConfig { tier, config },
+            )
+            .unwrap();
+        }
+        mint(deps.as_mut(), &creator, "1", &owner, cu_data(100, 1)).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(40);
+        let err = update_pool(deps.as_mut(), env.clone(), &owner, 2, &[]).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::TierLocked {
+                tier: 1,
+                remaining: 60
+            }
+        ));
+
+        env.block.time = env.block.time.plus_seconds(60);
+        let err = update_pool(deps.as_mut(), env.clone(), &owner, 3, &[]).unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::TierChangeCooldown { remaining: 900 }
+        ));
+
+        let err = update_pool(deps.as_mut(), env.clone(), &owner, 2, &[]).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidFee { .. }));
+
+        update_pool(
+            deps.as_mut(),
+            env.clone(),
+            &owner,
+            2,
+            &coins(10, "settlement"),
+        )
+        .unwrap();
+        assert_eq!(
+            query_reserve(deps.as_ref()).unwrap().general,
+            Uint128::new(10)
+        );
+    }
+}
@@ -1,15 +1,21 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, Env, Timestamp, Uint128};
+use cosmwasm_std::{
+    Addr, Binary, Decimal, Decimal256, Env, OverflowError, Timestamp, Uint128, Uint64,
+};
 use cw20::Denom;
 use q_nft::state::NftInfo;
 use q_nft::traits::Cw721CollectionConfig;
+use q_utils::fixed_point::{mul_decimal, mul_div, FixedPointError, Rounding};
 
 /// ConsumptionUnit contract config
 #[cw_serde]
 pub struct CUConfig {
     pub settlement_token: Denom,
     pub native_token: Denom,
-    pub price_oracle: Addr,
+    /// Price oracles queried for the native coin price, the median of their prices is used
+    pub price_oracles: Vec<Addr>,
+    /// Minimum number of oracles which must report a fresh price
+    pub oracle_quorum: u32,
 }
 
 impl Cw721CollectionConfig for CUConfig {}
@@ -46,6 +52,12 @@ pub enum ConsumptionUnitState {
     /// Was selected as a winner in Raffle
     /// (Commitment pool and consequently floorPrice cannot be changed)
     Selected,
+    /// Was not selected within the time-to-live
+    /// (cannot be nominated and its Commitment pool cannot be changed)
+    Expired,
+    /// Its consumption records are contested by an auditor
+    /// (cannot be transferred, burned, nominated, settled or change its Commitment pool)
+    Disputed,
 }
 
 pub type ConsumptionUnitNft = NftInfo<ConsumptionUnitData>;
@@ -59,4 +71,421 @@ impl ConsumptionUnitData {
         self.updated_at = env.block.time;
         self
     }
+
+    /// Value of the CU at its floor price, i.e. `consumption_value * floor_price`
+    pub fn floor_value(&self) -> Result<Uint128, FixedPointError> {
+        mul_decimal(self.consumption_value, self.floor_price, Rounding::Down)
+    }
+}
+
+/// Aggregated figures of all CUs allocated to a commitment tier
+#[cw_serde]
+#[derive(Default)]
+pub struct PoolStats {
+    pub cu_count: u64,
+    pub total_consumption_value: Uint128,
+    pub total_floor_value: Uint128,
+}
+
+/// Rules applied when a CU switches its commitment tier
+#[cw_serde]
+#[derive(Default)]
+pub struct TierConfig {
+    /// Minimum number of seconds since the last tier change before a CU can enter this tier
+    pub cooldown: u64,
+    /// Number of seconds a CU must stay in this tier before it can leave it
+    pub lock_period: u64,
+    /// Fee in settlement tokens charged when a CU switches into this tier
+    pub switch_fee: Option<Uint128>,
+    /// Multiplier applied to the native coin price to compute the floor price
+    /// of CUs entering this tier, 1 if not set
+    pub floor_multiplier: Option<Decimal>,
+    /// Penalty for leaving this tier before the end of the lock period.
+    /// If set, CUs can leave early by paying it, otherwise they are locked.
+    pub early_exit_penalty: Option<PenaltyConfig>,
+}
+
+/// Value the early exit penalty rate is applied to
+#[cw_serde]
+pub enum PenaltyBase {
+    ConsumptionValue,
+    FloorValue,
+}
+
+/// Where early exit penalties go
+#[cw_serde]
+pub enum PenaltyDestination {
+    /// Sent to the given address
+    Beneficiary(Addr),
+    /// Added to the reserve of the tier which was left, shared by the remaining CUs
+    Pool,
+}
+
+#[cw_serde]
+pub struct PenaltyConfig {
+    /// Share of the base charged in settlement tokens, at most 1
+    pub rate: Decimal,
+    pub base: PenaltyBase,
+    pub destination: PenaltyDestination,
+}
+
+/// Token in which the mint fee is charged
+#[cw_serde]
+pub enum FeeToken {
+    /// The configured `native_token`
+    Native,
+    /// The configured `settlement_token`
+    Settlement,
+}
+
+#[cw_serde]
+pub enum FeeAmount {
+    /// Fixed amount per minted CU
+    Flat { amount: Uint128 },
+    /// Share of the `consumption_value` of the minted CU, rounded up
+    Proportional { rate: Decimal },
+}
+
+/// Fee charged to the minter for every minted CU
+#[cw_serde]
+pub struct MintFeeConfig {
+    pub token: FeeToken,
+    pub amount: FeeAmount,
+}
+
+impl MintFeeConfig {
+    pub fn denom<'a>(&self, config: &'a CUConfig) -> &'a Denom {
+        match self.token {
+            FeeToken::Native => &config.native_token,
+            FeeToken::Settlement => &config.settlement_token,
+        }
+    }
+
+    pub fn fee_for(&self, consumption_value: Uint128) -> Result<Uint128, FixedPointError> {
+        match &self.amount {
+            FeeAmount::Flat { amount } => Ok(*amount),
+            FeeAmount::Proportional { rate } => mul_decimal(consumption_value, *rate, Rounding::Up),
+        }
+    }
+}
+
+/// Fees collected in a single denom, withdrawable by the collection creator
+#[cw_serde]
+pub struct CollectedFee {
+    pub denom: Denom,
+    pub amount: Uint128,
+}
+
+/// Sanity checks applied to prices returned by the price oracle
+#[cw_serde]
+pub struct OracleConfig {
+    /// Maximum age in seconds of an oracle price, older prices are discarded
+    pub max_price_age: u64,
+    /// Maximum relative deviation from the last accepted price while it is younger
+    /// than `deviation_window` seconds, unlimited if not set
+    pub max_deviation: Option<Decimal>,
+    pub deviation_window: u64,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    /// Floor prices use the time-weighted average price over this many seconds,
+    /// the spot price if zero
+    pub twap_window: u64,
+}
+
+/// Expiry of CUs which were not selected
+#[cw_serde]
+pub struct ExpiryConfig {
+    /// Seconds from `created_at` after which an unselected CU expires
+    pub ttl: u64,
+    /// Settlement tokens paid to the sweeper per swept CU out of the mint fees
+    /// collected in the settlement token
+    pub reward: Option<Uint128>,
+    /// Whether swept CUs are burned, otherwise they are kept in the `Expired` state
+    pub burn: bool,
+}
+
+impl ExpiryConfig {
+    /// Time a CU created at `created_at` expires, `None` if beyond the representable time
+    pub fn expires_at(&self, created_at: Timestamp) -> Option<Timestamp> {
+        let nanos = Uint64::new(self.ttl)
+            .checked_mul(Uint64::new(1_000_000_000))
+            .and_then(|ttl| ttl.checked_add(Uint64::new(created_at.nanos())))
+            .ok()?;
+        Some(Timestamp::from_nanos(nanos.u64()))
+    }
+
+    pub fn is_expired(&self, data: &ConsumptionUnitData, env: &Env) -> bool {
+        match data.state {
+            ConsumptionUnitState::Expired => true,
+            ConsumptionUnitState::Selected | ConsumptionUnitState::Disputed => false,
+            // an expiry beyond the representable time is never reached
+            _ => self
+                .expires_at(data.created_at)
+                .is_some_and(|expires_at| env.block.time >= expires_at),
+        }
+    }
+}
+
+/// Vesting of settlement payouts: nothing is released before the cliff,
+/// then the payout is released linearly until the end of the duration
+#[cw_serde]
+pub struct VestingConfig {
+    /// Seconds from the claim before the first release
+    pub cliff: u64,
+    /// Seconds from the claim until the payout is fully released
+    pub duration: u64,
+}
+
+/// Settlement payout vesting for an owner
+#[cw_serde]
+pub struct VestingSchedule {
+    /// Total payout in settlement tokens
+    pub amount: Uint128,
+    /// Part of the payout already released to the owner
+    pub released: Uint128,
+    pub start: Timestamp,
+    pub cliff: Timestamp,
+    pub end: Timestamp,
+}
+
+impl VestingSchedule {
+    /// Part of the payout unlocked at the current block, rounded down
+    pub fn vested(&self, env: &Env) -> Result<Uint128, FixedPointError> {
+        let now = env.block.time;
+        if now < self.cliff {
+            return Ok(Uint128::zero());
+        }
+        if now >= self.end {
+            return Ok(self.amount);
+        }
+        mul_div(
+            self.amount,
+            Uint128::from(now.seconds() - self.start.seconds()),
+            Uint128::from(self.end.seconds() - self.start.seconds()),
+            Rounding::Down,
+        )
+    }
+}
+
+/// Global accumulator of the rewards distributed to CU holders weighted by `consumption_value`
+#[cw_serde]
+#[derive(Default)]
+pub struct RewardState {
+    /// Rewards distributed per unit of `consumption_value` since the start
+    pub reward_per_unit: Decimal256,
+    /// Sum of the `consumption_value` of all CUs
+    pub total_weight: Uint128,
+    /// Rewards received while there was no CU and remainders too small to be credited,
+    /// added to the next distribution
+    pub undistributed: Uint128,
+}
+
+/// Reward checkpoint of a CU holder
+#[cw_serde]
+#[derive(Default)]
+pub struct RewardAccount {
+    /// Sum of the `consumption_value` of the CUs held
+    pub weight: Uint128,
+    /// Global `reward_per_unit` at the last update of the account
+    pub reward_per_unit: Decimal256,
+    /// Rewards accrued until the last update and not claimed yet
+    pub pending: Uint128,
+}
+
+/// Accepted oracle price recorded for the TWAP
+#[cw_serde]
+pub struct PriceObservation {
+    pub price: Decimal,
+    /// Sum of all prices weighted by the seconds they were in effect, up to this observation
+    pub cumulative: Decimal256,
+}
+
+/// Last oracle price which passed all checks
+#[cw_serde]
+pub struct AcceptedPrice {
+    pub price: Decimal,
+    /// Time the price was published by the oracle
+    pub updated_at: Timestamp,
+    /// Time the price was accepted by this contract
+    pub accepted_at: Timestamp,
+    /// Oracles whose prices contributed to the median
+    pub sources: Vec<Addr>,
+}
+
+/// Credential used to authenticate the reports of a reporter
+#[cw_serde]
+pub enum ReporterKey {
+    /// Reports are submitted by this address itself, no signature required
+    Address(Addr),
+    /// Reports are signed with this secp256k1 public key
+    Secp256k1(Binary),
+    /// Reports are signed with this ed25519 public key
+    Ed25519(Binary),
+}
+
+/// Registered consumption data provider
+#[cw_serde]
+pub struct Reporter {
+    pub key: ReporterKey,
+    /// Nominal currencies the reporter may report, any currency if empty
+    pub allowed_currencies: Vec<String>,
+    /// Maximum total `consumption_value` the reporter may mint, unlimited if not set
+    pub quota: Option<Uint128>,
+    /// Total `consumption_value` minted from the reports of the reporter
+    pub reported: Uint128,
+}
+
+/// Consumption report submitted on behalf of a registered reporter
+#[cw_serde]
+pub struct ConsumptionReport {
+    /// Id of the registered reporter
+    pub reporter: String,
+    /// Unique ID of the NFT to mint
+    pub token_id: String,
+    /// The owner of the newly minted NFT
+    pub owner: String,
+    /// The report cannot be submitted after this time
+    pub expires_at: Timestamp,
+    /// Consumption data of the CU, its `state`, `floor_price`, `created_at`
+    /// and `updated_at` are set by the contract
+    pub data: ConsumptionUnitData,
+}
+
+/// Schedule of the raffle rounds. Rounds are numbered across schedules,
+/// the rounds of a schedule are addressed by their index within it.
+#[cw_serde]
+pub struct RoundConfig {
+    /// Start of the first round
+    pub start: Timestamp,
+    /// Duration of a round in seconds
+    pub duration: u64,
+    /// Number of seconds at the end of each round reserved for the draw,
+    /// nominations are closed during this period
+    pub draw_period: u64,
+}
+
+impl RoundConfig {
+    /// Returns the index of the round running at `time`, `None` before the first round
+    pub fn round_at(&self, time: Timestamp) -> Option<u64> {
+        if time < self.start {
+            return None;
+        }
+        Some((time.seconds() - self.start.seconds()) / self.duration)
+    }
+
+    /// Fails if the round starts beyond the range of timestamps
+    pub fn round_start(&self, index: u64) -> Result<Timestamp, OverflowError> {
+        let offset = Uint64::new(index)
+            .checked_mul(Uint64::new(self.duration))?
+            .checked_mul(Uint64::new(1_000_000_000))?;
+        Ok(Timestamp::from_nanos(
+            Uint64::new(self.start.nanos()).checked_add(offset)?.u64(),
+        ))
+    }
+
+    pub fn nomination_end(&self, index: u64) -> Result<Timestamp, OverflowError> {
+        Ok(self.round_end(index)?.minus_seconds(self.draw_period))
+    }
+
+    pub fn round_end(&self, index: u64) -> Result<Timestamp, OverflowError> {
+        self.round_start(Uint64::new(index).checked_add(Uint64::one())?.u64())
+    }
+}
+
+/// Per-round counters
+#[cw_serde]
+#[derive(Default)]
+pub struct RoundInfo {
+    pub nominated: u64,
+    pub selected: u64,
+}
+
+/// Optional caps of a commitment tier, `None` means unlimited
+#[cw_serde]
+pub struct PoolCapacity {
+    pub max_cu_count: Option<u64>,
+    pub max_consumption_value: Option<Uint128>,
+}
+
+/// Change recorded in the history of a CU
+#[cw_serde]
+pub enum HistoryAction {
+    Mint {
+        owner: Addr,
+    },
+    Transfer {
+        from: Addr,
+        to: Addr,
+    },
+    TierChange {
+        old_tier: u16,
+        new_tier: u16,
+        old_floor_price: Decimal,
+        new_floor_price: Decimal,
+    },
+    StateChange {
+        old_state: ConsumptionUnitState,
+        new_state: ConsumptionUnitState,
+    },
+    Burn {
+        owner: Addr,
+    },
+    DisputeOpened {
+        reason: String,
+    },
+    DisputeResolved {
+        burned: bool,
+        reason: String,
+    },
+}
+
+/// Entry of the append-only history of a CU
+#[cw_serde]
+pub struct HistoryEntry {
+    /// Position of the entry in the history of the token, starting at 0
+    pub index: u64,
+    pub height: u64,
+    pub time: Timestamp,
+    /// Sender of the message which caused the change
+    pub actor: Addr,
+    pub action: HistoryAction,
+}
+
+/// Latest dispute opened on a CU by an auditor
+#[cw_serde]
+pub struct Dispute {
+    pub auditor: Addr,
+    pub reason: String,
+    pub opened_at: Timestamp,
+    /// State of the CU before the dispute, restored when the dispute is cleared
+    pub previous_state: ConsumptionUnitState,
+    /// Set once the creator resolved the dispute
+    pub resolution: Option<DisputeResolution>,
+}
+
+#[cw_serde]
+pub struct DisputeResolution {
+    pub resolver: Addr,
+    /// Whether the CU was burned, otherwise the dispute was cleared
+    pub burned: bool,
+    pub reason: String,
+    pub resolved_at: Timestamp,
+}
+
+/// Where the addresses allowed to hold CUs are looked up
+#[cw_serde]
+pub enum ComplianceConfig {
+    /// The allowlist stored in this contract
+    Local {},
+    /// A registry contract implementing `RegistryQueryMsg`
+    Registry { contract: Addr },
+}
+
+/// Freeze of a CU holder, e.g. on a court order
+#[cw_serde]
+pub struct FreezeInfo {
+    pub reason: String,
+    /// Creator or guardian who froze the address
+    pub frozen_by: Addr,
+    pub frozen_at: Timestamp,
 }
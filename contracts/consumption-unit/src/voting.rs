@@ -0,0 +1,276 @@
+use crate::error::ContractError;
+use crate::msg::{TierHolding, TotalPowerResponse, VotingPowerResponse};
+use crate::state::{HOLDINGS, TIER_TOTALS};
+use crate::types::ConsumptionUnitData;
+use cosmwasm_std::{Addr, Deps, Env, Order, StdResult, Storage, Uint128};
+
+/// Records the `consumption_value` of a CU received by `owner` in its tier,
+/// escrowed CUs are attributed to their depositor (see `escrow::holder`),
+/// called whenever a CU is minted, received or enters a tier
+pub fn add_holding(
+    storage: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    data: &ConsumptionUnitData,
+) -> Result<(), ContractError> {
+    let height = env.block.height;
+    let key = (owner, data.commitment_tier);
+    let holding = HOLDINGS.may_load(storage, key)?.unwrap_or_default();
+    HOLDINGS.save(
+        storage,
+        key,
+        &holding.checked_add(data.consumption_value)?,
+        height,
+    )?;
+    let total = TIER_TOTALS
+        .may_load(storage, data.commitment_tier)?
+        .unwrap_or_default();
+    TIER_TOTALS.save(
+        storage,
+        data.commitment_tier,
+        &total.checked_add(data.consumption_value)?,
+        height,
+    )?;
+    Ok(())
+}
+
+/// Removes the `consumption_value` of a CU from the holdings of `owner` in its tier,
+/// called whenever a CU is burned, sent away or leaves a tier
+pub fn remove_holding(
+    storage: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    data: &ConsumptionUnitData,
+) -> Result<(), ContractError> {
+    let height = env.block.height;
+    let key = (owner, data.commitment_tier);
+    // zero holdings are kept so that past holdings can still be enumerated
+    let holding = HOLDINGS.may_load(storage, key)?.unwrap_or_default();
+    HOLDINGS.save(
+        storage,
+        key,
+        &holding.checked_sub(data.consumption_value)?,
+        height,
+    )?;
+    let total = TIER_TOTALS
+        .may_load(storage, data.commitment_tier)?
+        .unwrap_or_default();
+    TIER_TOTALS.save(
+        storage,
+        data.commitment_tier,
+        &total.checked_sub(data.consumption_value)?,
+        height,
+    )?;
+    Ok(())
+}
+
+/// Moves the holding of a CU from `from` to `to`, called on CU transfers
+pub fn transfer_holding(
+    storage: &mut dyn Storage,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    data: &ConsumptionUnitData,
+) -> Result<(), ContractError> {
+    remove_holding(storage, env, from, data)?;
+    add_holding(storage, env, to, data)
+}
+
+/// Holdings of `address` per tier at the start of block `height`, the current ones if not set
+pub fn query_voting_power(
+    deps: Deps,
+    env: &Env,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<VotingPowerResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let height = height.unwrap_or(env.block.height);
+
+    let tiers = HOLDINGS
+        .prefix(&address)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|tier| {
+            let tier = tier?;
+            let consumption_value = HOLDINGS
+                .may_load_at_height(deps.storage, (&address, tier), height)?
+                .unwrap_or_default();
+            Ok(TierHolding {
+                tier,
+                consumption_value,
+            })
+        })
+        .filter(|holding| !matches!(holding, Ok(holding) if holding.consumption_value.is_zero()))
+        .collect::<StdResult<Vec<_>>>()?;
+    let total = tiers.iter().map(|holding| holding.consumption_value).sum();
+
+    Ok(VotingPowerResponse {
+        address,
+        height,
+        tiers,
+        total,
+    })
+}
+
+/// Holdings of all addresses per tier at the start of block `height`, the current ones if not set
+pub fn query_total_power(
+    deps: Deps,
+    env: &Env,
+    height: Option<u64>,
+) -> StdResult<TotalPowerResponse> {
+    let height = height.unwrap_or(env.block.height);
+
+    let tiers = TIER_TOTALS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .map(|tier| {
+            let tier = tier?;
+            let consumption_value = TIER_TOTALS
+                .may_load_at_height(deps.storage, tier, height)?
+                .unwrap_or_default();
+            Ok(TierHolding {
+                tier,
+                consumption_value,
+            })
+        })
+        .filter(|holding| !matches!(holding, Ok(holding) if holding.consumption_value.is_zero()))
+        .collect::<StdResult<Vec<_>>>()?;
+    let total: Uint128 = tiers.iter().map(|holding| holding.consumption_value).sum();
+
+    Ok(TotalPowerResponse {
+        height,
+        tiers,
+        total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::execute;
+    use crate::msg::{ConsumptionUnitExtensionUpdate, ExecuteMsg};
+    use crate::testing::{cu_data, mint, setup};
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+    use cosmwasm_std::to_json_binary;
+    use cw20::Denom;
+
+    #[test]
+    fn test_voting_power_snapshots() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let alice = deps.api.addr_make("alice");
+        let bob = deps.api.addr_make("bob");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        let start = env.block.height;
+        mint(deps.as_mut(), &creator, "1", &alice, cu_data(100, 1)).unwrap();
+        mint(deps.as_mut(), &creator, "2", &alice, cu_data(300, 1)).unwrap();
+
+        env.block.height += 1;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&alice, &[]),
+            ExecuteMsg::TransferNft {
+                recipient: bob.to_string(),
+                token_id: "2".to_string(),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&alice, &[]),
+            ExecuteMsg::UpdateNftInfo {
+                token_id: "1".to_string(),
+                extension: ConsumptionUnitExtensionUpdate::UpdatePool {
+                    new_commitment_tier_id: 2,
+                },
+            },
+        )
+        .unwrap();
+
+        // holdings are taken at the start of the block
+        let power =
+            query_voting_power(deps.as_ref(), &env, alice.to_string(), Some(start + 1)).unwrap();
+        assert_eq!(power.total, Uint128::new(400));
+        assert_eq!(power.tiers.len(), 1);
+
+        env.block.height += 1;
+        let power = query_voting_power(deps.as_ref(), &env, alice.to_string(), None).unwrap();
+        assert_eq!(
+            power.tiers,
+            vec![TierHolding {
+                tier: 2,
+                consumption_value: Uint128::new(100),
+            }]
+        );
+        let total = query_total_power(deps.as_ref(), &env, None).unwrap();
+        assert_eq!(total.total, Uint128::new(400));
+        assert_eq!(total.tiers.len(), 2);
+        let total = query_total_power(deps.as_ref(), &env, Some(start)).unwrap();
+        assert!(total.tiers.is_empty());
+    }
+
+    #[test]
+    fn test_escrowed_voting_power() {
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+        let creator = deps.api.addr_make("creator");
+        let alice = deps.api.addr_make("alice");
+        let auction = deps.api.addr_make("auction");
+        setup(
+            deps.as_mut(),
+            &creator,
+            Denom::Native("settlement".to_string()),
+        );
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&creator, &[]),
+            ExecuteMsg::AddEscrowContract {
+                contract: auction.to_string(),
+            },
+        )
+        .unwrap();
+        mint(deps.as_mut(), &creator, "1", &alice, cu_data(100, 1)).unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&alice, &[]),
+            ExecuteMsg::SendNft {
+                contract: auction.to_string(),
+                token_id: "1".to_string(),
+                msg: to_json_binary("auction").unwrap(),
+            },
+        )
+        .unwrap();
+
+        // the depositor keeps the voting power, the escrow contract gets none
+        env.block.height += 1;
+        let power = query_voting_power(deps.as_ref(), &env, alice.to_string(), None).unwrap();
+        assert_eq!(power.total, Uint128::new(100));
+        let power = query_voting_power(deps.as_ref(), &env, auction.to_string(), None).unwrap();
+        assert!(power.total.is_zero());
+        let total = query_total_power(deps.as_ref(), &env, None).unwrap();
+        assert_eq!(total.total, Uint128::new(100));
+
+        // burning the escrowed CU removes the power of the depositor
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&auction, &[]),
+            ExecuteMsg::Burn {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+        env.block.height += 1;
+        let power = query_voting_power(deps.as_ref(), &env, alice.to_string(), None).unwrap();
+        assert!(power.total.is_zero());
+        let total = query_total_power(deps.as_ref(), &env, None).unwrap();
+        assert!(total.total.is_zero());
+    }
+}
@@ -0,0 +1,856 @@
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, NftHookMsg, TokenHookMsg};
+use crate::state::{Auction, AuctionKind, Bid, Config, AUCTIONS, AUCTION_COUNT, CONFIG};
+use consumption_unit::msg::{ExecuteMsg as CuExecuteMsg, IsAllowedResponse, IsFrozenResponse};
+use consumption_unit::query::{query_nft_info_raw, QueryMsg as CuQueryMsg};
+use consumption_unit::types::{CUConfig, ConsumptionUnitState};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Deps, DepsMut, Env, Event, MessageInfo, OverflowError,
+    Response, StdResult, Timestamp, Uint128, Uint64, WasmMsg,
+};
+use cw20::Cw20ReceiveMsg;
+use q_nft::msg::{ContractInfoResponse, Cw721ReceiveMsg};
+use q_utils::asset::{denom_to_string, Asset};
+use q_utils::fixed_point::{mul_div, Rounding};
+
+const CONTRACT_NAME: &str = "gemlabs.io:cu-auction";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        cu_contract: deps.api.addr_validate(&msg.cu_contract)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    AUCTION_COUNT.save(deps.storage, &0)?;
+
+    Ok(Response::default()
+        .add_attribute("action", "cu-auction::instantiate")
+        .add_event(
+            Event::new("cu-auction::instantiate")
+                .add_attribute("cu_contract", config.cu_contract.to_string()),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ReceiveNft(msg) => execute_create_auction(deps, &env, &info, msg),
+        ExecuteMsg::Receive(wrapper) => execute_receive(deps, &env, &info, wrapper),
+        ExecuteMsg::Bid { auction_id } => {
+            let auction = load_auction(deps.as_ref(), auction_id)?;
+            let paid = Asset::from_funds(&auction.denom, &info)?;
+            bid(deps, &env, auction, info.sender, paid.amount)
+        }
+        ExecuteMsg::Settle { auction_id } => execute_settle(deps, &env, &info, auction_id),
+        ExecuteMsg::Cancel { auction_id } => execute_cancel(deps, &env, &info, auction_id),
+    }
+}
+
+pub fn load_auction(deps: Deps, auction_id: u64) -> Result<Auction, ContractError> {
+    AUCTIONS
+        .may_load(deps.storage, auction_id)?
+        .ok_or(ContractError::AuctionNotFound { auction_id })
+}
+
+/// Minimum accepted bid at the current block, `None` once the auction has ended.
+/// The price of Dutch auctions is rounded up in favour of the seller.
+pub fn min_bid(auction: &Auction, env: &Env) -> Result<Option<Uint128>, ContractError> {
+    if env.block.time >= auction.end_time {
+        return Ok(None);
+    }
+    let price = match &auction.kind {
+        AuctionKind::English {
+            start_price,
+            min_increment,
+            ..
+        } => match &auction.highest_bid {
+            Some(bid) => bid.amount.checked_add(*min_increment)?,
+            None => *start_price,
+        },
+        AuctionKind::Dutch {
+            start_price,
+            end_price,
+        } => {
+            let elapsed = env.block.time.seconds() - auction.start_time.seconds();
+            let duration = auction.end_time.seconds() - auction.start_time.seconds();
+            let decay = mul_div(
+                *start_price - *end_price,
+                Uint128::from(elapsed),
+                Uint128::from(duration),
+                Rounding::Down,
+            )?;
+            *start_price - decay
+        }
+    };
+    Ok(Some(price))
+}
+
+fn validate_auction(kind: &AuctionKind, duration: u64) -> Result<(), ContractError> {
+    let valid = duration > 0
+        && match kind {
+            AuctionKind::English {
+                start_price,
+                min_increment,
+                ..
+            } => !start_price.is_zero() && !min_increment.is_zero(),
+            AuctionKind::Dutch {
+                start_price,
+                end_price,
+            } => !end_price.is_zero() && start_price > end_price,
+        };
+    if !valid {
+        return Err(ContractError::InvalidAuction {});
+    }
+    Ok(())
+}
+
+/// `time` plus `seconds`, failing instead of panicking on overflow
+fn plus_seconds(time: Timestamp, seconds: u64) -> Result<Timestamp, OverflowError> {
+    let nanos = Uint64::new(seconds)
+        .checked_mul(Uint64::new(1_000_000_000))?
+        .checked_add(Uint64::new(time.nanos()))?;
+    Ok(Timestamp::from_nanos(nanos.u64()))
+}
+
+/// Whether the collection allows `address` to hold CUs
+fn is_allowed(deps: Deps, config: &Config, address: &Addr) -> StdResult<bool> {
+    let response: IsAllowedResponse = deps.querier.query_wasm_smart(
+        &config.cu_contract,
+        &CuQueryMsg::IsAllowed {
+            address: address.to_string(),
+        },
+    )?;
+    Ok(response.allowed)
+}
+
+/// Whether the collection froze `address`, frozen holders may not sell or receive proceeds
+fn is_frozen(deps: Deps, config: &Config, address: &Addr) -> StdResult<bool> {
+    let response: IsFrozenResponse = deps.querier.query_wasm_smart(
+        &config.cu_contract,
+        &CuQueryMsg::IsFrozen {
+            address: address.to_string(),
+        },
+    )?;
+    Ok(response.frozen)
+}
+
+/// State of the CU in the collection, `None` if it was burned while auctioned
+fn cu_state(
+    deps: Deps,
+    config: &Config,
+    token_id: &str,
+) -> StdResult<Option<ConsumptionUnitState>> {
+    Ok(
+        query_nft_info_raw(&deps.querier, &config.cu_contract, token_id)?
+            .map(|nft_info| nft_info.extension.state),
+    )
+}
+
+fn transfer_cu_msg(config: &Config, recipient: &Addr, token_id: String) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: config.cu_contract.to_string(),
+        msg: to_json_binary(&CuExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id,
+        })?,
+        funds: vec![],
+    })
+}
+
+/// Creates an auction for a CU escrowed through `SendNft` of the collection
+fn execute_create_auction(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.cu_contract {
+        return Err(ContractError::UnsupportedNft {});
+    }
+    let NftHookMsg::CreateAuction { kind, duration } = from_json(&msg.msg)?;
+    validate_auction(&kind, duration)?;
+
+    let collection: ContractInfoResponse<CUConfig> = deps
+        .querier
+        .query_wasm_smart(&config.cu_contract, &CuQueryMsg::ContractInfo {})?;
+
+    let id = AUCTION_COUNT.load(deps.storage)? + 1;
+    AUCTION_COUNT.save(deps.storage, &id)?;
+    let auction = Auction {
+        id,
+        token_id: msg.token_id,
+        seller: deps.api.addr_validate(&msg.sender)?,
+        kind,
+        denom: collection.collection_config.settlement_token,
+        start_time: env.block.time,
+        end_time: plus_seconds(env.block.time, duration)?,
+        highest_bid: None,
+    };
+    AUCTIONS.save(deps.storage, id, &auction)?;
+
+    let kind = match auction.kind {
+        AuctionKind::English { .. } => "english",
+        AuctionKind::Dutch { .. } => "dutch",
+    };
+    Ok(Response::new()
+        .add_attribute("action", "cu-auction::create_auction")
+        .add_event(
+            Event::new("cu-auction::create_auction")
+                .add_attribute("auction_id", id.to_string())
+                .add_attribute("token_id", auction.token_id)
+                .add_attribute("seller", auction.seller.to_string())
+                .add_attribute("kind", kind)
+                .add_attribute("denom", denom_to_string(&auction.denom))
+                .add_attribute("end_time", auction.end_time.to_string()),
+        ))
+}
+
+/// Handles CW20 settlement tokens sent through the `Send` hook
+fn execute_receive(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let TokenHookMsg::Bid { auction_id } = from_json(&wrapper.msg)?;
+    let auction = load_auction(deps.as_ref(), auction_id)?;
+    let paid = Asset::from_cw20_receive(&auction.denom, info, &wrapper)?;
+    let bidder = deps.api.addr_validate(&wrapper.sender)?;
+    bid(deps, env, auction, bidder, paid.amount)
+}
+
+fn bid(
+    deps: DepsMut,
+    env: &Env,
+    mut auction: Auction,
+    bidder: Addr,
+    paid: Uint128,
+) -> Result<Response, ContractError> {
+    let min_bid = min_bid(&auction, env)?.ok_or(ContractError::AuctionEnded {
+        auction_id: auction.id,
+    })?;
+    if paid < min_bid {
+        return Err(ContractError::BidTooLow { min_bid });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    if !is_allowed(deps.as_ref(), &config, &bidder)? {
+        return Err(ContractError::NotAllowed {
+            address: bidder.to_string(),
+        });
+    }
+    if is_frozen(deps.as_ref(), &config, &auction.seller)? {
+        return Err(ContractError::Frozen {
+            address: auction.seller.to_string(),
+        });
+    }
+
+    let mut response = Response::new().add_attribute("action", "cu-auction::bid");
+    let mut event = Event::new("cu-auction::bid")
+        .add_attribute("auction_id", auction.id.to_string())
+        .add_attribute("bidder", bidder.to_string())
+        .add_attribute("amount", paid.to_string());
+
+    match auction.kind {
+        AuctionKind::English { extension, .. } => {
+            if let Some(outbid) = auction.highest_bid.take() {
+                let refund = Asset::new(auction.denom.clone(), outbid.amount);
+                response = response.add_message(refund.transfer_msg(&outbid.bidder)?);
+            }
+            let extended_end = plus_seconds(env.block.time, extension)?;
+            if extended_end > auction.end_time {
+                auction.end_time = extended_end;
+                event = event.add_attribute("end_time", auction.end_time.to_string());
+            }
+            auction.highest_bid = Some(Bid {
+                bidder,
+                amount: paid,
+            });
+            AUCTIONS.save(deps.storage, auction.id, &auction)?;
+        }
+        AuctionKind::Dutch { .. } => {
+            AUCTIONS.remove(deps.storage, auction.id);
+
+            response = response
+                .add_message(transfer_cu_msg(&config, &bidder, auction.token_id)?)
+                .add_message(
+                    Asset::new(auction.denom.clone(), min_bid).transfer_msg(&auction.seller)?,
+                );
+            let excess = paid - min_bid;
+            if !excess.is_zero() {
+                response =
+                    response.add_message(Asset::new(auction.denom, excess).transfer_msg(&bidder)?);
+            }
+            event = event.add_attribute("price", min_bid.to_string());
+        }
+    }
+
+    Ok(response.add_event(event))
+}
+
+/// Settles an ended auction. If the CU cannot go to the highest bidder, the bid is refunded:
+/// a burned CU is gone, a disputed CU stays escrowed until the seller cancels the auction
+/// and otherwise (e.g. the bidder is no longer allowed or the seller was frozen) the CU goes
+/// back to the seller.
+fn execute_settle(
+    deps: DepsMut,
+    env: &Env,
+    _info: &MessageInfo,
+    auction_id: u64,
+) -> Result<Response, ContractError> {
+    let mut auction = load_auction(deps.as_ref(), auction_id)?;
+    if env.block.time < auction.end_time {
+        return Err(ContractError::AuctionNotEnded { auction_id });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let state = cu_state(deps.as_ref(), &config, &auction.token_id)?;
+    if state == Some(ConsumptionUnitState::Disputed) {
+        let Some(bid) = auction.highest_bid.take() else {
+            return Err(ContractError::Disputed {
+                token_id: auction.token_id,
+            });
+        };
+        AUCTIONS.save(deps.storage, auction_id, &auction)?;
+        return Ok(Response::new()
+            .add_attribute("action", "cu-auction::settle")
+            .add_message(Asset::new(auction.denom, bid.amount).transfer_msg(&bid.bidder)?)
+            .add_event(
+                Event::new("cu-auction::settle")
+                    .add_attribute("auction_id", auction_id.to_string())
+                    .add_attribute("refunded", bid.bidder.to_string())
+                    .add_attribute("disputed", "true"),
+            ));
+    }
+    AUCTIONS.remove(deps.storage, auction_id);
+
+    let mut response = Response::new().add_attribute("action", "cu-auction::settle");
+    let mut event =
+        Event::new("cu-auction::settle").add_attribute("auction_id", auction_id.to_string());
+    let winning_bid = match auction.highest_bid {
+        Some(bid)
+            if state.is_none()
+                || !is_allowed(deps.as_ref(), &config, &bid.bidder)?
+                || is_frozen(deps.as_ref(), &config, &auction.seller)? =>
+        {
+            response = response.add_message(
+                Asset::new(auction.denom.clone(), bid.amount).transfer_msg(&bid.bidder)?,
+            );
+            event = event.add_attribute("refunded", bid.bidder.to_string());
+            None
+        }
+        bid => bid,
+    };
+    match winning_bid {
+        Some(bid) => {
+            response = response
+                .add_message(transfer_cu_msg(&config, &bid.bidder, auction.token_id)?)
+                .add_message(Asset::new(auction.denom, bid.amount).transfer_msg(&auction.seller)?);
+            event = event
+                .add_attribute("winner", bid.bidder.to_string())
+                .add_attribute("price", bid.amount.to_string());
+        }
+        None if state.is_none() => {
+            event = event.add_attribute("burned", "true");
+        }
+        None => {
+            response =
+                response.add_message(transfer_cu_msg(&config, &auction.seller, auction.token_id)?);
+        }
+    }
+
+    Ok(response.add_event(event))
+}
+
+fn execute_cancel(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    auction_id: u64,
+) -> Result<Response, ContractError> {
+    let auction = load_auction(deps.as_ref(), auction_id)?;
+    if auction.seller != info.sender {
+        return Err(ContractError::NotSeller {});
+    }
+    if auction.highest_bid.is_some() {
+        return Err(ContractError::HasBids {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    AUCTIONS.remove(deps.storage, auction_id);
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-auction::cancel")
+        .add_message(transfer_cu_msg(
+            &config,
+            &auction.seller,
+            auction.token_id.clone(),
+        )?)
+        .add_event(
+            Event::new("cu-auction::cancel")
+                .add_attribute("auction_id", auction_id.to_string())
+                .add_attribute("token_id", auction.token_id),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    match msg {
+        MigrateMsg::Migrate {} => Ok(Response::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::query_auction;
+    use consumption_unit::types::ConsumptionUnitData;
+    use cosmwasm_std::testing::{
+        message_info, mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{
+        coins, BankMsg, ContractResult, CosmosMsg, Decimal, Empty, OwnedDeps, SystemResult,
+        WasmQuery,
+    };
+    use cw20::Denom;
+    use q_nft::state::{CollectionInfo, NftInfo};
+
+    type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>;
+
+    /// Mocks a collection settled in `settlement` where the CU is in `state`, `None` if
+    /// burned, all addresses but the `disallowed` ones may hold CUs and the address
+    /// `frozen` is frozen
+    fn mock_collection(
+        deps: &mut MockDeps,
+        state: Option<ConsumptionUnitState>,
+        disallowed: &[&Addr],
+    ) {
+        let env = mock_env();
+        let frozen = deps.api.addr_make("frozen");
+        let disallowed = disallowed
+            .iter()
+            .map(|address| address.to_string())
+            .collect::<Vec<_>>();
+        let nft_info = state.map(|state| NftInfo {
+            owner: env.contract.address.clone(),
+            extension: ConsumptionUnitData {
+                consumption_value: Uint128::new(100),
+                nominal_quantity: Uint128::new(100),
+                nominal_currency: "usd".to_string(),
+                commitment_tier: 1,
+                state,
+                floor_price: Decimal::percent(50),
+                hashes: vec![],
+                created_at: env.block.time,
+                updated_at: env.block.time,
+            },
+        });
+        deps.querier.update_wasm(move |query| {
+            let msg = match query {
+                // the CU is read from the storage of the collection, empty if burned
+                WasmQuery::Raw { .. } => {
+                    let value = nft_info
+                        .as_ref()
+                        .map(|nft_info| to_json_binary(nft_info).unwrap())
+                        .unwrap_or_default();
+                    return SystemResult::Ok(ContractResult::Ok(value));
+                }
+                WasmQuery::Smart { msg, .. } => msg,
+                _ => panic!("unexpected query {query:?}"),
+            };
+            let response = match from_json(msg).unwrap() {
+                CuQueryMsg::IsAllowed { address } => to_json_binary(&IsAllowedResponse {
+                    allowed: !disallowed.contains(&address),
+                }),
+                CuQueryMsg::ContractInfo {} => to_json_binary(&ContractInfoResponse {
+                    collection_info: CollectionInfo {
+                        name: "consumption unit".to_string(),
+                        symbol: "cu".to_string(),
+                        updated_at: env.block.time,
+                    },
+                    collection_config: CUConfig {
+                        settlement_token: Denom::Native("settlement".to_string()),
+                        native_token: Denom::Native("native".to_string()),
+                        price_oracles: vec![],
+                        oracle_quorum: 1,
+                    },
+                }),
+                CuQueryMsg::IsFrozen { address } => to_json_binary(&IsFrozenResponse {
+                    frozen: address == frozen.as_str(),
+                    address: Addr::unchecked(address),
+                    info: None,
+                }),
+                msg => panic!("unexpected query {msg:?}"),
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        });
+    }
+
+    /// Instantiates the auction contract for a mocked collection
+    fn setup() -> (MockDeps, Addr) {
+        let mut deps = mock_dependencies();
+        let cu_contract = deps.api.addr_make("cu_contract");
+        mock_collection(&mut deps, Some(ConsumptionUnitState::Reflected), &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&cu_contract, &[]),
+            InstantiateMsg {
+                cu_contract: cu_contract.to_string(),
+            },
+        )
+        .unwrap();
+        (deps, cu_contract)
+    }
+
+    fn create_auction(
+        deps: &mut MockDeps,
+        cu_contract: &Addr,
+        seller: &Addr,
+        kind: AuctionKind,
+    ) -> u64 {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(cu_contract, &[]),
+            ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: seller.to_string(),
+                token_id: "1".to_string(),
+                msg: to_json_binary(&NftHookMsg::CreateAuction {
+                    kind,
+                    duration: 100,
+                })
+                .unwrap(),
+            }),
+        )
+        .unwrap();
+        AUCTION_COUNT.load(&deps.storage).unwrap()
+    }
+
+    fn place_bid(
+        deps: &mut MockDeps,
+        env: &Env,
+        bidder: &Addr,
+        auction_id: u64,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(bidder, &coins(amount, "settlement")),
+            ExecuteMsg::Bid { auction_id },
+        )
+    }
+
+    fn payment(recipient: &Addr, amount: u128) -> CosmosMsg {
+        BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(amount, "settlement"),
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_english_auction() {
+        let api = MockApi::default();
+        let seller = api.addr_make("seller");
+        let alice = api.addr_make("alice");
+        let bob = api.addr_make("bob");
+        let (mut deps, cu_contract) = setup();
+        let auction_id = create_auction(
+            &mut deps,
+            &cu_contract,
+            &seller,
+            AuctionKind::English {
+                start_price: Uint128::new(100),
+                min_increment: Uint128::new(10),
+                extension: 30,
+            },
+        );
+
+        let mut env = mock_env();
+        let err = place_bid(&mut deps, &env, &alice, auction_id, 99).unwrap_err();
+        assert!(matches!(err, ContractError::BidTooLow { min_bid } if min_bid.u128() == 100));
+        place_bid(&mut deps, &env, &alice, auction_id, 100).unwrap();
+
+        // bidding in the last 30 seconds extends the auction
+        env.block.time = env.block.time.plus_seconds(90);
+        let err = place_bid(&mut deps, &env, &bob, auction_id, 105).unwrap_err();
+        assert!(matches!(err, ContractError::BidTooLow { min_bid } if min_bid.u128() == 110));
+        let response = place_bid(&mut deps, &env, &bob, auction_id, 110).unwrap();
+        assert_eq!(response.messages[0].msg, payment(&alice, 100));
+
+        let auction = query_auction(deps.as_ref(), &env, auction_id)
+            .unwrap()
+            .auction;
+        assert_eq!(auction.end_time, env.block.time.plus_seconds(30));
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&seller, &[]),
+            ExecuteMsg::Cancel { auction_id },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::HasBids {}));
+
+        env.block.time = env.block.time.plus_seconds(20);
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&alice, &[]),
+            ExecuteMsg::Settle { auction_id },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::AuctionNotEnded { .. }));
+
+        env.block.time = env.block.time.plus_seconds(10);
+        let err = place_bid(&mut deps, &env, &alice, auction_id, 200).unwrap_err();
+        assert!(matches!(err, ContractError::AuctionEnded { .. }));
+        let response = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&alice, &[]),
+            ExecuteMsg::Settle { auction_id },
+        )
+        .unwrap();
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(
+            response.messages[0].msg,
+            transfer_cu_msg(&config, &bob, "1".to_string())
+                .unwrap()
+                .into()
+        );
+        assert_eq!(response.messages[1].msg, payment(&seller, 110));
+        assert!(AUCTIONS.is_empty(&deps.storage));
+    }
+
+    #[test]
+    fn test_dutch_auction() {
+        let api = MockApi::default();
+        let seller = api.addr_make("seller");
+        let buyer = api.addr_make("buyer");
+        let (mut deps, cu_contract) = setup();
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&cu_contract, &[]),
+            ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: seller.to_string(),
+                token_id: "1".to_string(),
+                msg: to_json_binary(&NftHookMsg::CreateAuction {
+                    kind: AuctionKind::Dutch {
+                        start_price: Uint128::new(100),
+                        end_price: Uint128::new(100),
+                    },
+                    duration: 100,
+                })
+                .unwrap(),
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidAuction {}));
+
+        let auction_id = create_auction(
+            &mut deps,
+            &cu_contract,
+            &seller,
+            AuctionKind::Dutch {
+                start_price: Uint128::new(1_000),
+                end_price: Uint128::new(1),
+            },
+        );
+
+        // 999 * 25 / 100 = 249.75 is taken off the start price, rounded in favour of the seller
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(25);
+        let min_bid = query_auction(deps.as_ref(), &env, auction_id)
+            .unwrap()
+            .min_bid;
+        assert_eq!(min_bid, Some(Uint128::new(751)));
+
+        let err = place_bid(&mut deps, &env, &buyer, auction_id, 750).unwrap_err();
+        assert!(matches!(err, ContractError::BidTooLow { .. }));
+        let response = place_bid(&mut deps, &env, &buyer, auction_id, 800).unwrap();
+        let config = CONFIG.load(&deps.storage).unwrap();
+        let messages: Vec<_> = response.messages.into_iter().map(|msg| msg.msg).collect();
+        assert_eq!(
+            messages,
+            vec![
+                transfer_cu_msg(&config, &buyer, "1".to_string())
+                    .unwrap()
+                    .into(),
+                payment(&seller, 751),
+                payment(&buyer, 49),
+            ]
+        );
+
+        let err = place_bid(&mut deps, &env, &buyer, auction_id, 800).unwrap_err();
+        assert!(matches!(err, ContractError::AuctionNotFound { .. }));
+    }
+
+    fn settle(deps: &mut MockDeps, env: &Env, auction_id: u64) -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&MockApi::default().addr_make("anyone"), &[]),
+            ExecuteMsg::Settle { auction_id },
+        )
+    }
+
+    #[test]
+    fn test_settle_fallbacks() {
+        let api = MockApi::default();
+        let seller = api.addr_make("seller");
+        let alice = api.addr_make("alice");
+        let bob = api.addr_make("bob");
+        let (mut deps, cu_contract) = setup();
+        let config = CONFIG.load(&deps.storage).unwrap();
+        let kind = AuctionKind::English {
+            start_price: Uint128::new(100),
+            min_increment: Uint128::new(10),
+            extension: 30,
+        };
+        let mut env = mock_env();
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&cu_contract, &[]),
+            ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: seller.to_string(),
+                token_id: "1".to_string(),
+                msg: to_json_binary(&NftHookMsg::CreateAuction {
+                    kind: kind.clone(),
+                    duration: u64::MAX,
+                })
+                .unwrap(),
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Overflow(_)));
+
+        // bidders must be allowed to hold CUs
+        mock_collection(&mut deps, Some(ConsumptionUnitState::Reflected), &[&bob]);
+        let auction_id = create_auction(&mut deps, &cu_contract, &seller, kind.clone());
+        let err = place_bid(&mut deps, &env, &bob, auction_id, 100).unwrap_err();
+        assert!(matches!(err, ContractError::NotAllowed { address } if address == bob.as_str()));
+
+        // a winner no longer allowed is refunded and the CU goes back to the seller
+        place_bid(&mut deps, &env, &alice, auction_id, 100).unwrap();
+        mock_collection(&mut deps, Some(ConsumptionUnitState::Reflected), &[&alice]);
+        env.block.time = env.block.time.plus_seconds(100);
+        let response = settle(&mut deps, &env, auction_id).unwrap();
+        let messages: Vec<_> = response.messages.into_iter().map(|msg| msg.msg).collect();
+        assert_eq!(
+            messages,
+            vec![
+                payment(&alice, 100),
+                transfer_cu_msg(&config, &seller, "1".to_string())
+                    .unwrap()
+                    .into(),
+            ]
+        );
+
+        // a disputed CU stays escrowed until the seller cancels
+        let env = mock_env();
+        mock_collection(&mut deps, Some(ConsumptionUnitState::Reflected), &[]);
+        let auction_id = create_auction(&mut deps, &cu_contract, &seller, kind.clone());
+        place_bid(&mut deps, &env, &alice, auction_id, 100).unwrap();
+        mock_collection(&mut deps, Some(ConsumptionUnitState::Disputed), &[]);
+        let mut ended = env.clone();
+        ended.block.time = ended.block.time.plus_seconds(100);
+        let response = settle(&mut deps, &ended, auction_id).unwrap();
+        let messages: Vec<_> = response.messages.into_iter().map(|msg| msg.msg).collect();
+        assert_eq!(messages, vec![payment(&alice, 100)]);
+        let err = settle(&mut deps, &ended, auction_id).unwrap_err();
+        assert!(matches!(err, ContractError::Disputed { .. }));
+        mock_collection(&mut deps, Some(ConsumptionUnitState::Reflected), &[]);
+        let response = execute(
+            deps.as_mut(),
+            ended.clone(),
+            message_info(&seller, &[]),
+            ExecuteMsg::Cancel { auction_id },
+        )
+        .unwrap();
+        assert_eq!(
+            response.messages[0].msg,
+            transfer_cu_msg(&config, &seller, "1".to_string())
+                .unwrap()
+                .into()
+        );
+
+        // a failing collection is not mistaken for a burned CU
+        let auction_id = create_auction(&mut deps, &cu_contract, &seller, kind);
+        place_bid(&mut deps, &env, &alice, auction_id, 100).unwrap();
+        deps.querier
+            .update_wasm(|_| SystemResult::Ok(ContractResult::Err("query failed".to_string())));
+        settle(&mut deps, &ended, auction_id).unwrap_err();
+        assert!(AUCTIONS.has(&deps.storage, auction_id));
+
+        // the bid on a burned CU is refunded
+        mock_collection(&mut deps, None, &[]);
+        let response = settle(&mut deps, &ended, auction_id).unwrap();
+        let messages: Vec<_> = response.messages.into_iter().map(|msg| msg.msg).collect();
+        assert_eq!(messages, vec![payment(&alice, 100)]);
+        assert!(AUCTIONS.is_empty(&deps.storage));
+    }
+
+    #[test]
+    fn test_frozen_seller() {
+        let api = MockApi::default();
+        let seller = api.addr_make("frozen");
+        let alice = api.addr_make("alice");
+        let (mut deps, cu_contract) = setup();
+        let config = CONFIG.load(&deps.storage).unwrap();
+        let kind = AuctionKind::English {
+            start_price: Uint128::new(100),
+            min_increment: Uint128::new(10),
+            extension: 30,
+        };
+
+        let auction_id = create_auction(&mut deps, &cu_contract, &seller, kind);
+        let err = place_bid(&mut deps, &mock_env(), &alice, auction_id, 100).unwrap_err();
+        assert!(matches!(err, ContractError::Frozen { address } if address == seller.as_str()));
+
+        // a bid placed before the seller was frozen is refunded and the CU returned
+        let mut auction = AUCTIONS.load(&deps.storage, auction_id).unwrap();
+        auction.highest_bid = Some(Bid {
+            bidder: alice.clone(),
+            amount: Uint128::new(100),
+        });
+        AUCTIONS
+            .save(&mut deps.storage, auction_id, &auction)
+            .unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let response = settle(&mut deps, &env, auction_id).unwrap();
+        let messages: Vec<_> = response.messages.into_iter().map(|msg| msg.msg).collect();
+        assert_eq!(
+            messages,
+            vec![
+                payment(&alice, 100),
+                transfer_cu_msg(&config, &seller, "1".to_string())
+                    .unwrap()
+                    .into(),
+            ]
+        );
+    }
+}
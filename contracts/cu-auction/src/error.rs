@@ -0,0 +1,38 @@
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use q_utils::asset::AssetError;
+use q_utils::fixed_point::FixedPointError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+    #[error("{0}")]
+    Asset(#[from] AssetError),
+    #[error("{0}")]
+    FixedPoint(#[from] FixedPointError),
+    #[error("Only CUs of the configured collection can be auctioned")]
+    UnsupportedNft {},
+    #[error("Invalid auction parameters")]
+    InvalidAuction {},
+    #[error("Auction {auction_id} not found")]
+    AuctionNotFound { auction_id: u64 },
+    #[error("Auction {auction_id} has ended")]
+    AuctionEnded { auction_id: u64 },
+    #[error("Auction {auction_id} has not ended yet")]
+    AuctionNotEnded { auction_id: u64 },
+    #[error("Bid too low: the minimum is {min_bid}")]
+    BidTooLow { min_bid: Uint128 },
+    #[error("Only the seller can cancel the auction")]
+    NotSeller {},
+    #[error("Auctions with bids cannot be cancelled")]
+    HasBids {},
+    #[error("Address {address} is not allowed to hold consumption units")]
+    NotAllowed { address: String },
+    #[error("Address {address} is frozen")]
+    Frozen { address: String },
+    #[error("Consumption unit {token_id} is disputed")]
+    Disputed { token_id: String },
+}
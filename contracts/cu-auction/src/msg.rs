@@ -0,0 +1,72 @@
+use crate::state::{Auction, AuctionKind};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+use q_nft::msg::Cw721ReceiveMsg;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Address of the consumption unit collection
+    pub cu_contract: String,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Creates an auction for a CU sent through `SendNft` of the collection,
+    /// the embedded message must be a `NftHookMsg`
+    ReceiveNft(Cw721ReceiveMsg),
+
+    /// Bids in a CW20 settlement token, the embedded message must be a `TokenHookMsg`
+    Receive(Cw20ReceiveMsg),
+
+    /// Bids in a native settlement token sent along as funds, the bidder must be allowed
+    /// to hold CUs and the seller must not be frozen. Outbid bidders of English auctions
+    /// are refunded, Dutch auctions are settled by the first bid covering the current price
+    /// and the excess is refunded.
+    Bid { auction_id: u64 },
+
+    /// Settles an ended auction: the CU goes to the highest bidder and the bid to the seller,
+    /// or the CU goes back to the seller if there was no bid. The bid is refunded instead if
+    /// the bidder is no longer allowed to hold CUs, the seller was frozen or the CU was burned
+    /// or disputed meanwhile, a disputed CU stays escrowed until the seller cancels.
+    /// Can be called by anyone.
+    Settle { auction_id: u64 },
+
+    /// Cancels an auction without bids and returns the CU, can only be called by the seller
+    Cancel { auction_id: u64 },
+}
+
+/// Message embedded in `SendNft` of the collection
+#[cw_serde]
+pub enum NftHookMsg {
+    /// Auctions the CU for `duration` seconds starting now
+    CreateAuction { kind: AuctionKind, duration: u64 },
+}
+
+/// Message embedded in CW20 `Send`
+#[cw_serde]
+pub enum TokenHookMsg {
+    Bid { auction_id: u64 },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub cu_contract: Addr,
+}
+
+#[cw_serde]
+pub struct AuctionResponse {
+    pub auction: Auction,
+    /// Minimum accepted bid at the current block, `None` once the auction has ended
+    pub min_bid: Option<Uint128>,
+}
+
+#[cw_serde]
+pub struct AuctionsResponse {
+    pub auctions: Vec<Auction>,
+}
+
+#[cw_serde]
+pub enum MigrateMsg {
+    Migrate {},
+}
@@ -0,0 +1,71 @@
+use crate::contract::{load_auction, min_bid};
+use crate::msg::{AuctionResponse, AuctionsResponse, ConfigResponse};
+use crate::state::{AUCTIONS, CONFIG};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdError, StdResult};
+use cw_storage_plus::Bound;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(crate::msg::ConfigResponse)]
+    Config {},
+
+    /// Returns the auction along with the minimum bid at the current block
+    #[returns(crate::msg::AuctionResponse)]
+    Auction { auction_id: u64 },
+
+    /// Returns the open and unsettled auctions ordered by id
+    #[returns(crate::msg::AuctionsResponse)]
+    Auctions {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Auction { auction_id } => to_json_binary(&query_auction(deps, &env, auction_id)?),
+        QueryMsg::Auctions { start_after, limit } => {
+            to_json_binary(&query_auctions(deps, start_after, limit)?)
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        cu_contract: config.cu_contract,
+    })
+}
+
+pub fn query_auction(deps: Deps, env: &Env, auction_id: u64) -> StdResult<AuctionResponse> {
+    let auction =
+        load_auction(deps, auction_id).map_err(|err| StdError::generic_err(err.to_string()))?;
+    let min_bid = min_bid(&auction, env).map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(AuctionResponse { auction, min_bid })
+}
+
+pub fn query_auctions(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<AuctionsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let auctions = AUCTIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, auction)| auction))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AuctionsResponse { auctions })
+}
@@ -0,0 +1,55 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Timestamp, Uint128};
+use cw20::Denom;
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    /// Consumption unit collection auctioned on this contract
+    pub cu_contract: Addr,
+}
+
+#[cw_serde]
+pub enum AuctionKind {
+    /// Ascending auction won by the highest bid at the end
+    English {
+        /// Minimum first bid
+        start_price: Uint128,
+        /// Minimum raise over the highest bid
+        min_increment: Uint128,
+        /// Anti-sniping window in seconds: a bid placed less than `extension` seconds
+        /// before the end moves the end to `extension` seconds after the bid
+        extension: u64,
+    },
+    /// Auction won by the first bid, at a price decaying linearly from `start_price`
+    /// at the start to `end_price` at the end
+    Dutch {
+        start_price: Uint128,
+        end_price: Uint128,
+    },
+}
+
+#[cw_serde]
+pub struct Bid {
+    pub bidder: Addr,
+    pub amount: Uint128,
+}
+
+/// CU escrowed by the contract until the auction is settled or cancelled
+#[cw_serde]
+pub struct Auction {
+    pub id: u64,
+    pub token_id: String,
+    pub seller: Addr,
+    pub kind: AuctionKind,
+    /// Settlement token of the collection at the time of creation, bids are paid in it
+    pub denom: Denom,
+    pub start_time: Timestamp,
+    pub end_time: Timestamp,
+    /// Highest bid of an English auction
+    pub highest_bid: Option<Bid>,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const AUCTION_COUNT: Item<u64> = Item::new("auction_count");
+pub const AUCTIONS: Map<u64, Auction> = Map::new("auctions");
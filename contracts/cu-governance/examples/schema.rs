@@ -0,0 +1,12 @@
+use cosmwasm_schema::write_api;
+use cu_governance::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg};
+use cu_governance::query::QueryMsg;
+
+fn main() {
+    write_api! {
+        instantiate: InstantiateMsg,
+        query: QueryMsg,
+        execute: ExecuteMsg,
+        migrate: MigrateMsg,
+    }
+}
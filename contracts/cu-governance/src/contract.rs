@@ -0,0 +1,584 @@
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, ProposalMsg, TierWeight};
+use crate::state::{
+    Ballot, Config, Proposal, Status, Tally, Vote, BALLOTS, CONFIG, PROPOSALS, PROPOSAL_COUNT,
+    TIER_WEIGHTS,
+};
+use consumption_unit::msg::{TierHolding, TotalPowerResponse, VotingPowerResponse};
+use consumption_unit::query::QueryMsg as CuQueryMsg;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Decimal, Deps, DepsMut, Env, Event, MessageInfo, Order, Response,
+    StdResult, Storage, Uint128, WasmMsg,
+};
+use q_utils::fixed_point::{mul_decimal, Rounding};
+
+const CONTRACT_NAME: &str = "gemlabs.io:cu-governance";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let config = Config {
+        cu_contract: deps.api.addr_validate(&msg.cu_contract)?,
+        quorum: msg.quorum,
+        threshold: msg.threshold,
+        voting_period: msg.voting_period,
+    };
+    validate_config(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+    save_tier_weights(deps.storage, msg.tier_weights)?;
+
+    Ok(Response::default()
+        .add_attribute("action", "cu-governance::instantiate")
+        .add_event(
+            Event::new("cu-governance::instantiate")
+                .add_attribute("cu_contract", config.cu_contract.to_string())
+                .add_attribute("quorum", config.quorum.to_string())
+                .add_attribute("threshold", config.threshold.to_string()),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Propose {
+            title,
+            description,
+            msgs,
+        } => execute_propose(deps, &env, &info, title, description, msgs),
+        ExecuteMsg::Vote { proposal_id, vote } => {
+            execute_vote(deps, &env, &info, proposal_id, vote)
+        }
+        ExecuteMsg::Execute { proposal_id } => execute_execute(deps, &env, &info, proposal_id),
+        ExecuteMsg::UpdateConfig {
+            quorum,
+            threshold,
+            voting_period,
+            tier_weights,
+        } => execute_update_config(
+            deps,
+            &env,
+            &info,
+            quorum,
+            threshold,
+            voting_period,
+            tier_weights,
+        ),
+    }
+}
+
+fn validate_config(config: &Config) -> Result<(), ContractError> {
+    let valid = |share: Decimal| !share.is_zero() && share <= Decimal::one();
+    if !valid(config.quorum) || !valid(config.threshold) || config.voting_period == 0 {
+        return Err(ContractError::InvalidConfig {});
+    }
+    Ok(())
+}
+
+fn save_tier_weights(
+    storage: &mut dyn Storage,
+    weights: Vec<TierWeight>,
+) -> Result<(), ContractError> {
+    TIER_WEIGHTS.clear(storage);
+    for TierWeight { tier, weight } in weights {
+        TIER_WEIGHTS.save(storage, tier, &weight)?;
+    }
+    Ok(())
+}
+
+pub fn load_tier_weights(storage: &dyn Storage) -> StdResult<Vec<TierWeight>> {
+    TIER_WEIGHTS
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(tier, weight)| TierWeight { tier, weight }))
+        .collect()
+}
+
+/// Sums the holdings per tier times the weight of the tier, 1 for tiers without weight
+pub fn weighted_power(
+    holdings: &[TierHolding],
+    weights: &[TierWeight],
+) -> Result<Uint128, ContractError> {
+    holdings.iter().try_fold(Uint128::zero(), |power, holding| {
+        let weight = weights
+            .iter()
+            .find(|weight| weight.tier == holding.tier)
+            .map_or(Decimal::one(), |weight| weight.weight);
+        let weighted = mul_decimal(holding.consumption_value, weight, Rounding::Down)?;
+        Ok(power.checked_add(weighted)?)
+    })
+}
+
+/// Queries the holdings of `address` on the collection at the start of block `height`
+pub fn query_holdings(
+    deps: Deps,
+    cu_contract: &Addr,
+    address: &Addr,
+    height: u64,
+) -> StdResult<Vec<TierHolding>> {
+    let response: VotingPowerResponse = deps.querier.query_wasm_smart(
+        cu_contract,
+        &CuQueryMsg::VotingPower {
+            address: address.to_string(),
+            height: Some(height),
+        },
+    )?;
+    Ok(response.tiers)
+}
+
+fn load_proposal(storage: &dyn Storage, proposal_id: u64) -> Result<Proposal, ContractError> {
+    PROPOSALS
+        .may_load(storage, proposal_id)?
+        .ok_or(ContractError::ProposalNotFound { proposal_id })
+}
+
+/// Opens a proposal with voting power snapshotted at the current block, so that CUs
+/// acquired once the proposal is known do not count
+fn execute_propose(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    title: String,
+    description: String,
+    msgs: Vec<ProposalMsg>,
+) -> Result<Response, ContractError> {
+    if msgs.is_empty() {
+        return Err(ContractError::EmptyProposal {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let tier_weights = load_tier_weights(deps.storage)?;
+    let height = env.block.height;
+
+    let holdings = query_holdings(deps.as_ref(), &config.cu_contract, &info.sender, height)?;
+    if weighted_power(&holdings, &tier_weights)?.is_zero() {
+        return Err(ContractError::NoVotingPower { height });
+    }
+    let total: TotalPowerResponse = deps.querier.query_wasm_smart(
+        &config.cu_contract,
+        &CuQueryMsg::TotalPower {
+            height: Some(height),
+        },
+    )?;
+    let total_power = weighted_power(&total.tiers, &tier_weights)?;
+
+    let id = PROPOSAL_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    PROPOSAL_COUNT.save(deps.storage, &id)?;
+    let proposal = Proposal {
+        id,
+        title,
+        description,
+        proposer: info.sender.clone(),
+        msgs,
+        snapshot_height: height,
+        end_time: env.block.time.plus_seconds(config.voting_period),
+        quorum: config.quorum,
+        threshold: config.threshold,
+        tier_weights,
+        total_power,
+        tally: Tally::default(),
+        status: Status::Open,
+    };
+    PROPOSALS.save(deps.storage, id, &proposal)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-governance::propose")
+        .add_event(
+            Event::new("cu-governance::propose")
+                .add_attribute("proposal_id", id.to_string())
+                .add_attribute("proposer", info.sender.to_string())
+                .add_attribute("snapshot_height", height.to_string())
+                .add_attribute("end_time", proposal.end_time.to_string())
+                .add_attribute("total_power", total_power.to_string()),
+        ))
+}
+
+fn execute_vote(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    proposal_id: u64,
+    vote: Vote,
+) -> Result<Response, ContractError> {
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    if proposal.current_status(env) != Status::Open {
+        return Err(ContractError::VotingEnded { proposal_id });
+    }
+    if BALLOTS.has(deps.storage, (proposal_id, &info.sender)) {
+        return Err(ContractError::AlreadyVoted { proposal_id });
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let holdings = query_holdings(
+        deps.as_ref(),
+        &config.cu_contract,
+        &info.sender,
+        proposal.snapshot_height,
+    )?;
+    let power = weighted_power(&holdings, &proposal.tier_weights)?;
+    if power.is_zero() {
+        return Err(ContractError::NoVotingPower {
+            height: proposal.snapshot_height,
+        });
+    }
+
+    proposal.tally.add(&vote, power)?;
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+    let ballot = Ballot { vote, power };
+    BALLOTS.save(deps.storage, (proposal_id, &info.sender), &ballot)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-governance::vote")
+        .add_event(
+            Event::new("cu-governance::vote")
+                .add_attribute("proposal_id", proposal_id.to_string())
+                .add_attribute("voter", info.sender.to_string())
+                .add_attribute("vote", format!("{:?}", ballot.vote).to_lowercase())
+                .add_attribute("power", power.to_string()),
+        ))
+}
+
+fn execute_execute(
+    deps: DepsMut,
+    env: &Env,
+    _info: &MessageInfo,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let mut proposal = load_proposal(deps.storage, proposal_id)?;
+    if proposal.current_status(env) != Status::Passed {
+        return Err(ContractError::NotPassed { proposal_id });
+    }
+    proposal.status = Status::Executed;
+    PROPOSALS.save(deps.storage, proposal_id, &proposal)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let msgs = proposal
+        .msgs
+        .into_iter()
+        .map(|msg| match msg {
+            ProposalMsg::ConsumptionUnit(msg) => Ok(WasmMsg::Execute {
+                contract_addr: config.cu_contract.to_string(),
+                msg: to_json_binary(&msg)?,
+                funds: vec![],
+            }),
+            ProposalMsg::UpdateConfig {
+                quorum,
+                threshold,
+                voting_period,
+                tier_weights,
+            } => Ok(WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_json_binary(&ExecuteMsg::UpdateConfig {
+                    quorum,
+                    threshold,
+                    voting_period,
+                    tier_weights,
+                })?,
+                funds: vec![],
+            }),
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-governance::execute")
+        .add_messages(msgs)
+        .add_event(
+            Event::new("cu-governance::execute")
+                .add_attribute("proposal_id", proposal_id.to_string()),
+        ))
+}
+
+/// Updates the config, only applies to proposals opened afterwards
+fn execute_update_config(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    quorum: Option<Decimal>,
+    threshold: Option<Decimal>,
+    voting_period: Option<u64>,
+    tier_weights: Option<Vec<TierWeight>>,
+) -> Result<Response, ContractError> {
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(quorum) = quorum {
+        config.quorum = quorum;
+    }
+    if let Some(threshold) = threshold {
+        config.threshold = threshold;
+    }
+    if let Some(voting_period) = voting_period {
+        config.voting_period = voting_period;
+    }
+    validate_config(&config)?;
+    CONFIG.save(deps.storage, &config)?;
+    if let Some(tier_weights) = tier_weights {
+        save_tier_weights(deps.storage, tier_weights)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-governance::update_config")
+        .add_event(
+            Event::new("cu-governance::update_config")
+                .add_attribute("quorum", config.quorum.to_string())
+                .add_attribute("threshold", config.threshold.to_string())
+                .add_attribute("voting_period", config.voting_period.to_string()),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    match msg {
+        MigrateMsg::Migrate {} => Ok(Response::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::query_proposal;
+    use cosmwasm_std::testing::{
+        message_info, mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_json, ContractResult, Empty, OwnedDeps, SystemResult, WasmQuery};
+
+    type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>;
+
+    fn holding(tier: u16, consumption_value: u128) -> TierHolding {
+        TierHolding {
+            tier,
+            consumption_value: Uint128::new(consumption_value),
+        }
+    }
+
+    /// Instantiates the governance for a mocked collection where alice holds 300 in tier 1
+    /// and 100 in tier 2 with weight 2, and bob holds 200 in tier 1
+    fn setup() -> (MockDeps, Addr, Addr) {
+        let mut deps = mock_dependencies();
+        let cu_contract = deps.api.addr_make("cu_contract");
+        let alice = deps.api.addr_make("alice");
+        let bob = deps.api.addr_make("bob");
+
+        let (holder_a, holder_b) = (alice.clone(), bob.clone());
+        deps.querier.update_wasm(move |query| {
+            let WasmQuery::Smart { msg, .. } = query else {
+                panic!("unexpected query {query:?}");
+            };
+            let response = match from_json(msg).unwrap() {
+                CuQueryMsg::VotingPower { address, height } => {
+                    let tiers = if address == holder_a.as_str() {
+                        vec![holding(1, 300), holding(2, 100)]
+                    } else if address == holder_b.as_str() {
+                        vec![holding(1, 200)]
+                    } else {
+                        vec![]
+                    };
+                    let total = tiers.iter().map(|holding| holding.consumption_value).sum();
+                    to_json_binary(&VotingPowerResponse {
+                        address: Addr::unchecked(address),
+                        height: height.unwrap(),
+                        tiers,
+                        total,
+                    })
+                }
+                CuQueryMsg::TotalPower { height } => to_json_binary(&TotalPowerResponse {
+                    height: height.unwrap(),
+                    tiers: vec![holding(1, 500), holding(2, 100)],
+                    total: Uint128::new(600),
+                }),
+                msg => panic!("unexpected query {msg:?}"),
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        });
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&alice, &[]),
+            InstantiateMsg {
+                cu_contract: cu_contract.to_string(),
+                quorum: Decimal::percent(40),
+                threshold: Decimal::percent(50),
+                voting_period: 100,
+                tier_weights: vec![TierWeight {
+                    tier: 2,
+                    weight: Decimal::percent(200),
+                }],
+            },
+        )
+        .unwrap();
+        (deps, alice, bob)
+    }
+
+    fn propose(deps: &mut MockDeps, proposer: &Addr) -> u64 {
+        let response = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(proposer, &[]),
+            ExecuteMsg::Propose {
+                title: "Accept the creator role".to_string(),
+                description: "".to_string(),
+                msgs: vec![
+                    ProposalMsg::ConsumptionUnit(
+                        consumption_unit::msg::ExecuteMsg::UpdateCreatorOwnership(
+                            cw_ownable::Action::AcceptOwnership,
+                        ),
+                    ),
+                    ProposalMsg::UpdateConfig {
+                        quorum: Some(Decimal::percent(50)),
+                        threshold: None,
+                        voting_period: None,
+                        tier_weights: None,
+                    },
+                ],
+            },
+        )
+        .unwrap();
+        response.events[0].attributes[0].value.parse().unwrap()
+    }
+
+    fn vote(
+        deps: &mut MockDeps,
+        env: &Env,
+        voter: &Addr,
+        proposal_id: u64,
+        vote: Vote,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(voter, &[]),
+            ExecuteMsg::Vote { proposal_id, vote },
+        )
+    }
+
+    fn execute_proposal(
+        deps: &mut MockDeps,
+        env: &Env,
+        proposal_id: u64,
+    ) -> Result<Response, ContractError> {
+        let sender = deps.api.addr_make("anyone");
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sender, &[]),
+            ExecuteMsg::Execute { proposal_id },
+        )
+    }
+
+    #[test]
+    fn test_proposal_lifecycle() {
+        let (mut deps, alice, bob) = setup();
+        let mut env = mock_env();
+        let carol = deps.api.addr_make("carol");
+
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&carol, &[]),
+            ExecuteMsg::Propose {
+                title: "".to_string(),
+                description: "".to_string(),
+                msgs: vec![],
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::EmptyProposal {}));
+
+        let proposal_id = propose(&mut deps, &alice);
+        let proposal = query_proposal(deps.as_ref(), &env, proposal_id).unwrap();
+        assert_eq!(proposal.proposal.total_power, Uint128::new(700));
+        assert_eq!(proposal.status, Status::Open);
+
+        // tier 2 holdings count twice
+        vote(&mut deps, &env, &alice, proposal_id, Vote::Yes).unwrap();
+        vote(&mut deps, &env, &bob, proposal_id, Vote::No).unwrap();
+        let err = vote(&mut deps, &env, &bob, proposal_id, Vote::Yes).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyVoted { .. }));
+        let err = vote(&mut deps, &env, &carol, proposal_id, Vote::Yes).unwrap_err();
+        assert!(matches!(err, ContractError::NoVotingPower { .. }));
+        let tally = query_proposal(deps.as_ref(), &env, proposal_id)
+            .unwrap()
+            .proposal
+            .tally;
+        assert_eq!(tally.yes, Uint128::new(500));
+        assert_eq!(tally.no, Uint128::new(200));
+
+        let err = execute_proposal(&mut deps, &env, proposal_id).unwrap_err();
+        assert!(matches!(err, ContractError::NotPassed { .. }));
+
+        env.block.time = env.block.time.plus_seconds(100);
+        let err = vote(&mut deps, &env, &carol, proposal_id, Vote::No).unwrap_err();
+        assert!(matches!(err, ContractError::VotingEnded { .. }));
+        let response = execute_proposal(&mut deps, &env, proposal_id).unwrap();
+        assert_eq!(response.messages.len(), 2);
+        assert_eq!(
+            response.messages[1].msg,
+            WasmMsg::Execute {
+                contract_addr: env.contract.address.to_string(),
+                msg: to_json_binary(&ExecuteMsg::UpdateConfig {
+                    quorum: Some(Decimal::percent(50)),
+                    threshold: None,
+                    voting_period: None,
+                    tier_weights: None,
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+        let status = query_proposal(deps.as_ref(), &env, proposal_id)
+            .unwrap()
+            .status;
+        assert_eq!(status, Status::Executed);
+        let err = execute_proposal(&mut deps, &env, proposal_id).unwrap_err();
+        assert!(matches!(err, ContractError::NotPassed { .. }));
+
+        // the config can only be updated through a proposal
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&alice, &[]),
+            ExecuteMsg::UpdateConfig {
+                quorum: Some(Decimal::percent(1)),
+                threshold: None,
+                voting_period: None,
+                tier_weights: None,
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+    }
+
+    #[test]
+    fn test_quorum_not_reached() {
+        let (mut deps, alice, bob) = setup();
+        let mut env = mock_env();
+
+        // 200 of 700 voted, below the quorum of 40%
+        let proposal_id = propose(&mut deps, &alice);
+        vote(&mut deps, &env, &bob, proposal_id, Vote::Yes).unwrap();
+        env.block.time = env.block.time.plus_seconds(100);
+        let status = query_proposal(deps.as_ref(), &env, proposal_id)
+            .unwrap()
+            .status;
+        assert_eq!(status, Status::Rejected);
+        let err = execute_proposal(&mut deps, &env, proposal_id).unwrap_err();
+        assert!(matches!(err, ContractError::NotPassed { .. }));
+    }
+}
@@ -0,0 +1,29 @@
+use cosmwasm_std::{OverflowError, StdError};
+use q_utils::fixed_point::FixedPointError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+    #[error("{0}")]
+    FixedPoint(#[from] FixedPointError),
+    #[error("Unauthorized")]
+    Unauthorized {},
+    #[error("Quorum and threshold must be greater than 0 and at most 1, the voting period must not be 0")]
+    InvalidConfig {},
+    #[error("A proposal must contain at least one message")]
+    EmptyProposal {},
+    #[error("The sender has no voting power at height {height}")]
+    NoVotingPower { height: u64 },
+    #[error("Proposal {proposal_id} not found")]
+    ProposalNotFound { proposal_id: u64 },
+    #[error("Voting on proposal {proposal_id} has ended")]
+    VotingEnded { proposal_id: u64 },
+    #[error("The sender already voted on proposal {proposal_id}")]
+    AlreadyVoted { proposal_id: u64 },
+    #[error("Proposal {proposal_id} has not passed")]
+    NotPassed { proposal_id: u64 },
+}
@@ -0,0 +1,98 @@
+use crate::state::{Proposal, Status};
+use consumption_unit::msg::ExecuteMsg as CuExecuteMsg;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+
+#[cw_serde]
+pub struct TierWeight {
+    pub tier: u16,
+    pub weight: Decimal,
+}
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Address of the consumption unit collection
+    pub cu_contract: String,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub voting_period: u64,
+    pub tier_weights: Vec<TierWeight>,
+}
+
+/// Message dispatched when a passed proposal is executed
+#[cw_serde]
+pub enum ProposalMsg {
+    /// Executes the message on the consumption unit collection,
+    /// the governance contract must be its creator for config changes
+    ConsumptionUnit(CuExecuteMsg),
+
+    /// Updates the config of the governance contract itself
+    UpdateConfig {
+        quorum: Option<Decimal>,
+        threshold: Option<Decimal>,
+        voting_period: Option<u64>,
+        tier_weights: Option<Vec<TierWeight>>,
+    },
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Opens a proposal, the sender must hold CUs at the current height
+    Propose {
+        title: String,
+        description: String,
+        msgs: Vec<ProposalMsg>,
+    },
+
+    /// Votes with the power of the sender at the snapshot height of the proposal
+    Vote {
+        proposal_id: u64,
+        vote: crate::state::Vote,
+    },
+
+    /// Dispatches the messages of a passed proposal, can be called by anyone
+    Execute { proposal_id: u64 },
+
+    /// Updates the config, can only be called by the contract itself through a proposal.
+    /// Fields set to `None` are left unchanged.
+    UpdateConfig {
+        quorum: Option<Decimal>,
+        threshold: Option<Decimal>,
+        voting_period: Option<u64>,
+        tier_weights: Option<Vec<TierWeight>>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub cu_contract: Addr,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub voting_period: u64,
+    pub tier_weights: Vec<TierWeight>,
+}
+
+#[cw_serde]
+pub struct ProposalResponse {
+    pub proposal: Proposal,
+    /// Status at the current block
+    pub status: Status,
+}
+
+#[cw_serde]
+pub struct ProposalsResponse {
+    pub proposals: Vec<ProposalResponse>,
+}
+
+#[cw_serde]
+pub struct VotingPowerResponse {
+    pub address: Addr,
+    pub height: u64,
+    /// Sum of the `consumption_value` held per tier times the weight of the tier
+    pub power: Uint128,
+}
+
+#[cw_serde]
+pub enum MigrateMsg {
+    Migrate {},
+}
@@ -0,0 +1,126 @@
+use crate::contract::{load_tier_weights, query_holdings, weighted_power};
+use crate::msg::{ConfigResponse, ProposalResponse, ProposalsResponse, VotingPowerResponse};
+use crate::state::{Ballot, BALLOTS, CONFIG, PROPOSALS};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdError, StdResult};
+use cw_storage_plus::Bound;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(crate::msg::ConfigResponse)]
+    Config {},
+
+    #[returns(crate::msg::ProposalResponse)]
+    Proposal { proposal_id: u64 },
+
+    /// Returns the proposals ordered by id
+    #[returns(crate::msg::ProposalsResponse)]
+    Proposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Option<crate::state::Ballot>)]
+    Ballot { proposal_id: u64, voter: String },
+
+    /// Returns the voting power of the address at the start of block `height` with
+    /// the current tier weights, the current block if not set
+    #[returns(crate::msg::VotingPowerResponse)]
+    VotingPower {
+        address: String,
+        height: Option<u64>,
+    },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Proposal { proposal_id } => {
+            to_json_binary(&query_proposal(deps, &env, proposal_id)?)
+        }
+        QueryMsg::Proposals { start_after, limit } => {
+            to_json_binary(&query_proposals(deps, &env, start_after, limit)?)
+        }
+        QueryMsg::Ballot { proposal_id, voter } => {
+            to_json_binary(&query_ballot(deps, proposal_id, voter)?)
+        }
+        QueryMsg::VotingPower { address, height } => {
+            to_json_binary(&query_voting_power(deps, &env, address, height)?)
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        cu_contract: config.cu_contract,
+        quorum: config.quorum,
+        threshold: config.threshold,
+        voting_period: config.voting_period,
+        tier_weights: load_tier_weights(deps.storage)?,
+    })
+}
+
+pub fn query_proposal(deps: Deps, env: &Env, proposal_id: u64) -> StdResult<ProposalResponse> {
+    let proposal = PROPOSALS.load(deps.storage, proposal_id)?;
+    Ok(ProposalResponse {
+        status: proposal.current_status(env),
+        proposal,
+    })
+}
+
+pub fn query_proposals(
+    deps: Deps,
+    env: &Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let proposals = PROPOSALS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(_, proposal)| ProposalResponse {
+                status: proposal.current_status(env),
+                proposal,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(ProposalsResponse { proposals })
+}
+
+pub fn query_ballot(deps: Deps, proposal_id: u64, voter: String) -> StdResult<Option<Ballot>> {
+    let voter = deps.api.addr_validate(&voter)?;
+    BALLOTS.may_load(deps.storage, (proposal_id, &voter))
+}
+
+pub fn query_voting_power(
+    deps: Deps,
+    env: &Env,
+    address: String,
+    height: Option<u64>,
+) -> StdResult<VotingPowerResponse> {
+    let address = deps.api.addr_validate(&address)?;
+    let height = height.unwrap_or(env.block.height);
+    let config = CONFIG.load(deps.storage)?;
+
+    let holdings = query_holdings(deps, &config.cu_contract, &address, height)?;
+    let power = weighted_power(&holdings, &load_tier_weights(deps.storage)?)
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(VotingPowerResponse {
+        address,
+        height,
+        power,
+    })
+}
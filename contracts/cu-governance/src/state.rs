@@ -0,0 +1,110 @@
+use crate::msg::{ProposalMsg, TierWeight};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Env, OverflowError, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    /// Consumption unit collection whose holders vote
+    pub cu_contract: Addr,
+    /// Minimum share of the total voting power that must vote for a proposal to pass
+    pub quorum: Decimal,
+    /// Minimum share of yes votes among yes and no votes for a proposal to pass
+    pub threshold: Decimal,
+    /// Seconds a proposal is open for voting
+    pub voting_period: u64,
+}
+
+#[cw_serde]
+pub enum Vote {
+    Yes,
+    No,
+    Abstain,
+}
+
+#[cw_serde]
+pub enum Status {
+    /// Voting is ongoing
+    Open,
+    /// Voting has ended with quorum and threshold reached
+    Passed,
+    /// Voting has ended without reaching quorum or threshold
+    Rejected,
+    /// The messages of the passed proposal were dispatched
+    Executed,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct Tally {
+    pub yes: Uint128,
+    pub no: Uint128,
+    pub abstain: Uint128,
+}
+
+impl Tally {
+    pub fn add(&mut self, vote: &Vote, power: Uint128) -> Result<(), OverflowError> {
+        let count = match vote {
+            Vote::Yes => &mut self.yes,
+            Vote::No => &mut self.no,
+            Vote::Abstain => &mut self.abstain,
+        };
+        *count = count.checked_add(power)?;
+        Ok(())
+    }
+}
+
+#[cw_serde]
+pub struct Proposal {
+    pub id: u64,
+    pub title: String,
+    pub description: String,
+    pub proposer: Addr,
+    pub msgs: Vec<ProposalMsg>,
+    /// Voting power is taken from the CU holdings at the start of this block
+    pub snapshot_height: u64,
+    pub end_time: Timestamp,
+    /// Quorum, threshold and tier weights at the time of proposing
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub tier_weights: Vec<TierWeight>,
+    /// Weighted voting power of all holders at the snapshot height
+    pub total_power: Uint128,
+    pub tally: Tally,
+    /// Stored status, `Open` until the proposal is executed
+    pub status: Status,
+}
+
+impl Proposal {
+    /// Status of the proposal at the current block
+    pub fn current_status(&self, env: &Env) -> Status {
+        match self.status {
+            Status::Open if env.block.time < self.end_time => Status::Open,
+            Status::Open if self.passed() => Status::Passed,
+            Status::Open => Status::Rejected,
+            ref status => status.clone(),
+        }
+    }
+
+    fn passed(&self) -> bool {
+        let Tally { yes, no, abstain } = self.tally;
+        if self.total_power.is_zero() || yes.is_zero() {
+            return false;
+        }
+        let turnout = Decimal::from_ratio(yes + no + abstain, self.total_power);
+        turnout >= self.quorum && Decimal::from_ratio(yes, yes + no) >= self.threshold
+    }
+}
+
+#[cw_serde]
+pub struct Ballot {
+    pub vote: Vote,
+    pub power: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Weights of the `consumption_value` per commitment tier, 1 if not set
+pub const TIER_WEIGHTS: Map<u16, Decimal> = Map::new("tier_weights");
+pub const PROPOSAL_COUNT: Item<u64> = Item::new("proposal_count");
+pub const PROPOSALS: Map<u64, Proposal> = Map::new("proposals");
+pub const BALLOTS: Map<(u64, &Addr), Ballot> = Map::new("ballots");
@@ -0,0 +1,576 @@
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, NftHookMsg, TokenHookMsg};
+use crate::state::{listings, Config, Listing, Royalty, CONFIG};
+use consumption_unit::msg::{ExecuteMsg as CuExecuteMsg, IsFrozenResponse};
+use consumption_unit::query::QueryMsg as CuQueryMsg;
+use consumption_unit::types::{CUConfig, ConsumptionUnitData};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Decimal, Deps, DepsMut, Env, Event, MessageInfo, Response,
+    StdResult, Uint128, WasmMsg,
+};
+use cw20::Cw20ReceiveMsg;
+use q_nft::msg::{ContractInfoResponse, Cw721ReceiveMsg, NftInfoResponse};
+use q_utils::asset::{denom_to_string, Asset};
+use q_utils::fixed_point::{mul_decimal, Rounding};
+
+const CONTRACT_NAME: &str = "gemlabs.io:cu-marketplace";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let owner = msg.owner.as_deref().unwrap_or(info.sender.as_str());
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(owner))?;
+
+    let config = Config {
+        cu_contract: deps.api.addr_validate(&msg.cu_contract)?,
+        royalty: validate_royalty(deps.as_ref(), msg.royalty)?,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::default()
+        .add_attribute("action", "cu-marketplace::instantiate")
+        .add_event(
+            Event::new("cu-marketplace::instantiate")
+                .add_attribute("cu_contract", config.cu_contract.to_string())
+                .add_attribute("owner", owner),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ReceiveNft(msg) => execute_list(deps, &env, &info, msg),
+        ExecuteMsg::Receive(wrapper) => execute_receive(deps, &env, &info, wrapper),
+        ExecuteMsg::Buy { token_id } => {
+            let listing = load_listing(deps.as_ref(), &token_id)?;
+            let paid = Asset::from_funds(&listing.price.denom, &info)?;
+            buy(deps, listing, info.sender, paid.amount)
+        }
+        ExecuteMsg::Cancel { token_id } => execute_cancel(deps, &env, &info, token_id),
+        ExecuteMsg::UpdateConfig { royalty } => execute_update_config(deps, &env, &info, royalty),
+        ExecuteMsg::UpdateOwnership(action) => {
+            let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+            Ok(Response::new().add_attributes(ownership.into_attributes()))
+        }
+    }
+}
+
+fn validate_royalty(
+    deps: Deps,
+    royalty: Option<Royalty>,
+) -> Result<Option<Royalty>, ContractError> {
+    let Some(royalty) = royalty else {
+        return Ok(None);
+    };
+    if royalty.rate > Decimal::one() {
+        return Err(ContractError::InvalidRoyalty {});
+    }
+    Ok(Some(Royalty {
+        recipient: deps.api.addr_validate(royalty.recipient.as_str())?,
+        rate: royalty.rate,
+    }))
+}
+
+fn load_listing(deps: Deps, token_id: &str) -> Result<Listing, ContractError> {
+    listings()
+        .may_load(deps.storage, token_id)?
+        .ok_or(ContractError::NotListed {
+            token_id: token_id.to_string(),
+        })
+}
+
+/// Whether the collection froze `address`, frozen holders may not sell or receive proceeds
+fn is_frozen(deps: Deps, config: &Config, address: &Addr) -> StdResult<bool> {
+    let response: IsFrozenResponse = deps.querier.query_wasm_smart(
+        &config.cu_contract,
+        &CuQueryMsg::IsFrozen {
+            address: address.to_string(),
+        },
+    )?;
+    Ok(response.frozen)
+}
+
+fn transfer_cu_msg(config: &Config, recipient: &Addr, token_id: String) -> StdResult<WasmMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: config.cu_contract.to_string(),
+        msg: to_json_binary(&CuExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id,
+        })?,
+        funds: vec![],
+    })
+}
+
+/// Lists a CU escrowed through `SendNft` of the collection
+fn execute_list(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.cu_contract {
+        return Err(ContractError::UnsupportedNft {});
+    }
+    let NftHookMsg::List { price } = from_json(&msg.msg)?;
+    if price.is_zero() {
+        return Err(ContractError::InvalidPrice {});
+    }
+
+    let collection: ContractInfoResponse<CUConfig> = deps
+        .querier
+        .query_wasm_smart(&config.cu_contract, &CuQueryMsg::ContractInfo {})?;
+    let nft_info: NftInfoResponse<ConsumptionUnitData> = deps.querier.query_wasm_smart(
+        &config.cu_contract,
+        &CuQueryMsg::NftInfo {
+            token_id: msg.token_id.clone(),
+        },
+    )?;
+
+    let listing = Listing {
+        token_id: msg.token_id,
+        seller: deps.api.addr_validate(&msg.sender)?,
+        tier: nft_info.extension.commitment_tier,
+        price: Asset::new(collection.collection_config.settlement_token, price),
+        listed_at: env.block.time,
+    };
+    listings().save(deps.storage, &listing.token_id, &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-marketplace::list")
+        .add_event(
+            Event::new("cu-marketplace::list")
+                .add_attribute("token_id", listing.token_id)
+                .add_attribute("seller", listing.seller.to_string())
+                .add_attribute("tier", listing.tier.to_string())
+                .add_attribute("denom", denom_to_string(&listing.price.denom))
+                .add_attribute("price", listing.price.amount.to_string()),
+        ))
+}
+
+/// Handles CW20 settlement tokens sent through the `Send` hook
+fn execute_receive(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let TokenHookMsg::Buy { token_id } = from_json(&wrapper.msg)?;
+    let listing = load_listing(deps.as_ref(), &token_id)?;
+    let paid = Asset::from_cw20_receive(&listing.price.denom, info, &wrapper)?;
+    let buyer = deps.api.addr_validate(&wrapper.sender)?;
+    buy(deps, listing, buyer, paid.amount)
+}
+
+/// Settles a purchase: the CU goes to the buyer, the royalty to its recipient
+/// and the rest of the price to the seller, who must not be frozen
+fn buy(
+    deps: DepsMut,
+    listing: Listing,
+    buyer: Addr,
+    paid: Uint128,
+) -> Result<Response, ContractError> {
+    if paid != listing.price.amount {
+        return Err(ContractError::InvalidPayment {
+            expected: listing.price.amount,
+            received: paid,
+        });
+    }
+    let config = CONFIG.load(deps.storage)?;
+    if is_frozen(deps.as_ref(), &config, &listing.seller)? {
+        return Err(ContractError::Frozen {
+            address: listing.seller.to_string(),
+        });
+    }
+    listings().remove(deps.storage, &listing.token_id)?;
+
+    let mut response = Response::new()
+        .add_attribute("action", "cu-marketplace::buy")
+        .add_message(transfer_cu_msg(&config, &buyer, listing.token_id.clone())?);
+
+    let royalty = match &config.royalty {
+        Some(royalty) => {
+            let amount = mul_decimal(listing.price.amount, royalty.rate, Rounding::Down)?;
+            if !amount.is_zero() {
+                let payment = Asset::new(listing.price.denom.clone(), amount);
+                response = response.add_message(payment.transfer_msg(&royalty.recipient)?);
+            }
+            amount
+        }
+        None => Uint128::zero(),
+    };
+    let proceeds = listing.price.amount - royalty;
+    if !proceeds.is_zero() {
+        let payment = Asset::new(listing.price.denom.clone(), proceeds);
+        response = response.add_message(payment.transfer_msg(&listing.seller)?);
+    }
+
+    Ok(response.add_event(
+        Event::new("cu-marketplace::buy")
+            .add_attribute("token_id", listing.token_id)
+            .add_attribute("seller", listing.seller.to_string())
+            .add_attribute("buyer", buyer.to_string())
+            .add_attribute("price", listing.price.amount.to_string())
+            .add_attribute("royalty", royalty.to_string()),
+    ))
+}
+
+fn execute_cancel(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let listing = load_listing(deps.as_ref(), &token_id)?;
+    if listing.seller != info.sender {
+        return Err(ContractError::NotSeller {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    listings().remove(deps.storage, &token_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-marketplace::cancel")
+        .add_message(transfer_cu_msg(&config, &listing.seller, token_id.clone())?)
+        .add_event(
+            Event::new("cu-marketplace::cancel")
+                .add_attribute("token_id", token_id)
+                .add_attribute("seller", listing.seller.to_string()),
+        ))
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    _env: &Env,
+    info: &MessageInfo,
+    royalty: Option<Royalty>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.royalty = validate_royalty(deps.as_ref(), royalty)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    let mut event = Event::new("cu-marketplace::update_config");
+    if let Some(royalty) = &config.royalty {
+        event = event
+            .add_attribute("royalty_recipient", royalty.recipient.to_string())
+            .add_attribute("royalty_rate", royalty.rate.to_string());
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-marketplace::update_config")
+        .add_event(event))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    match msg {
+        MigrateMsg::Migrate {} => Ok(Response::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::msg::PriceCursor;
+    use crate::query::{query_listings_by_price, query_listings_by_tier};
+    use consumption_unit::types::ConsumptionUnitState;
+    use cosmwasm_std::testing::{
+        message_info, mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{coins, BankMsg, ContractResult, Empty, OwnedDeps, SystemResult, WasmQuery};
+    use cw20::{Cw20ExecuteMsg, Denom};
+    use q_nft::state::CollectionInfo;
+
+    type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>;
+
+    /// Instantiates the marketplace for a mocked collection where the tier of CU `n` is `n`
+    /// and the address `frozen` is frozen
+    fn setup(settlement_token: Denom, royalty: Option<Royalty>) -> (MockDeps, Addr) {
+        let mut deps = mock_dependencies();
+        let cu_contract = deps.api.addr_make("cu_contract");
+        let owner = deps.api.addr_make("owner");
+        let frozen = deps.api.addr_make("frozen");
+
+        let env = mock_env();
+        deps.querier.update_wasm(move |query| {
+            let WasmQuery::Smart { msg, .. } = query else {
+                panic!("unexpected query {query:?}");
+            };
+            let response = match from_json(msg).unwrap() {
+                CuQueryMsg::ContractInfo {} => to_json_binary(&ContractInfoResponse {
+                    collection_info: CollectionInfo {
+                        name: "consumption unit".to_string(),
+                        symbol: "cu".to_string(),
+                        updated_at: env.block.time,
+                    },
+                    collection_config: CUConfig {
+                        settlement_token: settlement_token.clone(),
+                        native_token: Denom::Native("native".to_string()),
+                        price_oracles: vec![],
+                        oracle_quorum: 1,
+                    },
+                }),
+                CuQueryMsg::NftInfo { token_id } => to_json_binary(&NftInfoResponse {
+                    extension: ConsumptionUnitData {
+                        consumption_value: Uint128::new(1_000),
+                        nominal_quantity: Uint128::new(1_000),
+                        nominal_currency: "usd".to_string(),
+                        commitment_tier: token_id.parse().unwrap(),
+                        state: ConsumptionUnitState::Reflected,
+                        floor_price: Decimal::percent(50),
+                        hashes: vec![],
+                        created_at: env.block.time,
+                        updated_at: env.block.time,
+                    },
+                }),
+                CuQueryMsg::IsFrozen { address } => to_json_binary(&IsFrozenResponse {
+                    frozen: address == frozen.as_str(),
+                    address: Addr::unchecked(address),
+                    info: None,
+                }),
+                msg => panic!("unexpected query {msg:?}"),
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        });
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            InstantiateMsg {
+                cu_contract: cu_contract.to_string(),
+                royalty,
+                owner: None,
+            },
+        )
+        .unwrap();
+        (deps, cu_contract)
+    }
+
+    fn list(
+        deps: &mut MockDeps,
+        sender: &Addr,
+        seller: &Addr,
+        token_id: &str,
+        price: u128,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(sender, &[]),
+            ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: seller.to_string(),
+                token_id: token_id.to_string(),
+                msg: to_json_binary(&NftHookMsg::List {
+                    price: Uint128::new(price),
+                })
+                .unwrap(),
+            }),
+        )
+    }
+
+    #[test]
+    fn test_native_sale_with_royalty() {
+        let api = MockApi::default();
+        let seller = api.addr_make("seller");
+        let buyer = api.addr_make("buyer");
+        let artist = api.addr_make("artist");
+        let (mut deps, cu_contract) = setup(
+            Denom::Native("settlement".to_string()),
+            Some(Royalty {
+                recipient: artist.clone(),
+                rate: Decimal::percent(5),
+            }),
+        );
+
+        let err = list(&mut deps, &seller, &seller, "1", 1_000).unwrap_err();
+        assert!(matches!(err, ContractError::UnsupportedNft {}));
+        let err = list(&mut deps, &cu_contract, &seller, "1", 0).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPrice {}));
+        list(&mut deps, &cu_contract, &seller, "1", 1_010).unwrap();
+
+        let buy_msg = ExecuteMsg::Buy {
+            token_id: "1".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&buyer, &coins(1_000, "settlement")),
+            buy_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPayment { .. }));
+
+        let response = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&buyer, &coins(1_010, "settlement")),
+            buy_msg,
+        )
+        .unwrap();
+        let messages: Vec<_> = response.messages.into_iter().map(|msg| msg.msg).collect();
+        assert_eq!(
+            messages,
+            vec![
+                transfer_cu_msg(
+                    &CONFIG.load(&deps.storage).unwrap(),
+                    &buyer,
+                    "1".to_string()
+                )
+                .unwrap()
+                .into(),
+                BankMsg::Send {
+                    to_address: artist.to_string(),
+                    amount: coins(50, "settlement"),
+                }
+                .into(),
+                BankMsg::Send {
+                    to_address: seller.to_string(),
+                    amount: coins(960, "settlement"),
+                }
+                .into(),
+            ]
+        );
+
+        let err = load_listing(deps.as_ref(), "1").unwrap_err();
+        assert!(matches!(err, ContractError::NotListed { .. }));
+    }
+
+    #[test]
+    fn test_cw20_sale_and_cancel() {
+        let api = MockApi::default();
+        let token = api.addr_make("token");
+        let alice = api.addr_make("alice");
+        let bob = api.addr_make("bob");
+        let (mut deps, cu_contract) = setup(Denom::Cw20(token.clone()), None);
+
+        list(&mut deps, &cu_contract, &alice, "1", 300).unwrap();
+        list(&mut deps, &cu_contract, &alice, "2", 100).unwrap();
+        list(&mut deps, &cu_contract, &bob, "3", 200).unwrap();
+        list(&mut deps, &cu_contract, &bob, "4", 100).unwrap();
+
+        let by_price = query_listings_by_price(deps.as_ref(), None, Some(3)).unwrap();
+        let token_ids: Vec<_> = by_price
+            .listings
+            .iter()
+            .map(|listing| listing.token_id.as_str())
+            .collect();
+        assert_eq!(token_ids, vec!["2", "4", "3"]);
+        let next = query_listings_by_price(
+            deps.as_ref(),
+            Some(PriceCursor {
+                price: Uint128::new(100),
+                token_id: "4".to_string(),
+            }),
+            None,
+        )
+        .unwrap();
+        assert_eq!(next.listings.len(), 2);
+        assert_eq!(next.listings[0].token_id, "3");
+        let by_tier = query_listings_by_tier(deps.as_ref(), 3, None, None).unwrap();
+        assert_eq!(by_tier.listings[0].seller, bob);
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&bob, &[]),
+            ExecuteMsg::Cancel {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotSeller {}));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&alice, &[]),
+            ExecuteMsg::Cancel {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+
+        let buy_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: bob.to_string(),
+            amount: Uint128::new(100),
+            msg: to_json_binary(&TokenHookMsg::Buy {
+                token_id: "2".to_string(),
+            })
+            .unwrap(),
+        });
+        // the price must be paid in the settlement token
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&bob, &[]),
+            buy_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Asset(_)));
+
+        let response = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&token, &[]),
+            buy_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            response.messages[1].msg,
+            WasmMsg::Execute {
+                contract_addr: token.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: alice.to_string(),
+                    amount: Uint128::new(100),
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+
+        let remaining = query_listings_by_price(deps.as_ref(), None, None).unwrap();
+        let token_ids: Vec<_> = remaining
+            .listings
+            .iter()
+            .map(|listing| listing.token_id.as_str())
+            .collect();
+        assert_eq!(token_ids, vec!["4", "3"]);
+
+        // the CUs of frozen sellers cannot be bought
+        let frozen = api.addr_make("frozen");
+        list(&mut deps, &cu_contract, &frozen, "5", 100).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&token, &[]),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: bob.to_string(),
+                amount: Uint128::new(100),
+                msg: to_json_binary(&TokenHookMsg::Buy {
+                    token_id: "5".to_string(),
+                })
+                .unwrap(),
+            }),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Frozen { address } if address == frozen.as_str()));
+    }
+}
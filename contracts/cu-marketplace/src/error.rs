@@ -0,0 +1,34 @@
+use cosmwasm_std::{StdError, Uint128};
+use cw_ownable::OwnershipError;
+use q_utils::asset::AssetError;
+use q_utils::fixed_point::FixedPointError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error("{0}")]
+    Ownership(#[from] OwnershipError),
+    #[error("{0}")]
+    Asset(#[from] AssetError),
+    #[error("{0}")]
+    FixedPoint(#[from] FixedPointError),
+    #[error("Only CUs of the configured collection can be listed")]
+    UnsupportedNft {},
+    #[error("Price must not be zero")]
+    InvalidPrice {},
+    #[error("Royalty rate must be at most 1")]
+    InvalidRoyalty {},
+    #[error("Token {token_id} is not listed")]
+    NotListed { token_id: String },
+    #[error("Only the seller can cancel the listing")]
+    NotSeller {},
+    #[error("Address {address} is frozen")]
+    Frozen { address: String },
+    #[error("Invalid payment: expected {expected}, received {received}")]
+    InvalidPayment {
+        expected: Uint128,
+        received: Uint128,
+    },
+}
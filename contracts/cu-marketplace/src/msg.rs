@@ -0,0 +1,77 @@
+use crate::state::{Listing, Royalty};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+use q_nft::msg::Cw721ReceiveMsg;
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Address of the consumption unit collection
+    pub cu_contract: String,
+    pub royalty: Option<Royalty>,
+    /// Owner allowed to update the royalty, the sender if not set
+    pub owner: Option<String>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Lists a CU sent through `SendNft` of the collection, the embedded message must be a `NftHookMsg`
+    ReceiveNft(Cw721ReceiveMsg),
+
+    /// Buys a listing priced in a CW20 settlement token, the embedded message must be a `TokenHookMsg`
+    Receive(Cw20ReceiveMsg),
+
+    /// Buys a listing priced in a native settlement token sent along as funds,
+    /// the seller must not be frozen
+    Buy {
+        token_id: String,
+    },
+
+    /// Cancels a listing and returns the CU to the seller, can only be called by the seller
+    Cancel {
+        token_id: String,
+    },
+
+    /// Sets or removes (`None`) the royalty paid on sales, can only be called by the owner
+    UpdateConfig {
+        royalty: Option<Royalty>,
+    },
+
+    UpdateOwnership(cw_ownable::Action),
+}
+
+/// Message embedded in `SendNft` of the collection
+#[cw_serde]
+pub enum NftHookMsg {
+    /// Lists the CU at `price` in the settlement token of the collection
+    List { price: Uint128 },
+}
+
+/// Message embedded in CW20 `Send`
+#[cw_serde]
+pub enum TokenHookMsg {
+    Buy { token_id: String },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub cu_contract: Addr,
+    pub royalty: Option<Royalty>,
+}
+
+#[cw_serde]
+pub struct ListingsResponse {
+    pub listings: Vec<Listing>,
+}
+
+/// Position in the listings ordered by price
+#[cw_serde]
+pub struct PriceCursor {
+    pub price: Uint128,
+    pub token_id: String,
+}
+
+#[cw_serde]
+pub enum MigrateMsg {
+    Migrate {},
+}
@@ -0,0 +1,162 @@
+use crate::msg::{ConfigResponse, ListingsResponse, PriceCursor};
+use crate::state::{listings, Listing, CONFIG};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult};
+use cw_storage_plus::Bound;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(crate::msg::ConfigResponse)]
+    Config {},
+
+    #[returns(cw_ownable::Ownership<String>)]
+    Ownership {},
+
+    #[returns(crate::state::Listing)]
+    Listing { token_id: String },
+
+    /// Returns all listings ordered by token id
+    #[returns(crate::msg::ListingsResponse)]
+    Listings {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the listings of the given seller ordered by token id
+    #[returns(crate::msg::ListingsResponse)]
+    ListingsBySeller {
+        seller: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the listings of CUs in the given commitment tier ordered by token id
+    #[returns(crate::msg::ListingsResponse)]
+    ListingsByTier {
+        tier: u16,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns all listings ordered by ascending price
+    #[returns(crate::msg::ListingsResponse)]
+    ListingsByPrice {
+        start_after: Option<PriceCursor>,
+        limit: Option<u32>,
+    },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
+        QueryMsg::Listing { token_id } => {
+            to_json_binary(&listings().load(deps.storage, &token_id)?)
+        }
+        QueryMsg::Listings { start_after, limit } => {
+            to_json_binary(&query_listings(deps, start_after, limit)?)
+        }
+        QueryMsg::ListingsBySeller {
+            seller,
+            start_after,
+            limit,
+        } => to_json_binary(&query_listings_by_seller(deps, seller, start_after, limit)?),
+        QueryMsg::ListingsByTier {
+            tier,
+            start_after,
+            limit,
+        } => to_json_binary(&query_listings_by_tier(deps, tier, start_after, limit)?),
+        QueryMsg::ListingsByPrice { start_after, limit } => {
+            to_json_binary(&query_listings_by_price(deps, start_after, limit)?)
+        }
+    }
+}
+
+fn collect(
+    iter: Box<dyn Iterator<Item = StdResult<(String, Listing)>> + '_>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let listings = iter
+        .take(limit)
+        .map(|item| item.map(|(_, listing)| listing))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ListingsResponse { listings })
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ConfigResponse {
+        cu_contract: config.cu_contract,
+        royalty: config.royalty,
+    })
+}
+
+pub fn query_listings(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let start = start_after.as_deref().map(Bound::exclusive);
+    collect(
+        listings().range(deps.storage, start, None, Order::Ascending),
+        limit,
+    )
+}
+
+pub fn query_listings_by_seller(
+    deps: Deps,
+    seller: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let seller = deps.api.addr_validate(&seller)?;
+    let start = start_after.map(Bound::exclusive);
+    collect(
+        listings()
+            .idx
+            .seller
+            .prefix(seller)
+            .range(deps.storage, start, None, Order::Ascending),
+        limit,
+    )
+}
+
+pub fn query_listings_by_tier(
+    deps: Deps,
+    tier: u16,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let start = start_after.map(Bound::exclusive);
+    collect(
+        listings()
+            .idx
+            .tier
+            .prefix(tier)
+            .range(deps.storage, start, None, Order::Ascending),
+        limit,
+    )
+}
+
+pub fn query_listings_by_price(
+    deps: Deps,
+    start_after: Option<PriceCursor>,
+    limit: Option<u32>,
+) -> StdResult<ListingsResponse> {
+    let start = start_after.map(|cursor| Bound::exclusive((cursor.price.u128(), cursor.token_id)));
+    collect(
+        listings()
+            .idx
+            .price
+            .range(deps.storage, start, None, Order::Ascending),
+        limit,
+    )
+}
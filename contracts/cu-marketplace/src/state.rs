@@ -0,0 +1,63 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Timestamp};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use q_utils::asset::Asset;
+
+#[cw_serde]
+pub struct Config {
+    /// Consumption unit collection traded on this marketplace
+    pub cu_contract: Addr,
+    pub royalty: Option<Royalty>,
+}
+
+/// Share of each sale paid to a recipient
+#[cw_serde]
+pub struct Royalty {
+    pub recipient: Addr,
+    /// Share of the price, at most 1
+    pub rate: Decimal,
+}
+
+/// CU escrowed by the marketplace until it is bought or the listing is cancelled
+#[cw_serde]
+pub struct Listing {
+    pub token_id: String,
+    pub seller: Addr,
+    /// Commitment tier of the CU, which cannot change while it is escrowed
+    pub tier: u16,
+    /// Price in the settlement token of the collection at the time of listing
+    pub price: Asset,
+    pub listed_at: Timestamp,
+}
+
+pub struct ListingIndexes<'a> {
+    pub seller: MultiIndex<'a, Addr, Listing, String>,
+    pub tier: MultiIndex<'a, u16, Listing, String>,
+    pub price: MultiIndex<'a, u128, Listing, String>,
+}
+
+impl IndexList<Listing> for ListingIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Listing>> + '_> {
+        let v: Vec<&dyn Index<Listing>> = vec![&self.seller, &self.tier, &self.price];
+        Box::new(v.into_iter())
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+pub fn listings<'a>() -> IndexedMap<&'a str, Listing, ListingIndexes<'a>> {
+    let indexes = ListingIndexes {
+        seller: MultiIndex::new(
+            |_, listing| listing.seller.clone(),
+            "listings",
+            "listings__seller",
+        ),
+        tier: MultiIndex::new(|_, listing| listing.tier, "listings", "listings__tier"),
+        price: MultiIndex::new(
+            |_, listing| listing.price.amount.u128(),
+            "listings",
+            "listings__price",
+        ),
+    };
+    IndexedMap::new("listings", indexes)
+}
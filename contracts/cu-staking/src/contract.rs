@@ -0,0 +1,854 @@
+use crate::error::ContractError;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, NftHookMsg, TierMultiplier, TokenHookMsg,
+};
+use crate::state::{
+    stakes, Config, Stake, StakerInfo, StakingState, CONFIG, STAKERS, STATE, TIER_MULTIPLIERS,
+};
+use consumption_unit::msg::{ExecuteMsg as CuExecuteMsg, IsFrozenResponse};
+use consumption_unit::query::{query_nft_info_raw, QueryMsg as CuQueryMsg};
+use consumption_unit::types::ConsumptionUnitData;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, Decimal, Decimal256, Deps, DepsMut, Env, Event, MessageInfo,
+    Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use q_nft::msg::{Cw721ReceiveMsg, NftInfoResponse};
+use q_utils::fixed_point::{mul_decimal, mul_decimal256, Rounding};
+
+const CONTRACT_NAME: &str = "gemlabs.io:cu-staking";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let owner = msg.owner.as_deref().unwrap_or(info.sender.as_str());
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(owner))?;
+
+    let config = Config {
+        cu_contract: deps.api.addr_validate(&msg.cu_contract)?,
+        reward_token: deps.api.addr_validate(&msg.reward_token)?,
+        emission_rate: msg.emission_rate,
+        unbonding_period: msg.unbonding_period,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    save_tier_multipliers(deps.storage, msg.tier_multipliers)?;
+    STATE.save(
+        deps.storage,
+        &StakingState {
+            reward_per_weight: Decimal256::zero(),
+            total_weight: Uint128::zero(),
+            available: Uint128::zero(),
+            last_update: env.block.time,
+        },
+    )?;
+
+    Ok(Response::default()
+        .add_attribute("action", "cu-staking::instantiate")
+        .add_event(
+            Event::new("cu-staking::instantiate")
+                .add_attribute("cu_contract", config.cu_contract.to_string())
+                .add_attribute("reward_token", config.reward_token.to_string())
+                .add_attribute("owner", owner),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ReceiveNft(msg) => execute_stake(deps, &env, &info, msg),
+        ExecuteMsg::Receive(wrapper) => execute_fund(deps, &env, &info, wrapper),
+        ExecuteMsg::Unstake { token_id } => execute_unstake(deps, &env, &info, token_id),
+        ExecuteMsg::Withdraw { token_id } => execute_withdraw(deps, &env, &info, token_id),
+        ExecuteMsg::Claim {} => execute_claim(deps, &env, &info),
+        ExecuteMsg::UpdateConfig {
+            emission_rate,
+            unbonding_period,
+            tier_multipliers,
+        } => execute_update_config(
+            deps,
+            &env,
+            &info,
+            emission_rate,
+            unbonding_period,
+            tier_multipliers,
+        ),
+        ExecuteMsg::UpdateOwnership(action) => {
+            let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+            Ok(Response::new().add_attributes(ownership.into_attributes()))
+        }
+    }
+}
+
+fn save_tier_multipliers(
+    storage: &mut dyn Storage,
+    multipliers: Vec<TierMultiplier>,
+) -> Result<(), ContractError> {
+    TIER_MULTIPLIERS.clear(storage);
+    for TierMultiplier { tier, multiplier } in multipliers {
+        if multiplier.is_zero() {
+            return Err(ContractError::InvalidMultiplier {});
+        }
+        TIER_MULTIPLIERS.save(storage, tier, &multiplier)?;
+    }
+    Ok(())
+}
+
+/// Emits the rewards since the last update to the bonded CUs, capped by the funded tokens.
+/// Nothing is emitted while no CU is bonded. Only the rewards credited to the weight leave
+/// `available`, the rounding remainder is emitted later.
+pub fn update_state(storage: &dyn Storage, env: &Env) -> Result<StakingState, ContractError> {
+    let config = CONFIG.load(storage)?;
+    let mut state = STATE.load(storage)?;
+    if !state.total_weight.is_zero() && env.block.time > state.last_update {
+        let elapsed = env.block.time.seconds() - state.last_update.seconds();
+        let emitted = config
+            .emission_rate
+            .checked_mul(Uint128::from(elapsed))?
+            .min(state.available);
+        let increment = Decimal256::checked_from_ratio(emitted, state.total_weight)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        let credited = mul_decimal256(state.total_weight, increment, Rounding::Down)?;
+        state.reward_per_weight = state.reward_per_weight.checked_add(increment)?;
+        state.available = state.available.checked_sub(credited)?;
+    }
+    state.last_update = env.block.time;
+    Ok(state)
+}
+
+/// Accrues the rewards emitted since the last checkpoint of the staker
+pub fn accrue(state: &StakingState, staker: &mut StakerInfo) -> Result<(), ContractError> {
+    let accrued = mul_decimal256(
+        staker.weight,
+        state.reward_per_weight - staker.reward_per_weight,
+        Rounding::Down,
+    )?;
+    staker.pending = staker.pending.checked_add(accrued)?;
+    staker.reward_per_weight = state.reward_per_weight;
+    Ok(())
+}
+
+/// Removes the weight of a bonded CU from its staker and the total, accruing its rewards first
+fn unbond(
+    storage: &mut dyn Storage,
+    state: &mut StakingState,
+    stake: &Stake,
+) -> Result<(), ContractError> {
+    let mut staker_info = STAKERS.load(storage, &stake.staker)?;
+    accrue(state, &mut staker_info)?;
+    staker_info.weight = staker_info.weight.checked_sub(stake.weight)?;
+    state.total_weight = state.total_weight.checked_sub(stake.weight)?;
+    STAKERS.save(storage, &stake.staker, &staker_info)?;
+    Ok(())
+}
+
+/// Whether the staked CU is still held by this contract. It can be burned while staked
+/// (e.g. when swept after expiry) and its token id minted again to another owner.
+fn cu_held(deps: Deps, env: &Env, config: &Config, token_id: &str) -> StdResult<bool> {
+    let nft_info = query_nft_info_raw(&deps.querier, &config.cu_contract, token_id)?;
+    Ok(nft_info.is_some_and(|nft_info| nft_info.owner == env.contract.address))
+}
+
+/// Whether the collection froze `address`, frozen stakers may not claim
+fn is_frozen(deps: Deps, config: &Config, address: &Addr) -> StdResult<bool> {
+    let response: IsFrozenResponse = deps.querier.query_wasm_smart(
+        &config.cu_contract,
+        &CuQueryMsg::IsFrozen {
+            address: address.to_string(),
+        },
+    )?;
+    Ok(response.frozen)
+}
+
+/// Removes the stakes of `staker` whose CU was burned or minted again, returns their
+/// token ids
+fn remove_burned_stakes(
+    deps: DepsMut,
+    env: &Env,
+    state: &mut StakingState,
+    staker: &Addr,
+) -> Result<Vec<String>, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let staked = stakes()
+        .idx
+        .staker
+        .prefix(staker.clone())
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, stake)| stake))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut burned = vec![];
+    for stake in staked {
+        if cu_held(deps.as_ref(), env, &config, &stake.token_id)? {
+            continue;
+        }
+        if stake.unbonded_at.is_none() {
+            unbond(deps.storage, state, &stake)?;
+        }
+        stakes().remove(deps.storage, &stake.token_id)?;
+        burned.push(stake.token_id);
+    }
+    Ok(burned)
+}
+
+fn load_stake(storage: &dyn Storage, token_id: &str) -> Result<Stake, ContractError> {
+    stakes()
+        .may_load(storage, token_id)?
+        .ok_or(ContractError::NotStaked {
+            token_id: token_id.to_string(),
+        })
+}
+
+/// Stakes a CU escrowed through `SendNft` of the collection. While escrowed the CU
+/// can neither be transferred nor redeemed by its owner.
+fn execute_stake(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.cu_contract {
+        return Err(ContractError::UnsupportedNft {});
+    }
+    let NftHookMsg::Stake {} = from_json(&msg.msg)?;
+    let staker = deps.api.addr_validate(&msg.sender)?;
+
+    let nft_info: NftInfoResponse<ConsumptionUnitData> = deps.querier.query_wasm_smart(
+        &config.cu_contract,
+        &CuQueryMsg::NftInfo {
+            token_id: msg.token_id.clone(),
+        },
+    )?;
+    let tier = nft_info.extension.commitment_tier;
+    let multiplier = TIER_MULTIPLIERS
+        .may_load(deps.storage, tier)?
+        .unwrap_or(Decimal::one());
+    let weight = mul_decimal(
+        nft_info.extension.consumption_value,
+        multiplier,
+        Rounding::Down,
+    )?;
+
+    let mut state = update_state(deps.storage, env)?;
+    let mut staker_info = STAKERS.may_load(deps.storage, &staker)?.unwrap_or_default();
+    accrue(&state, &mut staker_info)?;
+    staker_info.weight = staker_info.weight.checked_add(weight)?;
+    state.total_weight = state.total_weight.checked_add(weight)?;
+    STAKERS.save(deps.storage, &staker, &staker_info)?;
+    STATE.save(deps.storage, &state)?;
+
+    let stake = Stake {
+        token_id: msg.token_id,
+        staker,
+        tier,
+        weight,
+        staked_at: env.block.time,
+        unbonded_at: None,
+    };
+    stakes().save(deps.storage, &stake.token_id, &stake)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-staking::stake")
+        .add_event(
+            Event::new("cu-staking::stake")
+                .add_attribute("token_id", stake.token_id)
+                .add_attribute("staker", stake.staker.to_string())
+                .add_attribute("weight", weight.to_string()),
+        ))
+}
+
+/// Adds reward tokens sent through the CW20 `Send` hook to the emissions
+fn execute_fund(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.reward_token {
+        return Err(ContractError::UnsupportedToken {});
+    }
+    let TokenHookMsg::Fund {} = from_json(&wrapper.msg)?;
+
+    let mut state = update_state(deps.storage, env)?;
+    state.available = state.available.checked_add(wrapper.amount)?;
+    STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-staking::fund")
+        .add_event(
+            Event::new("cu-staking::fund")
+                .add_attribute("sender", wrapper.sender)
+                .add_attribute("amount", wrapper.amount.to_string()),
+        ))
+}
+
+fn execute_unstake(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let mut stake = load_stake(deps.storage, &token_id)?;
+    if stake.staker != info.sender {
+        return Err(ContractError::NotStaker {});
+    }
+    if stake.unbonded_at.is_some() {
+        return Err(ContractError::AlreadyUnbonding { token_id });
+    }
+
+    let mut state = update_state(deps.storage, env)?;
+    unbond(deps.storage, &mut state, &stake)?;
+    STATE.save(deps.storage, &state)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let unbonded_at = env.block.time.plus_seconds(config.unbonding_period);
+    stake.unbonded_at = Some(unbonded_at);
+    stakes().save(deps.storage, &token_id, &stake)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-staking::unstake")
+        .add_event(
+            Event::new("cu-staking::unstake")
+                .add_attribute("token_id", token_id)
+                .add_attribute("staker", stake.staker.to_string())
+                .add_attribute("unbonded_at", unbonded_at.to_string()),
+        ))
+}
+
+/// Returns an unbonded CU to its staker. The stake of a burned CU, or of a token id
+/// minted again to another owner, is removed right away.
+fn execute_withdraw(
+    deps: DepsMut,
+    env: &Env,
+    _info: &MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let stake = load_stake(deps.storage, &token_id)?;
+    let config = CONFIG.load(deps.storage)?;
+    if !cu_held(deps.as_ref(), env, &config, &token_id)? {
+        if stake.unbonded_at.is_none() {
+            let mut state = update_state(deps.storage, env)?;
+            unbond(deps.storage, &mut state, &stake)?;
+            STATE.save(deps.storage, &state)?;
+        }
+        stakes().remove(deps.storage, &token_id)?;
+        return Ok(Response::new()
+            .add_attribute("action", "cu-staking::withdraw")
+            .add_event(
+                Event::new("cu-staking::withdraw")
+                    .add_attribute("token_id", token_id)
+                    .add_attribute("staker", stake.staker.to_string())
+                    .add_attribute("burned", "true"),
+            ));
+    }
+
+    match stake.unbonded_at {
+        Some(unbonded_at) if env.block.time >= unbonded_at => {}
+        Some(unbonded_at) => {
+            return Err(ContractError::StillBonded {
+                token_id,
+                remaining: unbonded_at.seconds() - env.block.time.seconds(),
+            })
+        }
+        None => return Err(ContractError::NotUnstaked { token_id }),
+    }
+    stakes().remove(deps.storage, &token_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-staking::withdraw")
+        .add_message(WasmMsg::Execute {
+            contract_addr: config.cu_contract.to_string(),
+            msg: to_json_binary(&CuExecuteMsg::TransferNft {
+                recipient: stake.staker.to_string(),
+                token_id: token_id.clone(),
+            })?,
+            funds: vec![],
+        })
+        .add_event(
+            Event::new("cu-staking::withdraw")
+                .add_attribute("token_id", token_id)
+                .add_attribute("staker", stake.staker.to_string()),
+        ))
+}
+
+/// Sends the accrued rewards, the stakes of burned or re-minted CUs of the sender stop earning
+fn execute_claim(
+    mut deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if is_frozen(deps.as_ref(), &config, &info.sender)? {
+        return Err(ContractError::Frozen {
+            address: info.sender.to_string(),
+        });
+    }
+    let mut state = update_state(deps.storage, env)?;
+    let burned = remove_burned_stakes(deps.branch(), env, &mut state, &info.sender)?;
+    let mut staker_info = STAKERS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    accrue(&state, &mut staker_info)?;
+
+    let claimed = staker_info.pending;
+    if claimed.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    staker_info.pending = Uint128::zero();
+    STAKERS.save(deps.storage, &info.sender, &staker_info)?;
+    STATE.save(deps.storage, &state)?;
+
+    let mut event = Event::new("cu-staking::claim")
+        .add_attribute("staker", info.sender.to_string())
+        .add_attribute("amount", claimed.to_string());
+    if !burned.is_empty() {
+        event = event.add_attribute("burned", burned.join(","));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-staking::claim")
+        .add_message(WasmMsg::Execute {
+            contract_addr: config.reward_token.to_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: claimed,
+            })?,
+            funds: vec![],
+        })
+        .add_event(event))
+}
+
+fn execute_update_config(
+    deps: DepsMut,
+    env: &Env,
+    info: &MessageInfo,
+    emission_rate: Option<Uint128>,
+    unbonding_period: Option<u64>,
+    tier_multipliers: Option<Vec<TierMultiplier>>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    // rewards emitted so far use the previous rate
+    let state = update_state(deps.storage, env)?;
+    STATE.save(deps.storage, &state)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    if let Some(emission_rate) = emission_rate {
+        config.emission_rate = emission_rate;
+    }
+    if let Some(unbonding_period) = unbonding_period {
+        config.unbonding_period = unbonding_period;
+    }
+    CONFIG.save(deps.storage, &config)?;
+    if let Some(tier_multipliers) = tier_multipliers {
+        save_tier_multipliers(deps.storage, tier_multipliers)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "cu-staking::update_config")
+        .add_event(
+            Event::new("cu-staking::update_config")
+                .add_attribute("emission_rate", config.emission_rate.to_string())
+                .add_attribute("unbonding_period", config.unbonding_period.to_string()),
+        ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    match msg {
+        MigrateMsg::Migrate {} => Ok(Response::new()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::query_staker;
+    use consumption_unit::query::nft_info_key;
+    use consumption_unit::types::ConsumptionUnitState;
+    use cosmwasm_std::testing::{
+        message_info, mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{Addr, Binary, ContractResult, Empty, OwnedDeps, SystemResult, WasmQuery};
+    use q_nft::state::NftInfo;
+
+    type MockDeps = OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>;
+
+    /// Mocks a collection where CU `n` has a `consumption_value` of 100 in tier `n` and
+    /// is held by the staking contract, the `burned` CUs do not exist, the `reminted` ones
+    /// are held by another address and the address `frozen` is frozen
+    fn mock_collection(deps: &mut MockDeps, burned: &[&str], reminted: &[&str]) {
+        let env = mock_env();
+        let frozen = deps.api.addr_make("frozen");
+        let other = deps.api.addr_make("other");
+        let burned = burned.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let reminted = reminted.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let cu_data = move |token_id: &str| ConsumptionUnitData {
+            consumption_value: Uint128::new(100),
+            nominal_quantity: Uint128::new(100),
+            nominal_currency: "usd".to_string(),
+            commitment_tier: token_id.parse().unwrap(),
+            state: ConsumptionUnitState::Reflected,
+            floor_price: Decimal::percent(50),
+            hashes: vec![],
+            created_at: env.block.time,
+            updated_at: env.block.time,
+        };
+        deps.querier.update_wasm(move |query| {
+            let msg = match query {
+                // the CU is read from the storage of the collection, empty if burned
+                WasmQuery::Raw { key, .. } => {
+                    let prefix = nft_info_key("");
+                    let token_id = std::str::from_utf8(&key[prefix.len()..]).unwrap();
+                    let owner = if reminted.iter().any(|id| id == token_id) {
+                        other.clone()
+                    } else {
+                        env.contract.address.clone()
+                    };
+                    let value = match burned.iter().any(|id| id == token_id) {
+                        true => Binary::default(),
+                        false => to_json_binary(&NftInfo {
+                            owner,
+                            extension: cu_data(token_id),
+                        })
+                        .unwrap(),
+                    };
+                    return SystemResult::Ok(ContractResult::Ok(value));
+                }
+                WasmQuery::Smart { msg, .. } => msg,
+                _ => panic!("unexpected query {query:?}"),
+            };
+            let response = match from_json(msg).unwrap() {
+                CuQueryMsg::NftInfo { token_id } => to_json_binary(&NftInfoResponse {
+                    extension: cu_data(&token_id),
+                }),
+                CuQueryMsg::IsFrozen { address } => to_json_binary(&IsFrozenResponse {
+                    frozen: address == frozen.as_str(),
+                    address: Addr::unchecked(address),
+                    info: None,
+                }),
+                msg => panic!("unexpected query {msg:?}"),
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        });
+    }
+
+    /// Instantiates the staking contract for a mocked collection
+    fn setup() -> (MockDeps, Addr, Addr) {
+        let mut deps = mock_dependencies();
+        let cu_contract = deps.api.addr_make("cu_contract");
+        let reward_token = deps.api.addr_make("reward_token");
+        let owner = deps.api.addr_make("owner");
+        mock_collection(&mut deps, &[], &[]);
+
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&owner, &[]),
+            InstantiateMsg {
+                cu_contract: cu_contract.to_string(),
+                reward_token: reward_token.to_string(),
+                emission_rate: Uint128::new(10),
+                unbonding_period: 100,
+                tier_multipliers: vec![TierMultiplier {
+                    tier: 2,
+                    multiplier: Decimal::percent(200),
+                }],
+                owner: None,
+            },
+        )
+        .unwrap();
+        (deps, cu_contract, reward_token)
+    }
+
+    fn stake(
+        deps: &mut MockDeps,
+        sender: &Addr,
+        staker: &Addr,
+        token_id: &str,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(sender, &[]),
+            ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+                sender: staker.to_string(),
+                token_id: token_id.to_string(),
+                msg: to_json_binary(&NftHookMsg::Stake {}).unwrap(),
+            }),
+        )
+    }
+
+    fn pending(deps: &MockDeps, env: &Env, staker: &Addr) -> u128 {
+        query_staker(deps.as_ref(), env, staker.to_string())
+            .unwrap()
+            .pending
+            .u128()
+    }
+
+    #[test]
+    fn test_staking_emissions() {
+        let api = MockApi::default();
+        let alice = api.addr_make("alice");
+        let bob = api.addr_make("bob");
+        let (mut deps, cu_contract, reward_token) = setup();
+
+        let fund_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: alice.to_string(),
+            amount: Uint128::new(1_000),
+            msg: to_json_binary(&TokenHookMsg::Fund {}).unwrap(),
+        });
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&alice, &[]),
+            fund_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::UnsupportedToken {}));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(&reward_token, &[]),
+            fund_msg,
+        )
+        .unwrap();
+
+        let err = stake(&mut deps, &alice, &alice, "1").unwrap_err();
+        assert!(matches!(err, ContractError::UnsupportedNft {}));
+        stake(&mut deps, &cu_contract, &alice, "1").unwrap();
+        // tier 2 doubles the weight
+        stake(&mut deps, &cu_contract, &bob, "2").unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(30);
+        assert_eq!(pending(&deps, &env, &alice), 100);
+        assert_eq!(pending(&deps, &env, &bob), 200);
+
+        let unstake_msg = ExecuteMsg::Unstake {
+            token_id: "1".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&bob, &[]),
+            unstake_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::NotStaker {}));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&alice, &[]),
+            unstake_msg,
+        )
+        .unwrap();
+
+        // unbonding CUs earn nothing
+        env.block.time = env.block.time.plus_seconds(30);
+        assert_eq!(pending(&deps, &env, &alice), 100);
+        assert_eq!(pending(&deps, &env, &bob), 500);
+
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            token_id: "1".to_string(),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&bob, &[]),
+            withdraw_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ContractError::StillBonded { remaining: 70, .. }
+        ));
+        env.block.time = env.block.time.plus_seconds(70);
+        let response = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&bob, &[]),
+            withdraw_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            response.messages[0].msg,
+            WasmMsg::Execute {
+                contract_addr: cu_contract.to_string(),
+                msg: to_json_binary(&CuExecuteMsg::TransferNft {
+                    recipient: alice.to_string(),
+                    token_id: "1".to_string(),
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+
+        // emissions stop once the funded tokens are used up
+        env.block.time = env.block.time.plus_seconds(1_000);
+        let response = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&bob, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        assert_eq!(
+            response.messages[0].msg,
+            WasmMsg::Execute {
+                contract_addr: reward_token.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: bob.to_string(),
+                    amount: Uint128::new(900),
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+        assert_eq!(
+            STATE.load(&deps.storage).unwrap().available,
+            Uint128::zero()
+        );
+    }
+
+    fn fund(deps: &mut MockDeps, reward_token: &Addr, amount: u128) {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            message_info(reward_token, &[]),
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: reward_token.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_json_binary(&TokenHookMsg::Fund {}).unwrap(),
+            }),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_emission_remainder() {
+        let api = MockApi::default();
+        let alice = api.addr_make("alice");
+        let (mut deps, cu_contract, reward_token) = setup();
+        fund(&mut deps, &reward_token, 1_000);
+        // 10 tokens per second over a weight of 300 leave a rounding remainder
+        stake(&mut deps, &cu_contract, &alice, "1").unwrap();
+        stake(&mut deps, &cu_contract, &alice, "2").unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1);
+
+        // only the credited rewards leave the funds
+        let state = update_state(&deps.storage, &env).unwrap();
+        let credited = pending(&deps, &env, &alice);
+        assert_eq!(credited, 9);
+        assert_eq!(state.available, Uint128::new(1_000 - credited));
+    }
+
+    #[test]
+    fn test_burned_stakes() {
+        let api = MockApi::default();
+        let alice = api.addr_make("alice");
+        let bob = api.addr_make("bob");
+        let (mut deps, cu_contract, reward_token) = setup();
+        fund(&mut deps, &reward_token, 1_000);
+        stake(&mut deps, &cu_contract, &alice, "1").unwrap();
+        stake(&mut deps, &cu_contract, &bob, "2").unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(30);
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            token_id: "1".to_string(),
+        };
+
+        // a failing collection is not mistaken for a burned CU
+        deps.querier
+            .update_wasm(|_| SystemResult::Ok(ContractResult::Err("query failed".to_string())));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&bob, &[]),
+            withdraw_msg.clone(),
+        )
+        .unwrap_err();
+        assert!(stakes().has(&deps.storage, "1"));
+
+        // the stake of a burned CU can be withdrawn without unbonding, nothing is returned
+        mock_collection(&mut deps, &["1"], &["2"]);
+        let response = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&bob, &[]),
+            withdraw_msg,
+        )
+        .unwrap();
+        assert!(response.messages.is_empty());
+        assert!(stakes().may_load(&deps.storage, "1").unwrap().is_none());
+
+        env.block.time = env.block.time.plus_seconds(30);
+        assert_eq!(pending(&deps, &env, &alice), 100);
+        assert_eq!(pending(&deps, &env, &bob), 500);
+
+        // claiming drops the stakes of CUs burned or minted again to another owner
+        let response = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&bob, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap();
+        assert_eq!(
+            response.messages[0].msg,
+            WasmMsg::Execute {
+                contract_addr: reward_token.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: bob.to_string(),
+                    amount: Uint128::new(500),
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+        assert!(stakes().may_load(&deps.storage, "2").unwrap().is_none());
+        let state = STATE.load(&deps.storage).unwrap();
+        assert!(state.total_weight.is_zero());
+        assert_eq!(state.available, Uint128::new(400));
+    }
+
+    #[test]
+    fn test_frozen_staker() {
+        let frozen = MockApi::default().addr_make("frozen");
+        let (mut deps, cu_contract, reward_token) = setup();
+        fund(&mut deps, &reward_token, 1_000);
+        stake(&mut deps, &cu_contract, &frozen, "1").unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(10);
+        let err = execute(
+            deps.as_mut(),
+            env,
+            message_info(&frozen, &[]),
+            ExecuteMsg::Claim {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::Frozen { address } if address == frozen.as_str()));
+    }
+}
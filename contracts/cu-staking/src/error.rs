@@ -0,0 +1,36 @@
+use cosmwasm_std::{OverflowError, StdError};
+use cw_ownable::OwnershipError;
+use q_utils::fixed_point::FixedPointError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+    #[error("{0}")]
+    Ownership(#[from] OwnershipError),
+    #[error("{0}")]
+    FixedPoint(#[from] FixedPointError),
+    #[error("Only CUs of the configured collection can be staked")]
+    UnsupportedNft {},
+    #[error("Only the configured reward token is accepted")]
+    UnsupportedToken {},
+    #[error("Tier multipliers must not be zero")]
+    InvalidMultiplier {},
+    #[error("Token {token_id} is not staked")]
+    NotStaked { token_id: String },
+    #[error("Only the staker can unstake the CU")]
+    NotStaker {},
+    #[error("Token {token_id} is already unbonding")]
+    AlreadyUnbonding { token_id: String },
+    #[error("Token {token_id} must be unstaked before it can be withdrawn")]
+    NotUnstaked { token_id: String },
+    #[error("Token {token_id} is still bonded, {remaining} seconds remaining")]
+    StillBonded { token_id: String, remaining: u64 },
+    #[error("Address {address} is frozen")]
+    Frozen { address: String },
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+}
@@ -0,0 +1,100 @@
+use crate::state::Stake;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+use q_nft::msg::Cw721ReceiveMsg;
+
+#[cw_serde]
+pub struct TierMultiplier {
+    pub tier: u16,
+    pub multiplier: Decimal,
+}
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// Address of the consumption unit collection
+    pub cu_contract: String,
+    /// Address of the CW20 reward token
+    pub reward_token: String,
+    pub emission_rate: Uint128,
+    pub unbonding_period: u64,
+    pub tier_multipliers: Vec<TierMultiplier>,
+    /// Owner allowed to update the config, the sender if not set
+    pub owner: Option<String>,
+}
+
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Stakes a CU sent through `SendNft` of the collection, the embedded message must be a `NftHookMsg`
+    ReceiveNft(Cw721ReceiveMsg),
+
+    /// Funds emissions with reward tokens, the embedded message must be a `TokenHookMsg`
+    Receive(Cw20ReceiveMsg),
+
+    /// Stops the rewards of a staked CU and starts its unbonding period,
+    /// can only be called by the staker
+    Unstake {
+        token_id: String,
+    },
+
+    /// Returns an unbonded CU to the staker, can be called by anyone.
+    /// The stake of a CU burned in the collection is removed without unbonding.
+    Withdraw {
+        token_id: String,
+    },
+
+    /// Sends the rewards accrued by the CUs of the sender and removes the stakes of its
+    /// burned CUs
+    Claim {},
+
+    /// Updates the config, can only be called by the owner. Fields set to `None` are left unchanged.
+    /// New tier multipliers only apply to CUs staked afterwards.
+    UpdateConfig {
+        emission_rate: Option<Uint128>,
+        unbonding_period: Option<u64>,
+        tier_multipliers: Option<Vec<TierMultiplier>>,
+    },
+
+    UpdateOwnership(cw_ownable::Action),
+}
+
+/// Message embedded in `SendNft` of the collection
+#[cw_serde]
+pub enum NftHookMsg {
+    Stake {},
+}
+
+/// Message embedded in CW20 `Send`
+#[cw_serde]
+pub enum TokenHookMsg {
+    /// Adds the tokens to the emissions
+    Fund {},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub cu_contract: Addr,
+    pub reward_token: Addr,
+    pub emission_rate: Uint128,
+    pub unbonding_period: u64,
+    pub tier_multipliers: Vec<TierMultiplier>,
+}
+
+#[cw_serde]
+pub struct StakesResponse {
+    pub stakes: Vec<Stake>,
+}
+
+#[cw_serde]
+pub struct StakerResponse {
+    pub staker: Addr,
+    /// Sum of the weight of the bonded CUs of the staker
+    pub weight: Uint128,
+    /// Rewards accrued and not claimed yet
+    pub pending: Uint128,
+}
+
+#[cw_serde]
+pub enum MigrateMsg {
+    Migrate {},
+}
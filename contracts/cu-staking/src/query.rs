@@ -0,0 +1,110 @@
+use crate::contract::{accrue, update_state};
+use crate::msg::{ConfigResponse, StakerResponse, StakesResponse, TierMultiplier};
+use crate::state::{stakes, StakingState, CONFIG, STAKERS, TIER_MULTIPLIERS};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdError, StdResult};
+use cw_storage_plus::Bound;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 100;
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    #[returns(crate::msg::ConfigResponse)]
+    Config {},
+
+    #[returns(cw_ownable::Ownership<String>)]
+    Ownership {},
+
+    /// Returns the emissions accumulator as of the current block
+    #[returns(crate::state::StakingState)]
+    State {},
+
+    #[returns(crate::state::Stake)]
+    Stake { token_id: String },
+
+    /// Returns the CUs staked by the given staker ordered by token id
+    #[returns(crate::msg::StakesResponse)]
+    Stakes {
+        staker: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the weight and the pending rewards of the given staker
+    #[returns(crate::msg::StakerResponse)]
+    Staker { staker: String },
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
+        QueryMsg::State {} => to_json_binary(&query_state(deps, &env)?),
+        QueryMsg::Stake { token_id } => to_json_binary(&stakes().load(deps.storage, &token_id)?),
+        QueryMsg::Stakes {
+            staker,
+            start_after,
+            limit,
+        } => to_json_binary(&query_stakes(deps, staker, start_after, limit)?),
+        QueryMsg::Staker { staker } => to_json_binary(&query_staker(deps, &env, staker)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let tier_multipliers = TIER_MULTIPLIERS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(tier, multiplier)| TierMultiplier { tier, multiplier }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ConfigResponse {
+        cu_contract: config.cu_contract,
+        reward_token: config.reward_token,
+        emission_rate: config.emission_rate,
+        unbonding_period: config.unbonding_period,
+        tier_multipliers,
+    })
+}
+
+pub fn query_state(deps: Deps, env: &Env) -> StdResult<StakingState> {
+    update_state(deps.storage, env).map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+pub fn query_stakes(
+    deps: Deps,
+    staker: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<StakesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let staker = deps.api.addr_validate(&staker)?;
+    let start = start_after.map(Bound::exclusive);
+
+    let stakes = stakes()
+        .idx
+        .staker
+        .prefix(staker)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, stake)| stake))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(StakesResponse { stakes })
+}
+
+pub fn query_staker(deps: Deps, env: &Env, staker: String) -> StdResult<StakerResponse> {
+    let staker = deps.api.addr_validate(&staker)?;
+    let state = query_state(deps, env)?;
+    let mut staker_info = STAKERS.may_load(deps.storage, &staker)?.unwrap_or_default();
+    accrue(&state, &mut staker_info).map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    Ok(StakerResponse {
+        staker,
+        weight: staker_info.weight,
+        pending: staker_info.pending,
+    })
+}
@@ -0,0 +1,76 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, Decimal256, Timestamp, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+
+#[cw_serde]
+pub struct Config {
+    /// Consumption unit collection staked on this contract
+    pub cu_contract: Addr,
+    /// CW20 token emitted to stakers
+    pub reward_token: Addr,
+    /// Reward tokens emitted per second to all stakers, as long as funded
+    pub emission_rate: Uint128,
+    /// Seconds between unstaking and withdrawing a CU
+    pub unbonding_period: u64,
+}
+
+/// Global emissions accumulator
+#[cw_serde]
+pub struct StakingState {
+    /// Rewards emitted per unit of weight since the start
+    pub reward_per_weight: Decimal256,
+    /// Sum of the weight of all bonded CUs
+    pub total_weight: Uint128,
+    /// Funded reward tokens not emitted yet
+    pub available: Uint128,
+    pub last_update: Timestamp,
+}
+
+/// Reward checkpoint of a staker
+#[cw_serde]
+#[derive(Default)]
+pub struct StakerInfo {
+    /// Sum of the weight of the bonded CUs of the staker
+    pub weight: Uint128,
+    /// Global `reward_per_weight` at the last update of the staker
+    pub reward_per_weight: Decimal256,
+    /// Rewards accrued until the last update and not claimed yet
+    pub pending: Uint128,
+}
+
+/// CU escrowed by the contract until it is withdrawn after unbonding
+#[cw_serde]
+pub struct Stake {
+    pub token_id: String,
+    pub staker: Addr,
+    pub tier: u16,
+    /// `consumption_value` times the multiplier of the tier at the time of staking
+    pub weight: Uint128,
+    pub staked_at: Timestamp,
+    /// End of the unbonding period once unstaked, the CU earns no rewards while unbonding
+    pub unbonded_at: Option<Timestamp>,
+}
+
+pub struct StakeIndexes<'a> {
+    pub staker: MultiIndex<'a, Addr, Stake, String>,
+}
+
+impl IndexList<Stake> for StakeIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Stake>> + '_> {
+        let v: Vec<&dyn Index<Stake>> = vec![&self.staker];
+        Box::new(v.into_iter())
+    }
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const STATE: Item<StakingState> = Item::new("state");
+/// Weight multipliers per commitment tier, 1 if not set
+pub const TIER_MULTIPLIERS: Map<u16, Decimal> = Map::new("tier_multipliers");
+pub const STAKERS: Map<&Addr, StakerInfo> = Map::new("stakers");
+
+pub fn stakes<'a>() -> IndexedMap<&'a str, Stake, StakeIndexes<'a>> {
+    let indexes = StakeIndexes {
+        staker: MultiIndex::new(|_, stake| stake.staker.clone(), "stakes", "stakes__staker"),
+    };
+    IndexedMap::new("stakes", indexes)
+}
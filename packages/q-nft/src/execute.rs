@@ -1,7 +1,12 @@
 use crate::error::Cw721ContractError;
-use crate::state::{CREATOR, MINTER};
-use cosmwasm_std::{Addr, Api, StdResult, Storage};
-use cw_ownable::Ownership;
+use crate::state::{
+    Cw721Config, NftInfo, BALANCE_SNAPSHOTS, CREATOR, MINTER, OWNER_SNAPSHOTS, SNAPSHOTS_ENABLED,
+};
+use crate::traits::Cw721State;
+use cosmwasm_std::{
+    Addr, Api, Empty, Env, Order, OverflowError, OverflowOperation, StdError, StdResult, Storage,
+};
+use cw_ownable::{Ownership, OwnershipError};
 
 pub fn assert_minter(storage: &dyn Storage, sender: &Addr) -> Result<(), Cw721ContractError> {
     if MINTER.assert_owner(storage, sender).is_err() {
@@ -17,6 +22,64 @@ pub fn assert_creator(storage: &dyn Storage, sender: &Addr) -> Result<(), Cw721C
     Ok(())
 }
 
+/// Transfers the token to `recipient`, the sender must be its owner.
+/// Returns the updated token.
+pub fn transfer_nft<TNftExtension>(
+    storage: &mut dyn Storage,
+    env: &Env,
+    sender: &Addr,
+    recipient: &Addr,
+    token_id: &str,
+) -> Result<NftInfo<TNftExtension>, Cw721ContractError>
+where
+    TNftExtension: Cw721State,
+{
+    let config = Cw721Config::<TNftExtension, Empty>::default();
+    let mut token = config.nft_info.load(storage, token_id)?;
+    if token.owner != sender {
+        return Err(Cw721ContractError::Ownership(OwnershipError::NotOwner));
+    }
+    token.owner = recipient.clone();
+    config.nft_info.save(storage, token_id, &token)?;
+    snapshot_ownership(storage, env, token_id, Some(sender), Some(recipient))?;
+    Ok(token)
+}
+
+/// Records a change of ownership of the token at the current height if snapshots are enabled.
+/// `previous` is `None` when the token is minted, `owner` is `None` when it is burned.
+pub fn snapshot_ownership(
+    storage: &mut dyn Storage,
+    env: &Env,
+    token_id: &str,
+    previous: Option<&Addr>,
+    owner: Option<&Addr>,
+) -> StdResult<()> {
+    if !SNAPSHOTS_ENABLED.may_load(storage)?.unwrap_or_default() {
+        return Ok(());
+    }
+    let height = env.block.height;
+    if let Some(previous) = previous {
+        let balance = BALANCE_SNAPSHOTS
+            .may_load(storage, previous)?
+            .unwrap_or_default()
+            .checked_sub(1)
+            .ok_or_else(|| StdError::overflow(OverflowError::new(OverflowOperation::Sub)))?;
+        BALANCE_SNAPSHOTS.save(storage, previous, &balance, height)?;
+    }
+    match owner {
+        Some(owner) => {
+            let balance = BALANCE_SNAPSHOTS
+                .may_load(storage, owner)?
+                .unwrap_or_default()
+                .checked_add(1)
+                .ok_or_else(|| StdError::overflow(OverflowError::new(OverflowOperation::Add)))?;
+            BALANCE_SNAPSHOTS.save(storage, owner, &balance, height)?;
+            OWNER_SNAPSHOTS.save(storage, token_id, owner, height)
+        }
+        None => OWNER_SNAPSHOTS.remove(storage, token_id, height),
+    }
+}
+
 // ------- helper cw721 functions -------
 pub fn initialize_creator(
     storage: &mut dyn Storage,
@@ -26,6 +89,32 @@ pub fn initialize_creator(
     CREATOR.initialize_owner(storage, api, creator)
 }
 
+pub fn initialize_snapshots(storage: &mut dyn Storage, enabled: bool) -> StdResult<()> {
+    SNAPSHOTS_ENABLED.save(storage, &enabled)
+}
+
+/// Enables snapshots of an existing collection, recording the owners of all tokens
+/// at the current height. Returns the number of tokens recorded, none if already enabled.
+pub fn enable_snapshots<TNftExtension>(storage: &mut dyn Storage, env: &Env) -> StdResult<usize>
+where
+    TNftExtension: Cw721State,
+{
+    if SNAPSHOTS_ENABLED.may_load(storage)?.unwrap_or_default() {
+        return Ok(0);
+    }
+    SNAPSHOTS_ENABLED.save(storage, &true)?;
+
+    let owners = Cw721Config::<TNftExtension, Empty>::default()
+        .nft_info
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(token_id, token)| (token_id, token.owner)))
+        .collect::<StdResult<Vec<_>>>()?;
+    for (token_id, owner) in &owners {
+        snapshot_ownership(storage, env, token_id, None, Some(owner))?;
+    }
+    Ok(owners.len())
+}
+
 pub fn initialize_minter(
     storage: &mut dyn Storage,
     api: &dyn Api,
@@ -1,5 +1,6 @@
 use crate::state::CollectionInfo;
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{to_json_binary, Binary, CosmosMsg, StdResult, WasmMsg};
 
 #[cw_serde]
 pub struct Cw721InstantiateMsg<TCollectionExtensionMsg> {
@@ -17,6 +18,11 @@ pub struct Cw721InstantiateMsg<TCollectionExtensionMsg> {
 
     /// Sets the creator of collection. The creator is the only one eligible to update `CollectionInfo`.
     pub creator: Option<String>,
+
+    /// Records token ownership and owner balances per block height,
+    /// enables the `OwnerOfAtHeight` and `BalanceAtHeight` queries
+    #[serde(default)]
+    pub snapshots: bool,
 }
 
 /// This is a wrapper around CollectionInfo that includes the extension, contract info, and number of tokens (supply).
@@ -32,6 +38,12 @@ pub struct OwnerOfResponse {
     pub owner: String,
 }
 
+#[cw_serde]
+pub struct BalanceResponse {
+    /// Number of tokens held by the owner
+    pub count: u64,
+}
+
 #[cw_serde]
 pub struct NumTokensResponse {
     pub count: u64,
@@ -50,3 +62,30 @@ pub struct TokensResponse {
     /// to achieve pagination.
     pub tokens: Vec<String>,
 }
+
+/// Message sent to the receiving contract of `SendNft`
+#[cw_serde]
+pub struct Cw721ReceiveMsg {
+    /// Previous owner of the token
+    pub sender: String,
+    pub token_id: String,
+    pub msg: Binary,
+}
+
+impl Cw721ReceiveMsg {
+    /// Builds the `ReceiveNft` call of the receiving contract
+    pub fn into_cosmos_msg(self, contract_addr: impl Into<String>) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: to_json_binary(&ReceiverExecuteMsg::ReceiveNft(self))?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+/// Execute message a contract must accept to receive tokens through `SendNft`
+#[cw_serde]
+pub enum ReceiverExecuteMsg {
+    ReceiveNft(Cw721ReceiveMsg),
+}
@@ -1,12 +1,12 @@
-use cosmwasm_std::{Addr, Deps, Empty, Env, Order, StdResult, Storage};
+use cosmwasm_std::{Addr, Deps, Empty, Env, Order, StdError, StdResult, Storage};
 use cw_ownable::Ownership;
 use cw_storage_plus::Bound;
 
 use crate::msg::ContractInfoResponse;
 use crate::traits::Cw721CollectionConfig;
 use crate::{
-    msg::{NftInfoResponse, NumTokensResponse, OwnerOfResponse, TokensResponse},
-    state::{Cw721Config, CREATOR, MINTER},
+    msg::{BalanceResponse, NftInfoResponse, NumTokensResponse, OwnerOfResponse, TokensResponse},
+    state::{Cw721Config, BALANCE_SNAPSHOTS, CREATOR, MINTER, OWNER_SNAPSHOTS, SNAPSHOTS_ENABLED},
     traits::Cw721State,
 };
 
@@ -74,6 +74,42 @@ pub fn query_owner_of(
     })
 }
 
+fn assert_snapshots_enabled(storage: &dyn Storage) -> StdResult<()> {
+    if !SNAPSHOTS_ENABLED.may_load(storage)?.unwrap_or_default() {
+        return Err(StdError::generic_err("Snapshots are not enabled"));
+    }
+    Ok(())
+}
+
+/// Owner of the token at the start of block `height`
+pub fn query_owner_of_at_height(
+    storage: &dyn Storage,
+    token_id: String,
+    height: u64,
+) -> StdResult<OwnerOfResponse> {
+    assert_snapshots_enabled(storage)?;
+    let owner = OWNER_SNAPSHOTS
+        .may_load_at_height(storage, &token_id, height)?
+        .ok_or_else(|| StdError::not_found(format!("token {token_id} at height {height}")))?;
+    Ok(OwnerOfResponse {
+        owner: owner.to_string(),
+    })
+}
+
+/// Number of tokens held by the owner at the start of block `height`
+pub fn query_balance_at_height(
+    deps: Deps,
+    owner: String,
+    height: u64,
+) -> StdResult<BalanceResponse> {
+    assert_snapshots_enabled(deps.storage)?;
+    let owner = deps.api.addr_validate(&owner)?;
+    let count = BALANCE_SNAPSHOTS
+        .may_load_at_height(deps.storage, &owner, height)?
+        .unwrap_or_default();
+    Ok(BalanceResponse { count })
+}
+
 pub fn query_tokens(
     deps: Deps,
     _env: &Env,
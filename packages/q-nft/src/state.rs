@@ -2,7 +2,7 @@ use crate::traits::{Cw721CollectionConfig, Cw721State};
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, StdResult, Storage, Timestamp};
 use cw_ownable::{OwnershipStore, OWNERSHIP_KEY};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex, SnapshotMap, Strategy};
 
 /// Creator owns this contract and can update collection info!
 /// !!! Important note here: !!!
@@ -12,6 +12,23 @@ pub const CREATOR: OwnershipStore = OwnershipStore::new(OWNERSHIP_KEY);
 /// - minter is stored in the contract storage using cw_ownable::OwnershipStore (same as for OWNERSHIP but with different key)
 pub const MINTER: OwnershipStore = OwnershipStore::new("collection_minter");
 
+/// Whether ownership snapshots are recorded, set at instantiation or enabled on migration
+pub const SNAPSHOTS_ENABLED: Item<bool> = Item::new("snapshots_enabled");
+/// Owner of each token per block height, removed when the token is burned
+pub const OWNER_SNAPSHOTS: SnapshotMap<&str, Addr> = SnapshotMap::new(
+    "owner_snapshots",
+    "owner_snapshots__checkpoints",
+    "owner_snapshots__changelog",
+    Strategy::EveryBlock,
+);
+/// Number of tokens held by each owner per block height
+pub const BALANCE_SNAPSHOTS: SnapshotMap<&Addr, u64> = SnapshotMap::new(
+    "balance_snapshots",
+    "balance_snapshots__checkpoints",
+    "balance_snapshots__changelog",
+    Strategy::EveryBlock,
+);
+
 #[cw_serde]
 pub struct CollectionInfo {
     pub name: String,
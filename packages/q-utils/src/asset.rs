@@ -0,0 +1,259 @@
+//! Uniform handling of native and CW20 tokens identified by a [`Denom`].
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    coins, to_json_binary, BankMsg, CosmosMsg, MessageInfo, QuerierWrapper, StdError, StdResult,
+    Uint128, WasmMsg,
+};
+use cw20::{
+    BalanceResponse, Cw20ExecuteMsg, Cw20QueryMsg, Cw20ReceiveMsg, Denom, TokenInfoResponse,
+};
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum AssetError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+    #[error("Unexpected token, expected {expected}")]
+    UnexpectedToken { expected: String },
+}
+
+/// Human readable representation of a denom, used in event attributes
+pub fn denom_to_string(denom: &Denom) -> String {
+    match denom {
+        Denom::Native(denom) => format!("native:{denom}"),
+        Denom::Cw20(addr) => format!("cw20:{addr}"),
+    }
+}
+
+/// An amount of a native or CW20 token
+#[cw_serde]
+pub struct Asset {
+    pub denom: Denom,
+    pub amount: Uint128,
+}
+
+impl Asset {
+    pub fn new(denom: Denom, amount: impl Into<Uint128>) -> Self {
+        Asset {
+            denom,
+            amount: amount.into(),
+        }
+    }
+
+    /// The native `denom` sent along as funds, which must be the only funds sent
+    pub fn from_funds(denom: &Denom, info: &MessageInfo) -> Result<Self, AssetError> {
+        match denom {
+            Denom::Native(native) => {
+                Ok(Asset::new(denom.clone(), cw_utils::must_pay(info, native)?))
+            }
+            Denom::Cw20(_) => Err(AssetError::UnexpectedToken {
+                expected: denom_to_string(denom),
+            }),
+        }
+    }
+
+    /// The CW20 `denom` sent through the `Send` hook, `info` must be the one of the hook call
+    pub fn from_cw20_receive(
+        denom: &Denom,
+        info: &MessageInfo,
+        msg: &Cw20ReceiveMsg,
+    ) -> Result<Self, AssetError> {
+        match denom {
+            Denom::Cw20(token) if *token == info.sender => {
+                Ok(Asset::new(denom.clone(), msg.amount))
+            }
+            _ => Err(AssetError::UnexpectedToken {
+                expected: denom_to_string(denom),
+            }),
+        }
+    }
+
+    /// Builds the message sending this asset to `recipient`
+    pub fn transfer_msg(&self, recipient: impl Into<String>) -> StdResult<CosmosMsg> {
+        match &self.denom {
+            Denom::Native(denom) => Ok(BankMsg::Send {
+                to_address: recipient.into(),
+                amount: coins(self.amount.u128(), denom),
+            }
+            .into()),
+            Denom::Cw20(token) => Ok(WasmMsg::Execute {
+                contract_addr: token.to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: recipient.into(),
+                    amount: self.amount,
+                })?,
+                funds: vec![],
+            }
+            .into()),
+        }
+    }
+}
+
+/// Balance of `address` in `denom`
+pub fn query_balance(
+    querier: &QuerierWrapper,
+    denom: &Denom,
+    address: impl Into<String>,
+) -> StdResult<Uint128> {
+    match denom {
+        Denom::Native(denom) => Ok(querier.query_balance(address, denom)?.amount),
+        Denom::Cw20(token) => {
+            let response: BalanceResponse = querier.query_wasm_smart(
+                token,
+                &Cw20QueryMsg::Balance {
+                    address: address.into(),
+                },
+            )?;
+            Ok(response.balance)
+        }
+    }
+}
+
+/// Decimal places of a CW20 token, `None` for native denoms which do not expose them
+pub fn query_decimals(querier: &QuerierWrapper, denom: &Denom) -> StdResult<Option<u8>> {
+    match denom {
+        Denom::Native(_) => Ok(None),
+        Denom::Cw20(token) => {
+            let response: TokenInfoResponse =
+                querier.query_wasm_smart(token, &Cw20QueryMsg::TokenInfo {})?;
+            Ok(Some(response.decimals))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::{message_info, MockQuerier};
+    use cosmwasm_std::{
+        from_json, Addr, Binary, Coin, ContractResult, Empty, SystemResult, WasmQuery,
+    };
+
+    fn cw20_querier(token: &str) -> MockQuerier {
+        let mut querier: MockQuerier<Empty> =
+            MockQuerier::new(&[("user", &[Coin::new(7u128, "uqnc")])]);
+        let token = token.to_string();
+        querier.update_wasm(move |query| {
+            let WasmQuery::Smart { contract_addr, msg } = query else {
+                panic!("unexpected query");
+            };
+            assert_eq!(*contract_addr, token);
+            let response = match from_json(msg).unwrap() {
+                Cw20QueryMsg::Balance { address } => {
+                    assert_eq!(address, "user");
+                    to_json_binary(&BalanceResponse {
+                        balance: Uint128::new(42),
+                    })
+                }
+                Cw20QueryMsg::TokenInfo {} => to_json_binary(&TokenInfoResponse {
+                    name: "settlement".to_string(),
+                    symbol: "STL".to_string(),
+                    decimals: 6,
+                    total_supply: Uint128::new(1_000),
+                }),
+                _ => panic!("unexpected query"),
+            };
+            SystemResult::Ok(ContractResult::Ok(response.unwrap()))
+        });
+        querier
+    }
+
+    #[test]
+    fn test_transfer_msg() {
+        let native = Asset::new(Denom::Native("uqnc".to_string()), 10u128);
+        assert_eq!(
+            native.transfer_msg("recipient").unwrap(),
+            BankMsg::Send {
+                to_address: "recipient".to_string(),
+                amount: coins(10, "uqnc"),
+            }
+            .into()
+        );
+
+        let cw20 = Asset::new(Denom::Cw20(Addr::unchecked("token")), 10u128);
+        assert_eq!(
+            cw20.transfer_msg("recipient").unwrap(),
+            WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "recipient".to_string(),
+                    amount: Uint128::new(10),
+                })
+                .unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_received_funds() {
+        let user = Addr::unchecked("user");
+        let native = Denom::Native("uqnc".to_string());
+        let cw20 = Denom::Cw20(Addr::unchecked("token"));
+
+        let asset = Asset::from_funds(&native, &message_info(&user, &coins(5, "uqnc"))).unwrap();
+        assert_eq!(asset, Asset::new(native.clone(), 5u128));
+        assert_eq!(
+            Asset::from_funds(&native, &message_info(&user, &[])),
+            Err(AssetError::Payment(PaymentError::NoFunds {}))
+        );
+        assert_eq!(
+            Asset::from_funds(&native, &message_info(&user, &coins(5, "other"))),
+            Err(AssetError::Payment(PaymentError::MissingDenom(
+                "uqnc".to_string()
+            )))
+        );
+        assert_eq!(
+            Asset::from_funds(&cw20, &message_info(&user, &coins(5, "uqnc"))),
+            Err(AssetError::UnexpectedToken {
+                expected: "cw20:token".to_string()
+            })
+        );
+
+        let msg = Cw20ReceiveMsg {
+            sender: user.to_string(),
+            amount: Uint128::new(8),
+            msg: Binary::default(),
+        };
+        let asset =
+            Asset::from_cw20_receive(&cw20, &message_info(&Addr::unchecked("token"), &[]), &msg)
+                .unwrap();
+        assert_eq!(asset, Asset::new(cw20.clone(), 8u128));
+        assert_eq!(
+            Asset::from_cw20_receive(&cw20, &message_info(&Addr::unchecked("fake"), &[]), &msg),
+            Err(AssetError::UnexpectedToken {
+                expected: "cw20:token".to_string()
+            })
+        );
+        assert_eq!(
+            Asset::from_cw20_receive(&native, &message_info(&Addr::unchecked("token"), &[]), &msg),
+            Err(AssetError::UnexpectedToken {
+                expected: "native:uqnc".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        let querier = cw20_querier("token");
+        let querier = QuerierWrapper::<Empty>::new(&querier);
+        let native = Denom::Native("uqnc".to_string());
+        let cw20 = Denom::Cw20(Addr::unchecked("token"));
+
+        assert_eq!(
+            query_balance(&querier, &native, "user").unwrap(),
+            Uint128::new(7)
+        );
+        assert_eq!(
+            query_balance(&querier, &cw20, "user").unwrap(),
+            Uint128::new(42)
+        );
+        assert_eq!(query_decimals(&querier, &native).unwrap(), None);
+        assert_eq!(query_decimals(&querier, &cw20).unwrap(), Some(6));
+    }
+}
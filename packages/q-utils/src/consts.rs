@@ -1,4 +1,7 @@
 use cosmwasm_std::Uint128;
 
-/// Decimal precision for Native coin.
-pub const DECIMALS: Uint128 = Uint128::new(10 ^ 18);
+/// Decimal places of the Native coin.
+pub const NATIVE_DECIMALS: u32 = 18;
+
+/// Decimal precision for Native coin, i.e. `10^18`.
+pub const DECIMALS: Uint128 = Uint128::new(1_000_000_000_000_000_000);
@@ -0,0 +1,455 @@
+//! Fixed-point math between token amounts with arbitrary decimals and decimals.
+//!
+//! All operations compute the exact result in a wider integer before rounding it
+//! with the given [`Rounding`] mode, so no precision is lost on intermediate steps.
+
+use crate::consts::{DECIMALS, NATIVE_DECIMALS};
+use cosmwasm_std::{Decimal, Decimal256, Uint128, Uint256, Uint512};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum FixedPointError {
+    #[error("Fixed-point overflow")]
+    Overflow {},
+    #[error("Fixed-point division by zero")]
+    DivideByZero {},
+}
+
+/// How results which cannot be represented exactly are rounded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Towards zero
+    Down,
+    /// Away from zero
+    Up,
+    /// To the nearest value, halves are rounded up
+    HalfUp,
+}
+
+/// `10^decimals`
+fn pow10(decimals: u32) -> Result<Uint256, FixedPointError> {
+    Uint256::from(10u8)
+        .checked_pow(decimals)
+        .map_err(|_| FixedPointError::Overflow {})
+}
+
+/// `value * numerator / denominator`
+pub fn mul_div_256(
+    value: Uint256,
+    numerator: Uint256,
+    denominator: Uint256,
+    rounding: Rounding,
+) -> Result<Uint256, FixedPointError> {
+    if denominator.is_zero() {
+        return Err(FixedPointError::DivideByZero {});
+    }
+    let product = value.full_mul(numerator);
+    let denominator = Uint512::from(denominator);
+    let quotient = product / denominator;
+    let remainder = product % denominator;
+
+    let round_up = match rounding {
+        Rounding::Down => false,
+        Rounding::Up => !remainder.is_zero(),
+        Rounding::HalfUp => remainder + remainder >= denominator,
+    };
+    let result = if round_up {
+        quotient + Uint512::one()
+    } else {
+        quotient
+    };
+    Uint256::try_from(result).map_err(|_| FixedPointError::Overflow {})
+}
+
+/// `value * numerator / denominator`
+pub fn mul_div(
+    value: Uint128,
+    numerator: Uint128,
+    denominator: Uint128,
+    rounding: Rounding,
+) -> Result<Uint128, FixedPointError> {
+    let result = mul_div_256(value.into(), numerator.into(), denominator.into(), rounding)?;
+    Uint128::try_from(result).map_err(|_| FixedPointError::Overflow {})
+}
+
+/// `amount * value`
+pub fn mul_decimal(
+    amount: Uint128,
+    value: Decimal,
+    rounding: Rounding,
+) -> Result<Uint128, FixedPointError> {
+    mul_div(amount, value.atomics(), DECIMALS, rounding)
+}
+
+/// `amount * value`
+pub fn mul_decimal256(
+    amount: Uint128,
+    value: Decimal256,
+    rounding: Rounding,
+) -> Result<Uint128, FixedPointError> {
+    let result = mul_div_256(amount.into(), value.atomics(), DECIMALS.into(), rounding)?;
+    Uint128::try_from(result).map_err(|_| FixedPointError::Overflow {})
+}
+
+/// `amount / value`
+pub fn div_decimal(
+    amount: Uint128,
+    value: Decimal,
+    rounding: Rounding,
+) -> Result<Uint128, FixedPointError> {
+    mul_div(amount, DECIMALS, value.atomics(), rounding)
+}
+
+/// `a * b`
+pub fn decimal_mul(a: Decimal, b: Decimal, rounding: Rounding) -> Result<Decimal, FixedPointError> {
+    Ok(Decimal::new(mul_div(
+        a.atomics(),
+        b.atomics(),
+        DECIMALS,
+        rounding,
+    )?))
+}
+
+/// Decimal value of an `amount` with `decimals` decimal places
+pub fn amount_to_decimal(
+    amount: Uint128,
+    decimals: u32,
+    rounding: Rounding,
+) -> Result<Decimal, FixedPointError> {
+    let atomics = mul_div_256(amount.into(), DECIMALS.into(), pow10(decimals)?, rounding)?;
+    Ok(Decimal::new(
+        Uint128::try_from(atomics).map_err(|_| FixedPointError::Overflow {})?,
+    ))
+}
+
+/// Decimal value of an `amount` with `decimals` decimal places
+pub fn amount_to_decimal256(
+    amount: Uint128,
+    decimals: u32,
+    rounding: Rounding,
+) -> Result<Decimal256, FixedPointError> {
+    let atomics = mul_div_256(amount.into(), DECIMALS.into(), pow10(decimals)?, rounding)?;
+    Ok(Decimal256::new(atomics))
+}
+
+/// Amount with `decimals` decimal places of a decimal `value`
+pub fn decimal_to_amount(
+    value: Decimal,
+    decimals: u32,
+    rounding: Rounding,
+) -> Result<Uint128, FixedPointError> {
+    decimal256_to_amount(value.into(), decimals, rounding)
+}
+
+/// Amount with `decimals` decimal places of a decimal `value`
+pub fn decimal256_to_amount(
+    value: Decimal256,
+    decimals: u32,
+    rounding: Rounding,
+) -> Result<Uint128, FixedPointError> {
+    let amount = mul_div_256(value.atomics(), pow10(decimals)?, DECIMALS.into(), rounding)?;
+    Uint128::try_from(amount).map_err(|_| FixedPointError::Overflow {})
+}
+
+/// Converts an `amount` with `from` decimal places to `to` decimal places
+pub fn convert_precision(
+    amount: Uint128,
+    from: u32,
+    to: u32,
+    rounding: Rounding,
+) -> Result<Uint128, FixedPointError> {
+    let amount = mul_div_256(amount.into(), pow10(to)?, pow10(from)?, rounding)?;
+    Uint128::try_from(amount).map_err(|_| FixedPointError::Overflow {})
+}
+
+/// Converts an `amount` of a token with `decimals` decimal places to Native coin precision
+pub fn to_native(
+    amount: Uint128,
+    decimals: u32,
+    rounding: Rounding,
+) -> Result<Uint128, FixedPointError> {
+    convert_precision(amount, decimals, NATIVE_DECIMALS, rounding)
+}
+
+/// Converts an `amount` in Native coin precision to a token with `decimals` decimal places
+pub fn from_native(
+    amount: Uint128,
+    decimals: u32,
+    rounding: Rounding,
+) -> Result<Uint128, FixedPointError> {
+    convert_precision(amount, NATIVE_DECIMALS, decimals, rounding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const ALL: [Rounding; 3] = [Rounding::Down, Rounding::Up, Rounding::HalfUp];
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn test_decimals_const() {
+        assert_eq!(DECIMALS, Uint128::new(10u128.pow(NATIVE_DECIMALS)));
+        assert_eq!(DECIMALS, Decimal::one().atomics());
+    }
+
+    #[test]
+    fn test_mul_div_rounding() {
+        // (value, numerator, denominator, down, up, half up)
+        let cases: [(u128, u128, u128, u128, u128, u128); 8] = [
+            (10, 1, 3, 3, 4, 3),
+            (20, 1, 3, 6, 7, 7),
+            (5, 1, 2, 2, 3, 3),
+            (3, 1, 2, 1, 2, 2),
+            (1, 1, 3, 0, 1, 0),
+            (6, 1, 3, 2, 2, 2),
+            (0, 7, 3, 0, 0, 0),
+            (7, 0, 3, 0, 0, 0),
+        ];
+        for (value, numerator, denominator, down, up, half_up) in cases {
+            for (rounding, expected) in ALL.into_iter().zip([down, up, half_up]) {
+                assert_eq!(
+                    mul_div(
+                        Uint128::new(value),
+                        Uint128::new(numerator),
+                        Uint128::new(denominator),
+                        rounding
+                    )
+                    .unwrap(),
+                    Uint128::new(expected),
+                    "{value} * {numerator} / {denominator} rounded {rounding:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_div_wide_intermediate() {
+        // the product overflows 128 bits, the result does not
+        let result = mul_div(Uint128::MAX, Uint128::MAX, Uint128::MAX, Rounding::Down).unwrap();
+        assert_eq!(result, Uint128::MAX);
+        let result = mul_div_256(Uint256::MAX, Uint256::MAX, Uint256::MAX, Rounding::Up).unwrap();
+        assert_eq!(result, Uint256::MAX);
+    }
+
+    #[test]
+    fn test_mul_div_errors() {
+        for rounding in ALL {
+            assert_eq!(
+                mul_div(Uint128::one(), Uint128::one(), Uint128::zero(), rounding),
+                Err(FixedPointError::DivideByZero {})
+            );
+            assert_eq!(
+                mul_div(Uint128::MAX, Uint128::new(2), Uint128::one(), rounding),
+                Err(FixedPointError::Overflow {})
+            );
+        }
+        // rounding up past the maximum
+        assert_eq!(
+            mul_div_256(
+                Uint256::MAX,
+                Uint256::from(3u8),
+                Uint256::from(2u8),
+                Rounding::Up
+            ),
+            Err(FixedPointError::Overflow {})
+        );
+    }
+
+    #[test]
+    fn test_mul_decimal() {
+        let amount = Uint128::new(1_001);
+        assert_eq!(
+            mul_decimal(amount, dec("0.5"), Rounding::Down).unwrap(),
+            Uint128::new(500)
+        );
+        assert_eq!(
+            mul_decimal(amount, dec("0.5"), Rounding::Up).unwrap(),
+            Uint128::new(501)
+        );
+        assert_eq!(
+            mul_decimal(amount, dec("0.5"), Rounding::HalfUp).unwrap(),
+            Uint128::new(501)
+        );
+        assert_eq!(
+            mul_decimal(amount, dec("0.015"), Rounding::Up).unwrap(),
+            Uint128::new(16)
+        );
+        assert_eq!(
+            mul_decimal(Uint128::MAX, Decimal::one(), Rounding::Down).unwrap(),
+            Uint128::MAX
+        );
+        assert_eq!(
+            mul_decimal(Uint128::MAX, dec("1.000000000000000001"), Rounding::Down),
+            Err(FixedPointError::Overflow {})
+        );
+        assert_eq!(
+            mul_decimal256(amount, Decimal256::from_str("2.5").unwrap(), Rounding::Down).unwrap(),
+            Uint128::new(2_502)
+        );
+        assert_eq!(
+            mul_decimal256(amount, Decimal256::MAX, Rounding::Down),
+            Err(FixedPointError::Overflow {})
+        );
+    }
+
+    #[test]
+    fn test_div_decimal() {
+        let amount = Uint128::new(100);
+        for (rounding, expected) in ALL.into_iter().zip([33, 34, 33]) {
+            assert_eq!(
+                div_decimal(amount, dec("3"), rounding).unwrap(),
+                Uint128::new(expected)
+            );
+        }
+        assert_eq!(
+            div_decimal(amount, dec("0.5"), Rounding::Down).unwrap(),
+            Uint128::new(200)
+        );
+        assert_eq!(
+            div_decimal(amount, Decimal::zero(), Rounding::Down),
+            Err(FixedPointError::DivideByZero {})
+        );
+    }
+
+    #[test]
+    fn test_decimal_mul() {
+        let a = dec("0.000000000000000001");
+        let b = dec("0.5");
+        for (rounding, expected) in
+            ALL.into_iter()
+                .zip(["0", "0.000000000000000001", "0.000000000000000001"])
+        {
+            assert_eq!(decimal_mul(a, b, rounding).unwrap(), dec(expected));
+        }
+        assert_eq!(
+            decimal_mul(dec("2.5"), dec("0.8"), Rounding::Down).unwrap(),
+            dec("2")
+        );
+        assert_eq!(
+            decimal_mul(Decimal::MAX, dec("2"), Rounding::Down),
+            Err(FixedPointError::Overflow {})
+        );
+    }
+
+    #[test]
+    fn test_amount_decimal_conversions() {
+        // 6 decimals
+        assert_eq!(
+            amount_to_decimal(Uint128::new(1_234_567), 6, Rounding::Down).unwrap(),
+            dec("1.234567")
+        );
+        assert_eq!(
+            decimal_to_amount(dec("1.2345675"), 6, Rounding::Down).unwrap(),
+            Uint128::new(1_234_567)
+        );
+        assert_eq!(
+            decimal_to_amount(dec("1.2345675"), 6, Rounding::Up).unwrap(),
+            Uint128::new(1_234_568)
+        );
+        assert_eq!(
+            decimal_to_amount(dec("1.2345675"), 6, Rounding::HalfUp).unwrap(),
+            Uint128::new(1_234_568)
+        );
+        assert_eq!(
+            decimal_to_amount(dec("1.2345674"), 6, Rounding::HalfUp).unwrap(),
+            Uint128::new(1_234_567)
+        );
+
+        // 0 and 18 decimals
+        assert_eq!(
+            amount_to_decimal(Uint128::new(42), 0, Rounding::Down).unwrap(),
+            dec("42")
+        );
+        assert_eq!(
+            amount_to_decimal(DECIMALS, NATIVE_DECIMALS, Rounding::Down).unwrap(),
+            Decimal::one()
+        );
+        assert_eq!(
+            decimal_to_amount(dec("42.9"), 0, Rounding::Down).unwrap(),
+            Uint128::new(42)
+        );
+
+        // more decimals than a decimal can represent
+        let amount = Uint128::new(1_500_001_500_000);
+        for (rounding, expected) in ALL.into_iter().zip([
+            "0.000000000001500001",
+            "0.000000000001500002",
+            "0.000000000001500002",
+        ]) {
+            assert_eq!(
+                amount_to_decimal(amount, 24, rounding).unwrap(),
+                dec(expected)
+            );
+        }
+        assert_eq!(
+            amount_to_decimal(Uint128::new(1), 24, Rounding::Up).unwrap(),
+            dec("0.000000000000000001")
+        );
+        assert_eq!(
+            decimal_to_amount(dec("0.000000000000000001"), 24, Rounding::Down).unwrap(),
+            Uint128::new(1_000_000)
+        );
+
+        // 256-bit decimals
+        assert_eq!(
+            amount_to_decimal256(Uint128::MAX, 0, Rounding::Down).unwrap(),
+            Decimal256::from_atomics(Uint128::MAX, 0).unwrap()
+        );
+        assert_eq!(
+            decimal256_to_amount(Decimal256::from_str("7.25").unwrap(), 2, Rounding::Down).unwrap(),
+            Uint128::new(725)
+        );
+
+        // overflows
+        assert_eq!(
+            amount_to_decimal(Uint128::MAX, 0, Rounding::Down),
+            Err(FixedPointError::Overflow {})
+        );
+        assert_eq!(
+            decimal256_to_amount(Decimal256::MAX, 18, Rounding::Down),
+            Err(FixedPointError::Overflow {})
+        );
+        assert_eq!(
+            amount_to_decimal(Uint128::one(), 78, Rounding::Down),
+            Err(FixedPointError::Overflow {})
+        );
+    }
+
+    #[test]
+    fn test_convert_precision() {
+        let usdc = Uint128::new(1_500_000);
+        let native = Uint128::new(1_500_000_000_000_000_000);
+        for rounding in ALL {
+            assert_eq!(to_native(usdc, 6, rounding).unwrap(), native);
+            assert_eq!(from_native(native, 6, rounding).unwrap(), usdc);
+            assert_eq!(convert_precision(usdc, 6, 6, rounding).unwrap(), usdc);
+        }
+
+        let dust = native + Uint128::new(500_000_000_000);
+        for (rounding, expected) in ALL.into_iter().zip([1_500_000, 1_500_001, 1_500_001]) {
+            assert_eq!(
+                from_native(dust, 6, rounding).unwrap(),
+                Uint128::new(expected)
+            );
+        }
+        let dust = native + Uint128::new(499_999_999_999);
+        assert_eq!(
+            from_native(dust, 6, Rounding::HalfUp).unwrap(),
+            Uint128::new(1_500_000)
+        );
+
+        assert_eq!(
+            to_native(Uint128::MAX, 0, Rounding::Down),
+            Err(FixedPointError::Overflow {})
+        );
+        assert_eq!(
+            convert_precision(Uint128::MAX, 38, 0, Rounding::Down).unwrap(),
+            Uint128::new(3)
+        );
+    }
+}
@@ -1 +1,3 @@
+pub mod asset;
 pub mod consts;
+pub mod fixed_point;